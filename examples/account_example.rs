@@ -41,7 +41,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             if !account_info.assets.is_empty() {
                 println!("  Asset Balances:");
                 for asset in account_info.assets.iter().take(5) {
-                    if asset.wallet_balance != "0" {
+                    if !is_zero(&asset.wallet_balance) {
                         println!("    - {}: {} (Available: {})", 
                             asset.asset, asset.wallet_balance, asset.available_balance);
                     }
@@ -53,7 +53,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             if !account_info.positions.is_empty() {
                 println!("  Open Positions:");
                 for position in account_info.positions.iter() {
-                    if position.position_amt != "0" {
+                    if !is_zero(&position.position_amt) {
                         println!("    - {}: {} (Entry: {}, Unrealized PnL: {})", 
                             position.symbol, position.position_amt, 
                             position.entry_price, position.unrealized_pnl);
@@ -73,7 +73,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Ok(balances) => {
             println!("✓ Account Balances:");
             for balance in balances.iter() {
-                if balance.balance != "0" {
+                if !is_zero(&balance.balance) {
                     println!("  - {}: {} (Available: {})", 
                         balance.asset, balance.balance, balance.available_balance);
                 }
@@ -142,3 +142,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     Ok(())
 }
+
+/// Amounts are `Decimal` with the `decimal` feature and `String` without it
+fn is_zero(amount: &impl ToString) -> bool {
+    amount.to_string().parse::<f64>() == Ok(0.0)
+}
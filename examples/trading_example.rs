@@ -6,7 +6,7 @@
 
 use binance_futures_rs::{
     BinanceClient, Credentials, NewOrderRequest, CancelOrderRequest, QueryOrderRequest,
-    OrderSide, OrderType, TimeInForce, PositionSide
+    OrderSide, LimitTimeInForce, PositionSide
 };
 use std::env;
 
@@ -34,14 +34,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Place a limit buy order
     println!("2. Placing a limit buy order...");
-    let new_order = NewOrderRequest::new(
+    let new_order = NewOrderRequest::limit(
         "BTCUSDT".to_string(),
         OrderSide::Buy,
-        OrderType::Limit,
+        "0.001".parse()?,
+        "30000.0".parse()?,  // Low price to avoid execution
+        LimitTimeInForce::Gtc,
     )
-    .quantity("0.001".to_string())
-    .price("30000.0".to_string())  // Low price to avoid execution
-    .time_in_force(TimeInForce::Gtc)
     .position_side(PositionSide::Both);
 
     match trading.new_order(new_order).await {
@@ -168,7 +168,17 @@ async fn user_data_stream() -> Result<()> {
     let mut message_count = 0;
     const MAX_USER_MESSAGES: usize = 10;
 
-    while let Some(msg) = ws.next().await {
+    loop {
+        // 演示期间，如果5秒内没有消息则退出
+        let msg = match tokio::time::timeout(tokio::time::Duration::from_secs(5), ws.next()).await {
+            Ok(Some(msg)) => msg,
+            Ok(None) => break,
+            Err(_) => {
+                println!("5秒内未收到用户数据，结束演示");
+                break;
+            }
+        };
+
         match msg {
             Ok(tokio_tungstenite::tungstenite::Message::Text(text)) => {
                 match WebSocketClient::parse_message(&text) {
@@ -193,14 +203,6 @@ async fn user_data_stream() -> Result<()> {
             }
             _ => {}
         }
-
-        // 演示期间，如果没有消息，等待5秒后退出
-        tokio::select! {
-            _ = tokio::time::sleep(tokio::time::Duration::from_secs(5)) => {
-                println!("5秒内未收到用户数据，结束演示");
-                break;
-            }
-        }
     }
 
     // 停止用户数据流
@@ -213,13 +215,18 @@ async fn user_data_stream() -> Result<()> {
     Ok(())
 }
 
+/// 数量是否为零（字段为 `Decimal` 或 `String`，取决于 `decimal` feature）
+fn is_zero(amount: &impl ToString) -> bool {
+    amount.to_string().parse::<f64>() == Ok(0.0)
+}
+
 /// 处理用户数据消息
 fn handle_user_message(message: WebSocketMessage) {
     match message {
         WebSocketMessage::AccountUpdate(account) => {
             println!("👤 账户更新 - 原因: {}", account.account_update.event_reason);
             for balance in &account.account_update.balances {
-                if balance.balance_change != "0" {
+                if !is_zero(&balance.balance_change) {
                     println!(
                         "   余额变化: {} = {}, 变化: {}",
                         balance.asset, balance.wallet_balance, balance.balance_change
@@ -227,7 +234,7 @@ fn handle_user_message(message: WebSocketMessage) {
                 }
             }
             for position in &account.account_update.positions {
-                if position.position_amount != "0" {
+                if !is_zero(&position.position_amount) {
                     println!(
                         "   持仓: {} = {}, 未实现盈亏: {}",
                         position.symbol, position.position_amount, position.unrealized_pnl
@@ -241,7 +248,7 @@ fn handle_user_message(message: WebSocketMessage) {
                 "📋 订单更新 - {}: {} {:?} {}, 状态: {:?}",
                 o.symbol, o.side, o.order_type, o.original_quantity, o.order_status
             );
-            if o.last_filled_quantity != "0" {
+            if !is_zero(&o.last_filled_quantity) {
                 println!(
                     "   成交: 价格={}, 数量={}, 手续费={}",
                     o.last_filled_price, o.last_filled_quantity, o.commission_amount
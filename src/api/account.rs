@@ -1,8 +1,20 @@
-use crate::client::HttpClient;
-use crate::error::Result;
+use crate::api::pagination::{time_windows, DAY_MS};
+use crate::client::{HttpClient, RequestParams, RetryPolicy};
+use crate::error::{BinanceError, BinanceErrorCode, Result};
 use crate::types::account::*;
-use std::collections::HashMap;
+use crate::types::common::PositionMode;
+use crate::types::trading::NewOrderRequest;
+use crate::utils::get_timestamp;
+use futures_util::Stream;
 
+/// Span of each `income` and `forceOrders` request made by the history streams
+const HISTORY_SPAN: u64 = 7 * DAY_MS;
+/// Maximum page size of `income`
+const INCOME_LIMIT: u32 = 1000;
+/// Maximum page size of `forceOrders`
+const FORCE_ORDERS_LIMIT: u32 = 100;
+
+#[derive(Clone)]
 pub struct AccountApi {
     client: HttpClient,
 }
@@ -12,6 +24,12 @@ impl AccountApi {
         Self { client }
     }
 
+    /// Override the retry policy for requests made through this instance
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.client = self.client.with_retry_policy(retry_policy);
+        self
+    }
+
     /// Get current account information
     pub async fn account_info(&self) -> Result<AccountInfo> {
         self.client.get_signed("/fapi/v2/account", None).await
@@ -25,7 +43,7 @@ impl AccountApi {
     /// Get position risk
     pub async fn position_risk(&self, symbol: Option<&str>) -> Result<Vec<PositionRisk>> {
         let params = if let Some(symbol) = symbol {
-            let mut params = HashMap::new();
+            let mut params = RequestParams::new();
             params.insert("symbol".to_string(), symbol.to_string());
             Some(params)
         } else {
@@ -44,7 +62,7 @@ impl AccountApi {
         end_time: Option<u64>,
         limit: Option<u32>,
     ) -> Result<Vec<Income>> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         
         if let Some(symbol) = symbol {
             params.insert("symbol".to_string(), symbol.to_string());
@@ -70,10 +88,34 @@ impl AccountApi {
         self.client.get_signed("/fapi/v1/income", params).await
     }
 
+    /// Stream income records from `start_time` to `end_time` (default now), oldest first
+    pub fn income_history_stream(
+        &self,
+        symbol: Option<&str>,
+        income_type: Option<&str>,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> impl Stream<Item = Result<Income>> {
+        let api = self.clone();
+        let symbol = symbol.map(str::to_string);
+        let income_type = income_type.map(str::to_string);
+        let end_time = end_time.unwrap_or_else(get_timestamp);
+
+        time_windows(start_time, end_time, HISTORY_SPAN, INCOME_LIMIT, move |start, end, limit| {
+            let api = api.clone();
+            let symbol = symbol.clone();
+            let income_type = income_type.clone();
+            async move {
+                api.income_history(symbol.as_deref(), income_type.as_deref(), Some(start), Some(end), Some(limit))
+                    .await
+            }
+        })
+    }
+
     /// Get notional and leverage brackets
     pub async fn leverage_bracket(&self, symbol: Option<&str>) -> Result<Vec<LeverageBracket>> {
         let params = if let Some(symbol) = symbol {
-            let mut params = HashMap::new();
+            let mut params = RequestParams::new();
             params.insert("symbol".to_string(), symbol.to_string());
             Some(params)
         } else {
@@ -86,7 +128,7 @@ impl AccountApi {
     /// Get position ADL quantile estimation
     pub async fn adl_quantile(&self, symbol: Option<&str>) -> Result<Vec<AdlQuantile>> {
         let params = if let Some(symbol) = symbol {
-            let mut params = HashMap::new();
+            let mut params = RequestParams::new();
             params.insert("symbol".to_string(), symbol.to_string());
             Some(params)
         } else {
@@ -105,7 +147,7 @@ impl AccountApi {
         end_time: Option<u64>,
         limit: Option<u32>,
     ) -> Result<Vec<ForceOrder>> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         
         if let Some(symbol) = symbol {
             params.insert("symbol".to_string(), symbol.to_string());
@@ -131,6 +173,30 @@ impl AccountApi {
         self.client.get_signed("/fapi/v1/forceOrders", params).await
     }
 
+    /// Stream force orders from `start_time` to `end_time` (default now), oldest first
+    pub fn force_orders_stream(
+        &self,
+        symbol: Option<&str>,
+        auto_close_type: Option<&str>,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> impl Stream<Item = Result<ForceOrder>> {
+        let api = self.clone();
+        let symbol = symbol.map(str::to_string);
+        let auto_close_type = auto_close_type.map(str::to_string);
+        let end_time = end_time.unwrap_or_else(get_timestamp);
+
+        time_windows(start_time, end_time, HISTORY_SPAN, FORCE_ORDERS_LIMIT, move |start, end, limit| {
+            let api = api.clone();
+            let symbol = symbol.clone();
+            let auto_close_type = auto_close_type.clone();
+            async move {
+                api.force_orders(symbol.as_deref(), auto_close_type.as_deref(), Some(start), Some(end), Some(limit))
+                    .await
+            }
+        })
+    }
+
     /// Get trading status
     pub async fn api_trading_status(&self) -> Result<serde_json::Value> {
         self.client.get_signed("/fapi/v1/apiTradingStatus", None).await
@@ -138,7 +204,7 @@ impl AccountApi {
 
     /// Get user commission rate
     pub async fn commission_rate(&self, symbol: &str) -> Result<CommissionRate> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         params.insert("symbol".to_string(), symbol.to_string());
 
         self.client.get_signed("/fapi/v1/commissionRate", Some(params)).await
@@ -146,7 +212,7 @@ impl AccountApi {
 
     /// Change initial leverage
     pub async fn change_leverage(&self, symbol: &str, leverage: i32) -> Result<serde_json::Value> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         params.insert("symbol".to_string(), symbol.to_string());
         params.insert("leverage".to_string(), leverage.to_string());
 
@@ -155,13 +221,59 @@ impl AccountApi {
 
     /// Change margin type
     pub async fn change_margin_type(&self, symbol: &str, margin_type: &str) -> Result<serde_json::Value> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         params.insert("symbol".to_string(), symbol.to_string());
         params.insert("marginType".to_string(), margin_type.to_string());
 
         self.client.post_signed("/fapi/v1/marginType", Some(params)).await
     }
 
+    /// Get the account's position mode (one-way or hedge)
+    pub async fn position_mode(&self) -> Result<PositionMode> {
+        let response: DualSidePosition = self.client.get_signed("/fapi/v1/positionSide/dual", None).await?;
+        Ok(PositionMode::from_dual_side(response.dual_side_position))
+    }
+
+    /// Fetch the account's position mode and check `order` against it.
+    ///
+    /// Returns the mode so callers placing several orders can reuse it with
+    /// `NewOrderRequest::check_position_mode`.
+    pub async fn check_position_mode(&self, order: &NewOrderRequest) -> Result<PositionMode> {
+        let mode = self.position_mode().await?;
+        order.check_position_mode(mode)?;
+        Ok(mode)
+    }
+
+    /// Change the position mode on every symbol.
+    ///
+    /// Succeeds if the account is already in `mode`.
+    pub async fn set_position_mode(&self, mode: PositionMode) -> Result<()> {
+        let params = RequestParams::new().with("dualSidePosition", mode.is_dual_side());
+        let response = self
+            .client
+            .post_signed::<serde_json::Value>("/fapi/v1/positionSide/dual", Some(params))
+            .await;
+        ignore_no_change(response, BinanceErrorCode::NoNeedToChangePositionSide)
+    }
+
+    /// Whether multi-assets margin mode is enabled
+    pub async fn multi_assets_margin(&self) -> Result<bool> {
+        let response: MultiAssetsMargin = self.client.get_signed("/fapi/v1/multiAssetsMargin", None).await?;
+        Ok(response.multi_assets_margin)
+    }
+
+    /// Enable or disable multi-assets margin mode.
+    ///
+    /// Succeeds if the mode is already set.
+    pub async fn set_multi_assets_margin(&self, enabled: bool) -> Result<()> {
+        let params = RequestParams::new().with("multiAssetsMargin", enabled);
+        let response = self
+            .client
+            .post_signed::<serde_json::Value>("/fapi/v1/multiAssetsMargin", Some(params))
+            .await;
+        ignore_no_change(response, BinanceErrorCode::NoNeedToChangeJointMargin)
+    }
+
     /// Modify isolated position margin
     pub async fn position_margin(
         &self,
@@ -170,7 +282,7 @@ impl AccountApi {
         amount: &str,
         margin_type: i32, // 1: Add position margin, 2: Reduce position margin
     ) -> Result<serde_json::Value> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         params.insert("symbol".to_string(), symbol.to_string());
         params.insert("amount".to_string(), amount.to_string());
         params.insert("type".to_string(), margin_type.to_string());
@@ -191,7 +303,7 @@ impl AccountApi {
         end_time: Option<u64>,
         limit: Option<u32>,
     ) -> Result<Vec<serde_json::Value>> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         params.insert("symbol".to_string(), symbol.to_string());
         
         if let Some(margin_type) = margin_type {
@@ -214,17 +326,77 @@ impl AccountApi {
     }
 }
 
+/// Treat "already in this mode" as success
+fn ignore_no_change(response: Result<serde_json::Value>, no_change: BinanceErrorCode) -> Result<()> {
+    match response {
+        Ok(_) => Ok(()),
+        Err(BinanceError::Api { code, .. }) if code == no_change => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::client::{HttpClient, Credentials};
+    use crate::api::test_support::account_api;
+    use crate::client::MemoryTransport;
+    use crate::types::common::{OrderSide, PositionSide};
+    use reqwest::Method;
 
     #[test]
     fn test_account_api_creation() {
-        let credentials = Credentials::new("test_key".to_string(), "test_secret".to_string());
-        let client = HttpClient::new_with_credentials(credentials);
-        let account_api = AccountApi::new(client);
-        // Just test that we can create the API instance
-        assert!(true);
+        let _account_api = account_api(&MemoryTransport::new());
+    }
+
+    #[tokio::test]
+    async fn test_position_mode() {
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::GET, "/fapi/v1/positionSide/dual", 200, r#"{"dualSidePosition":true}"#);
+        transport.push_json(
+            Method::POST,
+            "/fapi/v1/positionSide/dual",
+            400,
+            r#"{"code":-4059,"msg":"No need to change position side."}"#,
+        );
+
+        let account_api = account_api(&transport);
+        assert_eq!(account_api.position_mode().await.unwrap(), PositionMode::Hedge);
+        account_api.set_position_mode(PositionMode::Hedge).await.unwrap();
+        assert_eq!(transport.requests()[1].param("dualSidePosition").as_deref(), Some("true"));
+    }
+
+    #[tokio::test]
+    async fn test_check_position_mode() {
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::GET, "/fapi/v1/positionSide/dual", 200, r#"{"dualSidePosition":true}"#);
+        transport.push_json(Method::GET, "/fapi/v1/positionSide/dual", 200, r#"{"dualSidePosition":true}"#);
+
+        let account_api = account_api(&transport);
+        let market = || NewOrderRequest::market("BTCUSDT".to_string(), OrderSide::Buy, "1".parse().unwrap());
+        let hedged = market().position_side(PositionSide::Long).build();
+        assert_eq!(account_api.check_position_mode(&hedged).await.unwrap(), PositionMode::Hedge);
+
+        let result = account_api.check_position_mode(&market().build()).await;
+        assert!(matches!(result, Err(BinanceError::InvalidParameter(_))));
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_multi_assets_margin() {
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::GET, "/fapi/v1/multiAssetsMargin", 200, r#"{"multiAssetsMargin":false}"#);
+        transport.push_json(
+            Method::POST,
+            "/fapi/v1/multiAssetsMargin",
+            400,
+            r#"{"code":-4168,"msg":"Unable to adjust to Multi-Assets mode with symbols of USDⓈ-M Futures under isolated-margin mode."}"#,
+        );
+
+        let account_api = account_api(&transport);
+        assert!(!account_api.multi_assets_margin().await.unwrap());
+
+        let err = account_api.set_multi_assets_margin(true).await.unwrap_err();
+        assert_eq!(err.code().unwrap().code(), -4168);
+        assert_eq!(transport.requests()[1].param("multiAssetsMargin").as_deref(), Some("true"));
     }
 }
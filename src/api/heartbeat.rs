@@ -0,0 +1,218 @@
+use crate::api::trading::TradingApi;
+use crate::error::{BinanceError, Result};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+/// Number of refresh failures buffered before new ones are dropped
+const ERROR_CHANNEL_CAPACITY: usize = 32;
+
+/// Failed countdown refresh for one symbol
+#[derive(Debug)]
+pub struct HeartbeatError {
+    pub symbol: String,
+    pub error: BinanceError,
+}
+
+/// Dead man's switch: keeps `countdownCancelAll` armed for a set of symbols.
+///
+/// While the process is alive the countdown is refreshed every `interval`.
+/// If the process dies, or the heartbeat stops, Binance cancels all open
+/// orders on those symbols once the countdown runs out.
+#[derive(Debug, Clone)]
+pub struct CountdownHeartbeat {
+    symbols: Vec<String>,
+    countdown: Duration,
+    interval: Duration,
+}
+
+impl CountdownHeartbeat {
+    /// Refresh `countdown` for `symbols`, by default every third of the countdown
+    pub fn new(symbols: Vec<String>, countdown: Duration) -> Self {
+        Self {
+            symbols,
+            countdown,
+            interval: countdown / 3,
+        }
+    }
+
+    /// Set how often the countdown is refreshed; must be shorter than the countdown
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Start refreshing in a background task.
+    ///
+    /// The first refresh is sent immediately. Must be called within a Tokio runtime.
+    pub fn spawn(self, trading: TradingApi) -> Result<HeartbeatHandle> {
+        if self.symbols.is_empty() {
+            return Err(BinanceError::InvalidParameter("No symbols to refresh".to_string()));
+        }
+        if self.interval.is_zero() || self.interval >= self.countdown {
+            return Err(BinanceError::InvalidParameter(format!(
+                "Heartbeat interval {:?} must be non-zero and shorter than countdown {:?}",
+                self.interval, self.countdown
+            )));
+        }
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let (error_tx, error_rx) = mpsc::channel(ERROR_CHANNEL_CAPACITY);
+        let symbols = self.symbols.clone();
+        let task = tokio::spawn(self.run(trading.clone(), shutdown_rx, error_tx));
+
+        Ok(HeartbeatHandle {
+            trading,
+            symbols,
+            shutdown: Some(shutdown_tx),
+            errors: error_rx,
+            task,
+        })
+    }
+
+    async fn run(
+        self,
+        trading: TradingApi,
+        mut shutdown: oneshot::Receiver<()>,
+        errors: mpsc::Sender<HeartbeatError>,
+    ) {
+        let mut ticker = tokio::time::interval(self.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                // Resolves on an explicit stop and when the handle is dropped
+                _ = &mut shutdown => break,
+                _ = ticker.tick() => {
+                    for symbol in &self.symbols {
+                        if let Err(error) = trading.countdown_cancel_all(symbol, self.countdown).await {
+                            // A full channel means nobody is reading; drop rather than block the heartbeat
+                            let _ = errors.try_send(HeartbeatError { symbol: symbol.clone(), error });
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Handle to a running [`CountdownHeartbeat`].
+///
+/// Dropping the handle stops the heartbeat and leaves the countdown armed.
+pub struct HeartbeatHandle {
+    trading: TradingApi,
+    symbols: Vec<String>,
+    shutdown: Option<oneshot::Sender<()>>,
+    errors: mpsc::Receiver<HeartbeatError>,
+    task: JoinHandle<()>,
+}
+
+impl HeartbeatHandle {
+    /// Wait for the next refresh failure; `None` once the heartbeat has stopped
+    pub async fn next_error(&mut self) -> Option<HeartbeatError> {
+        self.errors.recv().await
+    }
+
+    /// Refresh failure reported since the last call, if any
+    pub fn try_next_error(&mut self) -> Option<HeartbeatError> {
+        self.errors.try_recv().ok()
+    }
+
+    /// Whether the background task is still running
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+
+    /// Stop refreshing and wait for the task to exit.
+    ///
+    /// The countdown stays armed, so open orders are cancelled when it runs out.
+    pub async fn stop(mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        let _ = (&mut self.task).await;
+    }
+
+    /// Stop refreshing and cancel the countdown for every symbol, leaving open orders in place
+    pub async fn stop_and_disarm(self) -> Result<()> {
+        let trading = self.trading.clone();
+        let symbols = self.symbols.clone();
+        self.stop().await;
+
+        for symbol in &symbols {
+            trading.countdown_cancel_all(symbol, Duration::ZERO).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::trading_api;
+    use crate::client::MemoryTransport;
+    use reqwest::Method;
+
+    #[tokio::test]
+    async fn test_heartbeat_refreshes_and_disarms() {
+        let transport = MemoryTransport::new();
+        transport.push_json(
+            Method::POST,
+            "/fapi/v1/countdownCancelAll",
+            200,
+            r#"{"symbol":"BTCUSDT","countdownTime":"0"}"#,
+        );
+
+        let handle = CountdownHeartbeat::new(vec!["BTCUSDT".to_string()], Duration::from_secs(60))
+            .interval(Duration::from_millis(10))
+            .spawn(trading_api(&transport))
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(35)).await;
+        assert!(handle.is_running());
+
+        handle.stop_and_disarm().await.unwrap();
+
+        let requests = transport.requests();
+        assert!(requests.len() >= 2);
+        assert!(requests[..requests.len() - 1]
+            .iter()
+            .all(|r| r.param("countdownTime").as_deref() == Some("60000")));
+        assert_eq!(requests.last().unwrap().param("countdownTime").as_deref(), Some("0"));
+
+        // No refreshes after stopping
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(transport.requests().len(), requests.len());
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_reports_errors() {
+        let transport = MemoryTransport::new();
+        transport.push_json(
+            Method::POST,
+            "/fapi/v1/countdownCancelAll",
+            400,
+            r#"{"code":-1121,"msg":"Invalid symbol."}"#,
+        );
+
+        let mut handle = CountdownHeartbeat::new(vec!["NOPE".to_string()], Duration::from_secs(60))
+            .interval(Duration::from_millis(10))
+            .spawn(trading_api(&transport))
+            .unwrap();
+
+        let failure = handle.next_error().await.unwrap();
+        assert_eq!(failure.symbol, "NOPE");
+        assert_eq!(failure.error.code().unwrap().code(), -1121);
+
+        handle.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_rejects_interval_not_below_countdown() {
+        let transport = MemoryTransport::new();
+        let result = CountdownHeartbeat::new(vec!["BTCUSDT".to_string()], Duration::from_secs(10))
+            .interval(Duration::from_secs(10))
+            .spawn(trading_api(&transport));
+        assert!(matches!(result, Err(BinanceError::InvalidParameter(_))));
+    }
+}
@@ -1,9 +1,19 @@
-use crate::client::HttpClient;
+use crate::api::pagination::{id_cursor, time_windows, PageQuery, HOUR_MS};
+use crate::client::{HttpClient, RequestParams, RetryPolicy};
 use crate::error::Result;
 use crate::types::common::KlineInterval;
 use crate::types::market::*;
-use std::collections::HashMap;
+use crate::utils::get_timestamp;
+use futures_util::Stream;
 
+/// Longest `startTime`..`endTime` span accepted by `aggTrades`
+const AGG_TRADES_SPAN: u64 = HOUR_MS;
+/// Maximum page size of `aggTrades`
+const AGG_TRADES_LIMIT: u32 = 1000;
+/// Maximum page size of `klines`
+const KLINES_LIMIT: u32 = 1500;
+
+#[derive(Clone)]
 pub struct MarketApi {
     client: HttpClient,
 }
@@ -13,9 +23,15 @@ impl MarketApi {
         Self { client }
     }
 
+    /// Override the retry policy for requests made through this instance
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.client = self.client.with_retry_policy(retry_policy);
+        self
+    }
+
     /// Get order book depth
     pub async fn depth(&self, symbol: &str, limit: Option<u32>) -> Result<OrderBook> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         params.insert("symbol".to_string(), symbol.to_string());
         
         if let Some(limit) = limit {
@@ -27,7 +43,7 @@ impl MarketApi {
 
     /// Get recent trades list
     pub async fn trades(&self, symbol: &str, limit: Option<u32>) -> Result<Vec<Trade>> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         params.insert("symbol".to_string(), symbol.to_string());
         
         if let Some(limit) = limit {
@@ -39,7 +55,7 @@ impl MarketApi {
 
     /// Get older market trades
     pub async fn historical_trades(&self, symbol: &str, limit: Option<u32>, from_id: Option<u64>) -> Result<Vec<Trade>> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         params.insert("symbol".to_string(), symbol.to_string());
         
         if let Some(limit) = limit {
@@ -62,7 +78,7 @@ impl MarketApi {
         end_time: Option<u64>,
         limit: Option<u32>,
     ) -> Result<Vec<AggTrade>> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         params.insert("symbol".to_string(), symbol.to_string());
         
         if let Some(from_id) = from_id {
@@ -84,6 +100,33 @@ impl MarketApi {
         self.client.get_public("/fapi/v1/aggTrades", Some(params)).await
     }
 
+    /// Stream aggregate trades from `start_time` to `end_time` (default now), oldest first.
+    ///
+    /// Finds the first trade in 1 hour windows, then follows `fromId`.
+    pub fn agg_trades_stream(
+        &self,
+        symbol: &str,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> impl Stream<Item = Result<AggTrade>> {
+        let api = self.clone();
+        let symbol = symbol.to_string();
+        let end_time = end_time.unwrap_or_else(get_timestamp);
+
+        id_cursor(start_time, end_time, AGG_TRADES_SPAN, AGG_TRADES_LIMIT, move |query, limit| {
+            let api = api.clone();
+            let symbol = symbol.clone();
+            async move {
+                match query {
+                    PageQuery::Window { start, end } => {
+                        api.agg_trades(&symbol, None, Some(start), Some(end), Some(limit)).await
+                    }
+                    PageQuery::FromId(from_id) => api.agg_trades(&symbol, Some(from_id), None, None, Some(limit)).await,
+                }
+            }
+        })
+    }
+
     /// Get kline/candlestick data
     pub async fn klines(
         &self,
@@ -93,7 +136,7 @@ impl MarketApi {
         end_time: Option<u64>,
         limit: Option<u32>,
     ) -> Result<Vec<Kline>> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         params.insert("symbol".to_string(), symbol.to_string());
         params.insert("interval".to_string(), interval.to_string());
         
@@ -116,10 +159,29 @@ impl MarketApi {
         Ok(response.into_iter().map(Kline::from).collect())
     }
 
+    /// Stream klines opened from `start_time` to `end_time` (default now), oldest first
+    pub fn klines_stream(
+        &self,
+        symbol: &str,
+        interval: KlineInterval,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> impl Stream<Item = Result<Kline>> {
+        let api = self.clone();
+        let symbol = symbol.to_string();
+        let end_time = end_time.unwrap_or_else(get_timestamp);
+
+        time_windows(start_time, end_time, u64::MAX, KLINES_LIMIT, move |start, end, limit| {
+            let api = api.clone();
+            let symbol = symbol.clone();
+            async move { api.klines(&symbol, interval, Some(start), Some(end), Some(limit)).await }
+        })
+    }
+
     /// Get mark price and funding rate
     pub async fn mark_price(&self, symbol: Option<&str>) -> Result<Vec<MarkPrice>> {
         let params = if let Some(symbol) = symbol {
-            let mut params = HashMap::new();
+            let mut params = RequestParams::new();
             params.insert("symbol".to_string(), symbol.to_string());
             Some(params)
         } else {
@@ -140,10 +202,36 @@ impl MarketApi {
         }
     }
 
+    /// Get funding rate history, oldest first
+    pub async fn funding_rate_history(
+        &self,
+        symbol: &str,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<FundingRate>> {
+        let mut params = RequestParams::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+
+        if let Some(start_time) = start_time {
+            params.insert("startTime".to_string(), start_time.to_string());
+        }
+
+        if let Some(end_time) = end_time {
+            params.insert("endTime".to_string(), end_time.to_string());
+        }
+
+        if let Some(limit) = limit {
+            params.insert("limit".to_string(), limit.to_string());
+        }
+
+        self.client.get_public("/fapi/v1/fundingRate", Some(params)).await
+    }
+
     /// Get 24hr ticker price change statistics
     pub async fn ticker_24hr(&self, symbol: Option<&str>) -> Result<Vec<Ticker24hr>> {
         let params = if let Some(symbol) = symbol {
-            let mut params = HashMap::new();
+            let mut params = RequestParams::new();
             params.insert("symbol".to_string(), symbol.to_string());
             Some(params)
         } else {
@@ -167,7 +255,7 @@ impl MarketApi {
     /// Get symbol price ticker
     pub async fn price_ticker(&self, symbol: Option<&str>) -> Result<Vec<PriceTicker>> {
         let params = if let Some(symbol) = symbol {
-            let mut params = HashMap::new();
+            let mut params = RequestParams::new();
             params.insert("symbol".to_string(), symbol.to_string());
             Some(params)
         } else {
@@ -202,29 +290,54 @@ impl MarketApi {
     pub async fn time(&self) -> Result<serde_json::Value> {
         self.client.get_public("/fapi/v1/time", None).await
     }
+
+    /// Get server time as a typed response
+    pub async fn server_time(&self) -> Result<ServerTime> {
+        self.client.get_public("/fapi/v1/time", None).await
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::client::HttpClient;
+    use crate::client::{HttpClient, MemoryTransport};
+    use reqwest::Method;
 
     #[test]
     fn test_market_api_creation() {
         let client = HttpClient::new();
-        let market_api = MarketApi::new(client);
-        // Just test that we can create the API instance
-        assert!(true);
+        let _market_api = MarketApi::new(client);
     }
 
     #[tokio::test]
     async fn test_ping() {
-        let client = HttpClient::testnet();
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::GET, "/fapi/v1/ping", 200, "{}");
+
+        let client = HttpClient::testnet().with_transport(transport.clone());
         let market_api = MarketApi::new(client);
-        
-        // This test requires network access and may fail in CI
-        // Uncomment to test manually
-        // let result = market_api.ping().await;
-        // assert!(result.is_ok());
+
+        let result = market_api.ping().await;
+        assert!(result.is_ok());
+        assert_eq!(transport.requests()[0].url(), "https://testnet.binancefuture.com/fapi/v1/ping");
+    }
+
+    #[tokio::test]
+    async fn test_depth_query() {
+        let transport = MemoryTransport::new();
+        transport.push_json(
+            Method::GET,
+            "/fapi/v1/depth",
+            200,
+            r#"{"lastUpdateId":1,"E":2,"T":3,"bids":[["100.0","1.5"]],"asks":[["100.1","2.0"]]}"#,
+        );
+
+        let market_api = MarketApi::new(HttpClient::new().with_transport(transport.clone()));
+        let depth = market_api.depth("BTCUSDT", Some(5)).await.unwrap();
+        assert_eq!(depth.bids.len(), 1);
+
+        let request = &transport.requests()[0];
+        assert_eq!(request.param("symbol").as_deref(), Some("BTCUSDT"));
+        assert_eq!(request.param("limit").as_deref(), Some("5"));
     }
 }
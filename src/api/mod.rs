@@ -1,7 +1,14 @@
 pub mod account;
+pub mod heartbeat;
 pub mod market;
+mod pagination;
+pub mod submit;
 pub mod trading;
+#[cfg(test)]
+pub(crate) mod test_support;
 
 pub use account::AccountApi;
+pub use heartbeat::{CountdownHeartbeat, HeartbeatError, HeartbeatHandle};
 pub use market::MarketApi;
+pub use submit::{ClientOrderIdGenerator, OrderSubmitter};
 pub use trading::TradingApi;
@@ -0,0 +1,363 @@
+use crate::error::{BinanceError, Result};
+use crate::types::account::{ForceOrder, Income};
+use crate::types::market::{AggTrade, Kline};
+use crate::types::trading::{Order, UserTrade};
+use futures_util::stream::{self, Stream, TryStreamExt};
+use std::collections::HashSet;
+use std::future::Future;
+use std::hash::Hash;
+
+pub(crate) const HOUR_MS: u64 = 60 * 60 * 1000;
+pub(crate) const DAY_MS: u64 = 24 * HOUR_MS;
+
+/// One page request made by a paginated stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PageQuery {
+    /// Records in `[start, end]`, both in ms
+    Window { start: u64, end: u64 },
+    /// Records with an id of at least `from_id`
+    FromId(u64),
+}
+
+/// Record returned by a historical list endpoint
+pub(crate) trait PageRecord {
+    type Key: Eq + Hash;
+
+    /// Unique key, used to drop records returned by two pages
+    fn key(&self) -> Self::Key;
+
+    /// Time the endpoint filters on, in ms
+    fn time(&self) -> u64;
+}
+
+impl PageRecord for Order {
+    type Key = u64;
+
+    fn key(&self) -> u64 {
+        self.order_id
+    }
+
+    fn time(&self) -> u64 {
+        self.time.unwrap_or(self.update_time)
+    }
+}
+
+impl PageRecord for UserTrade {
+    type Key = u64;
+
+    fn key(&self) -> u64 {
+        self.id
+    }
+
+    fn time(&self) -> u64 {
+        self.time
+    }
+}
+
+impl PageRecord for Income {
+    // One trade can produce several incomes with the same tranId
+    type Key = (u64, String);
+
+    fn key(&self) -> Self::Key {
+        (self.tran_id, self.income_type.clone())
+    }
+
+    fn time(&self) -> u64 {
+        self.time
+    }
+}
+
+impl PageRecord for ForceOrder {
+    type Key = u64;
+
+    fn key(&self) -> u64 {
+        self.order_id
+    }
+
+    fn time(&self) -> u64 {
+        self.time
+    }
+}
+
+impl PageRecord for AggTrade {
+    type Key = u64;
+
+    fn key(&self) -> u64 {
+        self.agg_trade_id
+    }
+
+    fn time(&self) -> u64 {
+        self.timestamp
+    }
+}
+
+impl PageRecord for Kline {
+    type Key = u64;
+
+    fn key(&self) -> u64 {
+        self.open_time
+    }
+
+    fn time(&self) -> u64 {
+        self.open_time
+    }
+}
+
+struct TimeWindows<T: PageRecord, F> {
+    fetch: F,
+    cursor: u64,
+    end: u64,
+    span: u64,
+    limit: u32,
+    /// Keys already returned with time `seen_at`
+    seen: HashSet<T::Key>,
+    seen_at: u64,
+    done: bool,
+}
+
+/// Walk `[start, end]` in windows of at most `span` ms.
+///
+/// `fetch` is called with the window start, end and page size. After a full
+/// page the next window starts at the time of its last record; records at
+/// that time which were already returned are dropped.
+pub(crate) fn time_windows<T, F, Fut>(
+    start: u64,
+    end: u64,
+    span: u64,
+    limit: u32,
+    fetch: F,
+) -> impl Stream<Item = Result<T>>
+where
+    T: PageRecord,
+    F: FnMut(u64, u64, u32) -> Fut,
+    Fut: Future<Output = Result<Vec<T>>>,
+{
+    let state: TimeWindows<T, F> = TimeWindows {
+        fetch,
+        cursor: start,
+        end,
+        span: span.max(1),
+        limit,
+        seen: HashSet::new(),
+        seen_at: 0,
+        done: start > end,
+    };
+
+    stream::try_unfold(state, |mut state| async move {
+        while !state.done {
+            let window_end = state.cursor.saturating_add(state.span - 1).min(state.end);
+            let mut page = (state.fetch)(state.cursor, window_end, state.limit).await?;
+            page.sort_by_key(PageRecord::time);
+
+            let full = page.len() >= state.limit as usize;
+            let last_time = page.last().map(PageRecord::time);
+            let fresh: Vec<T> = page
+                .into_iter()
+                .filter(|record| !(record.time() == state.seen_at && state.seen.contains(&record.key())))
+                .collect();
+
+            match last_time {
+                Some(last_time) if full => {
+                    if fresh.is_empty() {
+                        // A whole page at one millisecond; there is no way to page past it
+                        log::warn!(
+                            "{} records at {}ms, any more at that time are skipped",
+                            state.limit,
+                            last_time
+                        );
+                        state.cursor = last_time.saturating_add(1);
+                        continue;
+                    }
+                    if last_time != state.seen_at {
+                        state.seen.clear();
+                        state.seen_at = last_time;
+                    }
+                    state
+                        .seen
+                        .extend(fresh.iter().filter(|record| record.time() == last_time).map(PageRecord::key));
+                    state.cursor = last_time;
+                }
+                _ => {
+                    state.seen.clear();
+                    if window_end >= state.end {
+                        state.done = true;
+                    } else {
+                        state.cursor = window_end + 1;
+                    }
+                }
+            }
+
+            if !fresh.is_empty() {
+                return Ok::<_, BinanceError>(Some((fresh, state)));
+            }
+        }
+        Ok(None)
+    })
+    .map_ok(|page| stream::iter(page.into_iter().map(Ok)))
+    .try_flatten()
+}
+
+enum IdCursor {
+    /// Looking for the first record, one window at a time
+    Seek(u64),
+    /// Next id to request
+    From(u64),
+    Done,
+}
+
+/// Find the first record in `[start, end]` with windows of at most `span` ms,
+/// then follow `fromId` until a record is past `end`.
+pub(crate) fn id_cursor<T, F, Fut>(
+    start: u64,
+    end: u64,
+    span: u64,
+    limit: u32,
+    fetch: F,
+) -> impl Stream<Item = Result<T>>
+where
+    T: PageRecord<Key = u64>,
+    F: FnMut(PageQuery, u32) -> Fut,
+    Fut: Future<Output = Result<Vec<T>>>,
+{
+    let span = span.max(1);
+
+    stream::try_unfold((IdCursor::Seek(start), fetch), move |(mut cursor, mut fetch)| async move {
+        loop {
+            let (query, last_id) = match cursor {
+                IdCursor::Seek(start) if start <= end => {
+                    let window_end = start.saturating_add(span - 1).min(end);
+                    (PageQuery::Window { start, end: window_end }, None)
+                }
+                IdCursor::From(from_id) => (PageQuery::FromId(from_id), Some(from_id)),
+                _ => return Ok::<_, BinanceError>(None),
+            };
+
+            let mut page = fetch(query, limit).await?;
+            page.sort_by_key(PageRecord::key);
+            let full = page.len() >= limit as usize;
+
+            let mut records: Vec<T> = page
+                .into_iter()
+                .filter(|record| last_id.is_none_or(|from_id| record.key() >= from_id))
+                .collect();
+            let past_end = records.iter().position(|record| record.time() > end);
+            if let Some(index) = past_end {
+                records.truncate(index);
+            }
+
+            cursor = match (query, records.last()) {
+                // A window page is followed by id, whether or not it was full
+                (PageQuery::Window { .. }, Some(last)) => IdCursor::From(last.key() + 1),
+                (PageQuery::FromId(_), Some(last)) if full && past_end.is_none() => IdCursor::From(last.key() + 1),
+                (PageQuery::Window { end: window_end, .. }, None) if window_end < end => IdCursor::Seek(window_end + 1),
+                _ => IdCursor::Done,
+            };
+
+            if !records.is_empty() {
+                return Ok(Some((records, (cursor, fetch))));
+            }
+        }
+    })
+    .map_ok(|page| stream::iter(page.into_iter().map(Ok)))
+    .try_flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Record {
+        id: u64,
+        time: u64,
+    }
+
+    impl PageRecord for Record {
+        type Key = u64;
+
+        fn key(&self) -> u64 {
+            self.id
+        }
+
+        fn time(&self) -> u64 {
+            self.time
+        }
+    }
+
+    fn records(times: &[u64]) -> Vec<Record> {
+        times.iter().enumerate().map(|(i, &time)| Record { id: i as u64 + 1, time }).collect()
+    }
+
+    fn ids(records: &[Record]) -> Vec<u64> {
+        records.iter().map(|r| r.id).collect()
+    }
+
+    #[tokio::test]
+    async fn test_time_windows_respect_span_and_drop_duplicates() {
+        let data = records(&[1, 2, 3, 3, 4, 12, 25, 26]);
+        let windows = Arc::new(Mutex::new(Vec::new()));
+
+        let log = windows.clone();
+        let result: Vec<Record> = time_windows(0, 25, 10, 3, move |start, end, limit| {
+            log.lock().unwrap().push((start, end));
+            let page: Vec<Record> = data
+                .iter()
+                .filter(|r| r.time >= start && r.time <= end)
+                .take(limit as usize)
+                .cloned()
+                .collect();
+            async move { Ok(page) }
+        })
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(ids(&result), vec![1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(*windows.lock().unwrap(), vec![(0, 9), (3, 12), (4, 13), (14, 23), (24, 25)]);
+    }
+
+    #[tokio::test]
+    async fn test_id_cursor_seeks_then_follows_ids_until_end() {
+        let data = records(&[15, 16, 17, 18, 19, 30, 40]);
+        let queries = Arc::new(Mutex::new(Vec::new()));
+
+        let log = queries.clone();
+        let result: Vec<Record> = id_cursor(0, 30, 10, 3, move |query, limit| {
+            log.lock().unwrap().push(query);
+            let page: Vec<Record> = data
+                .iter()
+                .filter(|r| match query {
+                    PageQuery::Window { start, end } => r.time >= start && r.time <= end,
+                    PageQuery::FromId(from_id) => r.id >= from_id,
+                })
+                .take(limit as usize)
+                .cloned()
+                .collect();
+            async move { Ok(page) }
+        })
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(ids(&result), vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(
+            *queries.lock().unwrap(),
+            vec![
+                PageQuery::Window { start: 0, end: 9 },
+                PageQuery::Window { start: 10, end: 19 },
+                PageQuery::FromId(4),
+                PageQuery::FromId(7),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_errors_end_the_stream() {
+        let mut stream = Box::pin(time_windows(0, 100, 10, 3, |_, _, _| async {
+            Err::<Vec<Record>, _>(BinanceError::Timeout)
+        }));
+
+        assert!(matches!(stream.try_next().await, Err(BinanceError::Timeout)));
+    }
+}
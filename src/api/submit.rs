@@ -0,0 +1,265 @@
+use crate::api::trading::TradingApi;
+use crate::error::{BinanceError, BinanceErrorCode, Result};
+use crate::types::trading::{NewOrderRequest, Order, QueryOrderRequest};
+use crate::utils::get_timestamp;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Maximum length of a `newClientOrderId` accepted by Binance
+const MAX_CLIENT_ORDER_ID_LEN: usize = 36;
+/// Maximum prefix length, leaving room for the session and counter
+const MAX_PREFIX_LEN: usize = 14;
+
+/// Generates unique `newClientOrderId`s of the form `{prefix}{session}-{counter}`.
+///
+/// The session is the creation time and the counter a `u64`, both in base 36,
+/// so ids do not repeat across restarts and always fit in 36 characters; the
+/// counter is shared by clones.
+#[derive(Debug, Clone)]
+pub struct ClientOrderIdGenerator {
+    prefix: String,
+    session: String,
+    counter: Arc<AtomicU64>,
+}
+
+impl ClientOrderIdGenerator {
+    /// Create a generator; `prefix` may use `A-Z a-z 0-9 . : / _ -` and at most 14 characters
+    pub fn new(prefix: impl Into<String>) -> Result<Self> {
+        let prefix = prefix.into();
+        let session = to_base36(get_timestamp());
+        // Longest id this generator can produce, at a counter of u64::MAX
+        let longest = prefix.len() + session.len() + 1 + to_base36(u64::MAX).len();
+        if prefix.len() > MAX_PREFIX_LEN
+            || longest > MAX_CLIENT_ORDER_ID_LEN
+            || !prefix.chars().all(is_client_order_id_char)
+        {
+            return Err(BinanceError::InvalidParameter(format!(
+                "Client order id prefix {:?} must be at most {} characters of A-Z a-z 0-9 . : / _ -",
+                prefix, MAX_PREFIX_LEN
+            )));
+        }
+
+        Ok(Self {
+            prefix,
+            session,
+            counter: Arc::new(AtomicU64::new(1)),
+        })
+    }
+
+    /// Next unique client order id
+    pub fn next_id(&self) -> String {
+        let counter = self.counter.fetch_add(1, Ordering::Relaxed);
+        format!("{}{}-{}", self.prefix, self.session, to_base36(counter))
+    }
+
+    /// Whether `client_order_id` was generated by this generator or one with the same prefix and session
+    pub fn owns(&self, client_order_id: &str) -> bool {
+        client_order_id
+            .strip_prefix(&self.prefix)
+            .and_then(|rest| rest.strip_prefix(&self.session))
+            .is_some_and(|rest| rest.starts_with('-'))
+    }
+}
+
+fn is_client_order_id_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '.' | ':' | '/' | '_' | '-')
+}
+
+fn to_base36(mut value: u64) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut digits = Vec::new();
+    loop {
+        digits.push(DIGITS[(value % 36) as usize]);
+        value /= 36;
+        if value == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    String::from_utf8(digits).expect("base 36 digits are ASCII")
+}
+
+/// Idempotent order placement.
+///
+/// Every order gets a unique `newClientOrderId`. When placement fails with an
+/// unknown outcome (timeout, 5xx, -1006/-1007) the order is looked up by that
+/// id, and resubmitted with the same id only if Binance reports it does not
+/// exist, so an order is never placed twice.
+#[derive(Clone)]
+pub struct OrderSubmitter {
+    trading: TradingApi,
+    ids: ClientOrderIdGenerator,
+    max_resubmits: u32,
+    reconcile_delay: Duration,
+}
+
+impl OrderSubmitter {
+    pub fn new(trading: TradingApi, ids: ClientOrderIdGenerator) -> Self {
+        Self {
+            trading,
+            ids,
+            max_resubmits: 1,
+            reconcile_delay: Duration::from_secs(1),
+        }
+    }
+
+    /// How many times an order confirmed missing is resubmitted (default 1)
+    pub fn max_resubmits(mut self, max_resubmits: u32) -> Self {
+        self.max_resubmits = max_resubmits;
+        self
+    }
+
+    /// How long to wait before querying an order with an unknown outcome (default 1 second)
+    pub fn reconcile_delay(mut self, reconcile_delay: Duration) -> Self {
+        self.reconcile_delay = reconcile_delay;
+        self
+    }
+
+    /// Client order id generator used for new orders
+    pub fn ids(&self) -> &ClientOrderIdGenerator {
+        &self.ids
+    }
+
+    /// Place an order, reconciling unknown outcomes.
+    ///
+    /// An id already set on the order is kept. If the outcome stays unknown,
+    /// e.g. because the query fails too, the original error is returned and
+    /// the order must not be resent without checking its id first.
+    pub async fn submit(&self, order: impl Into<NewOrderRequest>) -> Result<Order> {
+        let mut order = order.into();
+        let client_order_id = order
+            .new_client_order_id
+            .get_or_insert_with(|| self.ids.next_id())
+            .clone();
+        let query = QueryOrderRequest::new(order.symbol.clone()).client_order_id(client_order_id);
+        let mut resubmits = 0;
+
+        loop {
+            let error = match self.trading.new_order(order.clone()).await {
+                Err(e) if e.is_execution_unknown() => e,
+                result => return result,
+            };
+
+            tokio::time::sleep(self.reconcile_delay).await;
+            match self.trading.query_order(query.clone()).await {
+                Ok(existing) => return Ok(existing),
+                Err(e) if e.code() == Some(BinanceErrorCode::NoSuchOrder) && resubmits < self.max_resubmits => {
+                    resubmits += 1;
+                }
+                Err(_) => return Err(error),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::{order_json, trading_api};
+    use crate::client::MemoryTransport;
+    use crate::types::common::OrderSide;
+    use crate::types::order_builder::{kind, OrderBuilder};
+    use reqwest::Method;
+    use serde_json::json;
+
+    fn order() -> String {
+        order_json(json!({"orderId": 7, "clientOrderId": "bot-1", "side": "BUY", "type": "MARKET", "origType": "MARKET"}))
+    }
+
+    fn submitter(transport: &MemoryTransport) -> OrderSubmitter {
+        OrderSubmitter::new(trading_api(transport), ClientOrderIdGenerator::new("bot-").unwrap()).reconcile_delay(Duration::ZERO)
+    }
+
+    fn market_order() -> OrderBuilder<kind::Market> {
+        NewOrderRequest::market("BTCUSDT".to_string(), OrderSide::Buy, "0.1".parse().unwrap())
+    }
+
+    fn sent_client_order_ids(transport: &MemoryTransport) -> Vec<String> {
+        transport
+            .requests()
+            .iter()
+            .filter_map(|r| r.param("newClientOrderId").or_else(|| r.param("origClientOrderId")))
+            .collect()
+    }
+
+    #[test]
+    fn test_client_order_id_generator() {
+        let ids = ClientOrderIdGenerator::new("mm.v2:").unwrap();
+        let first = ids.next_id();
+        let second = ids.clone().next_id();
+
+        assert_ne!(first, second);
+        assert!(first.starts_with("mm.v2:"));
+        assert!(first.len() <= MAX_CLIENT_ORDER_ID_LEN);
+        assert!(first.chars().all(is_client_order_id_char));
+        assert!(ids.owns(&second));
+        assert!(!ids.owns("manual-1"));
+
+        assert!(ClientOrderIdGenerator::new("has space").is_err());
+        assert!(ClientOrderIdGenerator::new("a".repeat(15)).is_err());
+
+        // Even the last counter value fits with the longest prefix
+        let ids = ClientOrderIdGenerator::new("a".repeat(MAX_PREFIX_LEN)).unwrap();
+        ids.counter.store(u64::MAX, Ordering::Relaxed);
+        assert_eq!(ids.next_id().len(), MAX_CLIENT_ORDER_ID_LEN);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_outcome_finds_existing_order() {
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::POST, "/fapi/v1/order", 503, "Service Unavailable");
+        transport.push_json(Method::GET, "/fapi/v1/order", 200, &order());
+
+        let order = submitter(&transport).submit(market_order()).await.unwrap();
+        assert_eq!(order.order_id, 7);
+
+        let methods: Vec<_> = transport.requests().iter().map(|r| r.method.clone()).collect();
+        assert_eq!(methods, vec![Method::POST, Method::GET]);
+        let ids = sent_client_order_ids(&transport);
+        assert_eq!(ids[0], ids[1]);
+    }
+
+    #[tokio::test]
+    async fn test_missing_order_is_resubmitted_with_same_id() {
+        let transport = MemoryTransport::new();
+        transport.push_json(
+            Method::POST,
+            "/fapi/v1/order",
+            408,
+            r#"{"code":-1007,"msg":"Timeout waiting for response from backend server."}"#,
+        );
+        transport.push_json(Method::POST, "/fapi/v1/order", 200, &order());
+        transport.push_json(Method::GET, "/fapi/v1/order", 400, r#"{"code":-2013,"msg":"Order does not exist."}"#);
+
+        let order = submitter(&transport).submit(market_order()).await.unwrap();
+        assert_eq!(order.order_id, 7);
+
+        let ids = sent_client_order_ids(&transport);
+        assert_eq!(ids.len(), 3);
+        assert!(ids.iter().all(|id| *id == ids[0]));
+    }
+
+    #[tokio::test]
+    async fn test_rejection_is_not_reconciled() {
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::POST, "/fapi/v1/order", 400, r#"{"code":-2019,"msg":"Margin is insufficient."}"#);
+
+        let order = market_order().client_order_id("manual-1".to_string());
+        let err = submitter(&transport).submit(order).await.unwrap_err();
+        assert!(err.is_order_rejected());
+        assert_eq!(transport.requests().len(), 1);
+        assert_eq!(sent_client_order_ids(&transport), vec!["manual-1".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_unresolved_outcome_returns_original_error() {
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::POST, "/fapi/v1/order", 503, "Service Unavailable");
+        transport.push_json(Method::GET, "/fapi/v1/order", 503, "Service Unavailable");
+
+        let err = submitter(&transport).max_resubmits(0).submit(market_order()).await.unwrap_err();
+        assert!(matches!(err, BinanceError::Server { status: 503, .. }));
+        assert_eq!(transport.requests().iter().filter(|r| r.method == Method::POST).count(), 1);
+    }
+}
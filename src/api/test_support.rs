@@ -0,0 +1,35 @@
+//! Fixtures shared by the API and order-state unit tests
+
+use super::{AccountApi, TradingApi};
+use crate::client::{Credentials, HttpClient, MemoryTransport};
+use serde_json::{json, Value};
+
+pub(crate) fn credentials() -> Credentials {
+    Credentials::new("test_key".to_string(), "test_secret".to_string())
+}
+
+pub(crate) fn trading_api(transport: &MemoryTransport) -> TradingApi {
+    TradingApi::new(HttpClient::new_with_credentials(credentials()).with_transport(transport.clone()))
+}
+
+pub(crate) fn account_api(transport: &MemoryTransport) -> AccountApi {
+    AccountApi::new(HttpClient::new_with_credentials(credentials()).with_transport(transport.clone()))
+}
+
+/// An order as `GET/POST /fapi/v1/order` returns it: a resting BTCUSDT limit sell, with
+/// `overrides` (e.g. `json!({"status": "FILLED", "executedQty": "0.5"})`) replacing fields
+pub(crate) fn order_json(overrides: Value) -> String {
+    let mut order = json!({
+        "orderId": 42, "symbol": "BTCUSDT", "status": "NEW", "clientOrderId": "quote-1",
+        "price": "30000.1", "avgPrice": "0", "origQty": "0.5", "executedQty": "0", "cumQty": "0",
+        "cumQuote": "0", "timeInForce": "GTC", "type": "LIMIT", "reduceOnly": false,
+        "closePosition": false, "side": "SELL", "positionSide": "BOTH", "stopPrice": "0",
+        "workingType": "CONTRACT_PRICE", "priceProtect": false, "origType": "LIMIT",
+        "priceMatch": "NONE", "selfTradePreventionMode": "NONE", "goodTillDate": 0,
+        "updateTime": 1629182711600u64
+    });
+    if let (Some(order), Value::Object(overrides)) = (order.as_object_mut(), overrides) {
+        order.extend(overrides);
+    }
+    order.to_string()
+}
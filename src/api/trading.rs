@@ -1,26 +1,147 @@
-use crate::client::HttpClient;
-use crate::error::Result;
+use crate::api::pagination::{id_cursor, time_windows, PageQuery, DAY_MS};
+use crate::client::{HttpClient, RequestParams, RetryPolicy};
+use crate::error::{ApiErrorResponse, BinanceError, Result};
+#[cfg(feature = "decimal")]
+use crate::risk::RiskChecker;
+use crate::types::Amount;
+use crate::types::common::{OrderSide, OrderStatus, OrderType, PositionSide, TimeInForce, WorkingType};
 use crate::types::trading::*;
-use std::collections::HashMap;
+use crate::utils::get_timestamp;
+use futures_util::Stream;
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
+/// Maximum number of orders per batch place or modify request
+const MAX_BATCH_ORDERS: usize = 5;
+/// Maximum number of ids per batch cancel request
+const MAX_BATCH_CANCEL: usize = 10;
+/// Longest `startTime`..`endTime` span accepted by `allOrders` and `userTrades`
+const ORDER_HISTORY_SPAN: u64 = 7 * DAY_MS;
+/// Maximum page size of `allOrders` and `userTrades`
+const ORDER_HISTORY_LIMIT: u32 = 1000;
+
+/// Order ids handed out to dry-run orders
+static DRY_RUN_ORDER_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Clone)]
 pub struct TradingApi {
     client: HttpClient,
+    #[cfg(feature = "decimal")]
+    risk: Option<RiskChecker>,
 }
 
 impl TradingApi {
     pub fn new(client: HttpClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            #[cfg(feature = "decimal")]
+            risk: None,
+        }
+    }
+
+    /// Override the retry policy for requests made through this instance
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.client = self.client.with_retry_policy(retry_policy);
+        self
+    }
+
+    /// Check every new or modified order against `risk` before it is sent,
+    /// in dry-run mode too. Batches are rejected as a whole.
+    #[cfg(feature = "decimal")]
+    pub fn with_risk_checker(mut self, risk: RiskChecker) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    #[cfg(feature = "decimal")]
+    pub fn risk_checker(&self) -> Option<&RiskChecker> {
+        self.risk.as_ref()
     }
 
     /// Place a new order
-    pub async fn new_order(&self, order: NewOrderRequest) -> Result<Order> {
+    pub async fn new_order(&self, order: impl Into<NewOrderRequest>) -> Result<Order> {
+        let order = order.into();
+        #[cfg(feature = "decimal")]
+        if let Some(risk) = &self.risk {
+            risk.check(&order)?;
+        }
+        let params = self.order_to_params(&order)?;
+        self.send_order_request(Method::POST, "/fapi/v1/order", params, |params| {
+            dry_run_order(params, OrderStatus::New)
+        })
+        .await
+    }
+
+    /// Test a new order: validated and signed by Binance but never sent to the matching engine.
+    ///
+    /// Like every order path, in dry-run mode it is only signed and logged.
+    pub async fn test_order(&self, order: impl Into<NewOrderRequest>) -> Result<()> {
+        let order = order.into();
+        #[cfg(feature = "decimal")]
+        if let Some(risk) = &self.risk {
+            risk.check(&order)?;
+        }
         let params = self.order_to_params(&order)?;
-        self.client.post_signed("/fapi/v1/order", Some(params)).await
+        let _: serde_json::Value = self
+            .send_order_request(Method::POST, "/fapi/v1/order/test", params, |_| serde_json::json!({}))
+            .await?;
+        Ok(())
+    }
+
+    /// Modify a LIMIT order in place, keeping its queue priority where possible
+    pub async fn modify_order(&self, modify: ModifyOrderRequest) -> Result<Order> {
+        #[cfg(feature = "decimal")]
+        if let Some(risk) = &self.risk {
+            risk.check_modify(&modify)?;
+        }
+        let params = self.modify_to_params(&modify)?;
+        self.send_order_request(Method::PUT, "/fapi/v1/order", params, |params| {
+            dry_run_order(params, OrderStatus::New)
+        })
+        .await
+    }
+
+    /// Modify multiple LIMIT orders, split into requests of 5.
+    ///
+    /// Returns one result per order, in the order given.
+    pub async fn modify_batch_orders(&self, orders: Vec<ModifyOrderRequest>) -> Result<Vec<BatchItemResult<Order>>> {
+        #[cfg(feature = "decimal")]
+        if let Some(risk) = &self.risk {
+            risk.check_modify_batch(&orders)?;
+        }
+        let batch_orders: Vec<RequestParams> = orders
+            .iter()
+            .map(|modify| self.modify_to_params(modify))
+            .collect::<Result<Vec<_>>>()?;
+
+        self.send_batch(Method::PUT, &batch_orders).await
+    }
+
+    /// Get the amendment history of an order
+    pub async fn order_amendment_history(
+        &self,
+        query_req: QueryOrderRequest,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<OrderAmendment>> {
+        let params = RequestParams::new()
+            .with("symbol", query_req.symbol)
+            .with_opt("orderId", query_req.order_id)
+            .with_opt("origClientOrderId", query_req.orig_client_order_id)
+            .with_opt("startTime", start_time)
+            .with_opt("endTime", end_time)
+            .with_opt("limit", limit);
+
+        self.client.get_signed("/fapi/v1/orderAmendment", Some(params)).await
     }
 
     /// Cancel an order
     pub async fn cancel_order(&self, cancel_req: CancelOrderRequest) -> Result<Order> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         params.insert("symbol".to_string(), cancel_req.symbol);
         
         if let Some(order_id) = cancel_req.order_id {
@@ -31,20 +152,95 @@ impl TradingApi {
             params.insert("origClientOrderId".to_string(), client_order_id);
         }
 
-        self.client.delete_signed("/fapi/v1/order", Some(params)).await
+        self.send_order_request(Method::DELETE, "/fapi/v1/order", params, |params| {
+            dry_run_order(params, OrderStatus::Canceled)
+        })
+        .await
+    }
+
+    /// Cancel multiple orders on a symbol by order id and/or original client order id.
+    ///
+    /// Lists longer than 10 are split into several requests. Returns one result
+    /// per id, order ids first; a request that fails as a whole fails each of
+    /// its ids and the remaining requests are still sent.
+    pub async fn cancel_batch_orders(
+        &self,
+        symbol: &str,
+        order_ids: &[u64],
+        orig_client_order_ids: &[String],
+    ) -> Result<Vec<BatchItemResult<Order>>> {
+        if order_ids.is_empty() && orig_client_order_ids.is_empty() {
+            return Err(BinanceError::InvalidParameter(
+                "Either orderIdList or origClientOrderIdList must be sent".to_string(),
+            ));
+        }
+
+        let mut results = Vec::with_capacity(order_ids.len() + orig_client_order_ids.len());
+
+        for chunk in order_ids.chunks(MAX_BATCH_CANCEL) {
+            let params = RequestParams::new()
+                .with("symbol", symbol)
+                .with("orderIdList", serde_json::to_string(chunk)?);
+            let response = self
+                .send_order_request(Method::DELETE, "/fapi/v1/batchOrders", params, |_| {
+                    chunk
+                        .iter()
+                        .map(|id| dry_run_canceled(RequestParams::new().with("symbol", symbol).with("orderId", id)))
+                        .collect()
+                })
+                .await;
+            results.extend(chunk_results(response, chunk.len()));
+        }
+
+        for chunk in orig_client_order_ids.chunks(MAX_BATCH_CANCEL) {
+            let params = RequestParams::new()
+                .with("symbol", symbol)
+                .with("origClientOrderIdList", serde_json::to_string(chunk)?);
+            let response = self
+                .send_order_request(Method::DELETE, "/fapi/v1/batchOrders", params, |_| {
+                    chunk
+                        .iter()
+                        .map(|id| {
+                            dry_run_canceled(RequestParams::new().with("symbol", symbol).with("origClientOrderId", id))
+                        })
+                        .collect()
+                })
+                .await;
+            results.extend(chunk_results(response, chunk.len()));
+        }
+
+        Ok(results)
     }
 
     /// Cancel all open orders on a symbol
     pub async fn cancel_all_orders(&self, symbol: &str) -> Result<serde_json::Value> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         params.insert("symbol".to_string(), symbol.to_string());
 
-        self.client.delete_signed("/fapi/v1/allOpenOrders", Some(params)).await
+        self.send_order_request(Method::DELETE, "/fapi/v1/allOpenOrders", params, |_| {
+            serde_json::json!({"code": 200, "msg": "The operation of cancel all open order is done."})
+        })
+        .await
+    }
+
+    /// Cancel all open orders on `symbol` once `countdown` elapses without being refreshed.
+    ///
+    /// Each call resets the timer; a zero countdown cancels it.
+    pub async fn countdown_cancel_all(&self, symbol: &str, countdown: Duration) -> Result<CountdownCancelAll> {
+        let params = RequestParams::new()
+            .with("symbol", symbol)
+            .with("countdownTime", countdown.as_millis());
+
+        self.send_order_request(Method::POST, "/fapi/v1/countdownCancelAll", params, |_| CountdownCancelAll {
+            symbol: symbol.to_string(),
+            countdown_time: countdown.as_millis().to_string(),
+        })
+        .await
     }
 
     /// Query order
     pub async fn query_order(&self, query_req: QueryOrderRequest) -> Result<Order> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         params.insert("symbol".to_string(), query_req.symbol);
         
         if let Some(order_id) = query_req.order_id {
@@ -67,7 +263,7 @@ impl TradingApi {
         end_time: Option<u64>,
         limit: Option<u32>,
     ) -> Result<Vec<Order>> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         params.insert("symbol".to_string(), symbol.to_string());
         
         if let Some(order_id) = order_id {
@@ -89,10 +285,30 @@ impl TradingApi {
         self.client.get_signed("/fapi/v1/allOrders", Some(params)).await
     }
 
+    /// Stream all orders from `start_time` to `end_time` (default now), oldest first.
+    ///
+    /// Walks 7 day windows, fetching more pages while a window is full.
+    pub fn all_orders_stream(
+        &self,
+        symbol: &str,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> impl Stream<Item = Result<Order>> {
+        let api = self.clone();
+        let symbol = symbol.to_string();
+        let end_time = end_time.unwrap_or_else(get_timestamp);
+
+        time_windows(start_time, end_time, ORDER_HISTORY_SPAN, ORDER_HISTORY_LIMIT, move |start, end, limit| {
+            let api = api.clone();
+            let symbol = symbol.clone();
+            async move { api.all_orders(&symbol, None, Some(start), Some(end), Some(limit)).await }
+        })
+    }
+
     /// Get current open orders
     pub async fn open_orders(&self, symbol: Option<&str>) -> Result<Vec<Order>> {
         let params = if let Some(symbol) = symbol {
-            let mut params = HashMap::new();
+            let mut params = RequestParams::new();
             params.insert("symbol".to_string(), symbol.to_string());
             Some(params)
         } else {
@@ -102,19 +318,62 @@ impl TradingApi {
         self.client.get_signed("/fapi/v1/openOrders", params).await
     }
 
-    /// Place multiple orders
-    pub async fn batch_orders(&self, orders: Vec<NewOrderRequest>) -> Result<Vec<Order>> {
-        let batch_orders: Vec<HashMap<String, String>> = orders
+    /// Place multiple orders, split into requests of 5.
+    ///
+    /// Returns one result per order, in the order given, so accepted orders
+    /// can be tracked even when others are rejected.
+    pub async fn batch_orders(&self, orders: Vec<NewOrderRequest>) -> Result<Vec<BatchItemResult<Order>>> {
+        #[cfg(feature = "decimal")]
+        if let Some(risk) = &self.risk {
+            risk.check_batch(&orders)?;
+        }
+        let batch_orders: Vec<RequestParams> = orders
             .into_iter()
             .map(|order| self.order_to_params(&order))
             .collect::<Result<Vec<_>>>()?;
 
-        let batch_orders_json = serde_json::to_string(&batch_orders)?;
-        
-        let mut params = HashMap::new();
-        params.insert("batchOrders".to_string(), batch_orders_json);
+        self.send_batch(Method::POST, &batch_orders).await
+    }
+
+    /// Send `batchOrders` in chunks of [`MAX_BATCH_ORDERS`].
+    ///
+    /// A chunk that fails as a whole is recorded as an error for each of its
+    /// orders and the remaining chunks are still sent, so orders placed by
+    /// other chunks are always reported.
+    async fn send_batch(&self, method: Method, batch_orders: &[RequestParams]) -> Result<Vec<BatchItemResult<Order>>> {
+        let mut results = Vec::with_capacity(batch_orders.len());
+
+        for chunk in batch_orders.chunks(MAX_BATCH_ORDERS) {
+            let params = RequestParams::new().with("batchOrders", serde_json::to_string(chunk)?);
+            let response = self
+                .send_order_request(method.clone(), "/fapi/v1/batchOrders", params, |_| {
+                    chunk
+                        .iter()
+                        .map(|params| BatchItemResult::Ok(dry_run_order(params, OrderStatus::New)))
+                        .collect()
+                })
+                .await;
+            results.extend(chunk_results(response, chunk.len()));
+        }
 
-        self.client.post_signed("/fapi/v1/batchOrders", Some(params)).await
+        Ok(results)
+    }
+
+    /// Send a request that changes orders.
+    ///
+    /// In dry-run mode the request is signed and logged but not sent, and
+    /// `dry_run` builds the response from the request parameters instead.
+    async fn send_order_request<T, F>(&self, method: Method, endpoint: &str, params: RequestParams, dry_run: F) -> Result<T>
+    where
+        T: DeserializeOwned,
+        F: FnOnce(&RequestParams) -> T,
+    {
+        if self.client.is_dry_run() {
+            let response = dry_run(&params);
+            self.client.dry_run_signed(method, endpoint, params)?;
+            return Ok(response);
+        }
+        self.client.send_signed(method, endpoint, Some(params)).await
     }
 
     /// Get account trade list
@@ -126,7 +385,7 @@ impl TradingApi {
         from_id: Option<u64>,
         limit: Option<u32>,
     ) -> Result<Vec<UserTrade>> {
-        let mut params = HashMap::new();
+        let mut params = RequestParams::new();
         params.insert("symbol".to_string(), symbol.to_string());
         
         if let Some(start_time) = start_time {
@@ -148,95 +407,192 @@ impl TradingApi {
         self.client.get_signed("/fapi/v1/userTrades", Some(params)).await
     }
 
-    /// Convert NewOrderRequest to HashMap for API call
-    fn order_to_params(&self, order: &NewOrderRequest) -> Result<HashMap<String, String>> {
-        let mut params = HashMap::new();
-        
-        params.insert("symbol".to_string(), order.symbol.clone());
-        params.insert("side".to_string(), serde_json::to_string(&order.side)?.trim_matches('"').to_string());
-        params.insert("type".to_string(), serde_json::to_string(&order.order_type)?.trim_matches('"').to_string());
-        
-        if let Some(position_side) = &order.position_side {
-            params.insert("positionSide".to_string(), serde_json::to_string(position_side)?.trim_matches('"').to_string());
-        }
-        
-        if let Some(time_in_force) = &order.time_in_force {
-            params.insert("timeInForce".to_string(), serde_json::to_string(time_in_force)?.trim_matches('"').to_string());
-        }
-        
-        if let Some(quantity) = &order.quantity {
-            params.insert("quantity".to_string(), quantity.clone());
-        }
-        
-        if let Some(reduce_only) = order.reduce_only {
-            params.insert("reduceOnly".to_string(), reduce_only.to_string());
-        }
-        
-        if let Some(price) = &order.price {
-            params.insert("price".to_string(), price.clone());
-        }
-        
-        if let Some(client_order_id) = &order.new_client_order_id {
-            params.insert("newClientOrderId".to_string(), client_order_id.clone());
-        }
-        
-        if let Some(stop_price) = &order.stop_price {
-            params.insert("stopPrice".to_string(), stop_price.clone());
-        }
-        
-        if let Some(close_position) = order.close_position {
-            params.insert("closePosition".to_string(), close_position.to_string());
-        }
-        
-        if let Some(activation_price) = &order.activation_price {
-            params.insert("activationPrice".to_string(), activation_price.clone());
-        }
-        
-        if let Some(callback_rate) = &order.callback_rate {
-            params.insert("callbackRate".to_string(), callback_rate.clone());
+    /// Stream account trades from `start_time` to `end_time` (default now), oldest first.
+    ///
+    /// Finds the first trade in 7 day windows, then follows `fromId`.
+    pub fn user_trades_stream(
+        &self,
+        symbol: &str,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> impl Stream<Item = Result<UserTrade>> {
+        let api = self.clone();
+        let symbol = symbol.to_string();
+        let end_time = end_time.unwrap_or_else(get_timestamp);
+
+        id_cursor(start_time, end_time, ORDER_HISTORY_SPAN, ORDER_HISTORY_LIMIT, move |query, limit| {
+            let api = api.clone();
+            let symbol = symbol.clone();
+            async move {
+                match query {
+                    PageQuery::Window { start, end } => {
+                        api.user_trades(&symbol, Some(start), Some(end), None, Some(limit)).await
+                    }
+                    PageQuery::FromId(from_id) => api.user_trades(&symbol, None, None, Some(from_id), Some(limit)).await,
+                }
+            }
+        })
+    }
+
+    /// Convert NewOrderRequest to request parameters for API call
+    fn order_to_params(&self, order: &NewOrderRequest) -> Result<RequestParams> {
+        let mut params = RequestParams::new();
+
+        params.insert("symbol", order.symbol.clone());
+        params.insert("side", order.side.to_string());
+        params.insert("type", order.order_type.to_string());
+        params.insert_opt("positionSide", order.position_side);
+        params.insert_opt("timeInForce", order.time_in_force);
+        params.insert_opt("quantity", order.quantity.as_ref());
+        params.insert_opt("reduceOnly", order.reduce_only);
+        params.insert_opt("price", order.price.as_ref());
+        params.insert_opt("newClientOrderId", order.new_client_order_id.as_ref());
+        params.insert_opt("stopPrice", order.stop_price.as_ref());
+        params.insert_opt("closePosition", order.close_position);
+        params.insert_opt("activationPrice", order.activation_price.as_ref());
+        params.insert_opt("callbackRate", order.callback_rate.as_ref());
+        params.insert_opt("workingType", order.working_type);
+        params.insert_opt("priceProtect", order.price_protect);
+        params.insert_opt("priceMatch", order.price_match);
+        params.insert_opt("selfTradePreventionMode", order.self_trade_prevention_mode);
+        params.insert_opt("goodTillDate", order.good_till_date);
+        params.insert_opt("newOrderRespType", order.new_order_resp_type);
+
+        Ok(params)
+    }
+
+    /// Convert ModifyOrderRequest to request parameters for API call
+    fn modify_to_params(&self, modify: &ModifyOrderRequest) -> Result<RequestParams> {
+        if modify.order_id.is_none() && modify.orig_client_order_id.is_none() {
+            return Err(BinanceError::InvalidParameter(
+                "Either orderId or origClientOrderId must be sent".to_string(),
+            ));
         }
-        
-        if let Some(working_type) = &order.working_type {
-            params.insert("workingType".to_string(), serde_json::to_string(working_type)?.trim_matches('"').to_string());
+        if modify.price.is_some() == modify.price_match.is_some() {
+            return Err(BinanceError::InvalidParameter(
+                "Exactly one of price or priceMatch must be sent".to_string(),
+            ));
         }
-        
-        if let Some(price_protect) = order.price_protect {
-            params.insert("priceProtect".to_string(), price_protect.to_string());
+
+        Ok(RequestParams::new()
+            .with_opt("orderId", modify.order_id)
+            .with_opt("origClientOrderId", modify.orig_client_order_id.as_ref())
+            .with("symbol", &modify.symbol)
+            .with("side", modify.side)
+            .with("quantity", modify.quantity.to_string())
+            .with_opt("price", modify.price.as_ref())
+            .with_opt("priceMatch", modify.price_match))
+    }
+}
+
+/// Synthetic order built from request parameters for dry-run mode.
+///
+/// Fields the request does not carry get exchange defaults or zero.
+fn dry_run_order(params: &RequestParams, status: OrderStatus) -> Order {
+    fn zero() -> Amount {
+        "0".parse().unwrap_or_default()
+    }
+    fn amount(params: &RequestParams, key: &str) -> Amount {
+        params.get(key).and_then(|value| value.parse().ok()).unwrap_or_else(zero)
+    }
+    fn typed<T: DeserializeOwned>(params: &RequestParams, key: &str) -> Option<T> {
+        serde_json::from_value(serde_json::Value::String(params.get(key)?.to_string())).ok()
+    }
+    fn flag(params: &RequestParams, key: &str) -> bool {
+        params.get(key) == Some("true")
+    }
+
+    let order_id = params
+        .get("orderId")
+        .and_then(|id| id.parse().ok())
+        .unwrap_or_else(|| DRY_RUN_ORDER_ID.fetch_add(1, Ordering::Relaxed));
+    let client_order_id = params
+        .get("newClientOrderId")
+        .or_else(|| params.get("origClientOrderId"))
+        .map_or_else(|| format!("dry-run-{}", order_id), str::to_string);
+    let order_type = typed(params, "type").unwrap_or(OrderType::Limit);
+    let now = get_timestamp();
+
+    Order {
+        symbol: params.get("symbol").unwrap_or_default().to_string(),
+        order_id,
+        client_order_id,
+        price: amount(params, "price"),
+        avg_price: zero(),
+        orig_qty: amount(params, "quantity"),
+        executed_qty: zero(),
+        cum_qty: Some(zero()),
+        cum_quote: zero(),
+        status,
+        time_in_force: typed(params, "timeInForce").unwrap_or(TimeInForce::Gtc),
+        order_type,
+        orig_type: order_type,
+        side: typed(params, "side").unwrap_or(OrderSide::Buy),
+        position_side: typed(params, "positionSide").unwrap_or(PositionSide::Both),
+        stop_price: amount(params, "stopPrice"),
+        reduce_only: flag(params, "reduceOnly"),
+        close_position: flag(params, "closePosition"),
+        activation_price: params.get("activationPrice").and_then(|price| price.parse().ok()),
+        callback_rate: params.get("callbackRate").and_then(|rate| rate.parse().ok()),
+        working_type: typed(params, "workingType").unwrap_or(WorkingType::ContractPrice),
+        price_protect: flag(params, "priceProtect"),
+        price_match: typed(params, "priceMatch"),
+        self_trade_prevention_mode: typed(params, "selfTradePreventionMode"),
+        good_till_date: params.get("goodTillDate").and_then(|date| date.parse().ok()),
+        time: Some(now),
+        update_time: now,
+    }
+}
+
+fn dry_run_canceled(params: RequestParams) -> BatchItemResult<Order> {
+    BatchItemResult::Ok(dry_run_order(&params, OrderStatus::Canceled))
+}
+
+/// Turn the failure of a whole batch request into one error per entry: the
+/// shared error where the entries may have been executed, the exchange's
+/// `{code, msg}` for any other API error, the shared error otherwise
+fn chunk_results(response: Result<Vec<BatchItemResult<Order>>>, len: usize) -> Vec<BatchItemResult<Order>> {
+    match response {
+        Ok(results) => results,
+        Err(err) if err.is_execution_unknown() => vec![BatchItemResult::Failed(Arc::new(err)); len],
+        Err(BinanceError::Api { code, msg, .. }) => {
+            vec![BatchItemResult::Err(ApiErrorResponse { code: code.code(), msg }); len]
         }
-        
-        Ok(params)
+        Err(err) => vec![BatchItemResult::Failed(Arc::new(err)); len],
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::client::{HttpClient, Credentials};
-    use crate::types::common::{OrderSide, OrderType, TimeInForce};
+    use crate::api::test_support::{credentials, order_json, trading_api};
+    use crate::client::{HttpClient, MemoryTransport};
+    use crate::types::order_builder::LimitTimeInForce;
+    use crate::types::common::{
+        NewOrderRespType, OrderSide, OrderStatus, PriceMatch, SelfTradePreventionMode, TimeInForce,
+        WorkingType,
+    };
+    use futures_util::TryStreamExt;
+    use serde_json::json;
+    use reqwest::Method;
 
     #[test]
     fn test_trading_api_creation() {
-        let credentials = Credentials::new("test_key".to_string(), "test_secret".to_string());
-        let client = HttpClient::new_with_credentials(credentials);
-        let trading_api = TradingApi::new(client);
-        // Just test that we can create the API instance
-        assert!(true);
+        let _trading_api = trading_api(&MemoryTransport::new());
     }
 
     #[test]
     fn test_order_to_params() {
-        let credentials = Credentials::new("test_key".to_string(), "test_secret".to_string());
-        let client = HttpClient::new_with_credentials(credentials);
-        let trading_api = TradingApi::new(client);
+        let trading_api = trading_api(&MemoryTransport::new());
         
-        let order = NewOrderRequest::new(
+        let order = NewOrderRequest::limit(
             "BTCUSDT".to_string(),
             OrderSide::Buy,
-            OrderType::Limit,
+            "1.0".parse().unwrap(),
+            "50000.0".parse().unwrap(),
+            LimitTimeInForce::Gtc,
         )
-        .quantity("1.0".to_string())
-        .price("50000.0".to_string())
-        .time_in_force(TimeInForce::Gtc);
+        .build();
 
         let params = trading_api.order_to_params(&order).unwrap();
         
@@ -247,4 +603,322 @@ mod tests {
         assert_eq!(params.get("price").unwrap(), "50000.0");
         assert_eq!(params.get("timeInForce").unwrap(), "GTC");
     }
+
+    #[test]
+    fn test_order_to_params_emits_every_field() {
+        let trading_api = trading_api(&MemoryTransport::new());
+
+        let (quantity, stop_price) = ("0.1".parse().unwrap(), "29000".parse().unwrap());
+        let mut order = NewOrderRequest::stop_market("BTCUSDT".to_string(), OrderSide::Sell, quantity, stop_price)
+            .working_type(WorkingType::MarkPrice)
+            .price_protect(true)
+            .self_trade_prevention_mode(SelfTradePreventionMode::ExpireBoth)
+            .new_order_resp_type(NewOrderRespType::Result)
+            .build();
+        // Fields a STOP_MARKET builder does not offer are serialized all the same
+        order.price_match = Some(PriceMatch::Opponent5);
+        order.good_till_date = Some(1_700_000_000_000);
+        order.time_in_force = Some(TimeInForce::Gtd);
+
+        let params = trading_api.order_to_params(&order).unwrap();
+        assert_eq!(params.get("type"), Some("STOP_MARKET"));
+        assert_eq!(params.get("workingType"), Some("MARK_PRICE"));
+        assert_eq!(params.get("priceProtect"), Some("true"));
+        assert_eq!(params.get("priceMatch"), Some("OPPONENT_5"));
+        assert_eq!(params.get("selfTradePreventionMode"), Some("EXPIRE_BOTH"));
+        assert_eq!(params.get("timeInForce"), Some("GTD"));
+        assert_eq!(params.get("goodTillDate"), Some("1700000000000"));
+        assert_eq!(params.get("newOrderRespType"), Some("RESULT"));
+    }
+
+    #[tokio::test]
+    async fn test_modify_order() {
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::PUT, "/fapi/v1/order", 200, &order_json(json!({})));
+
+        let modify = ModifyOrderRequest::new("BTCUSDT".to_string(), OrderSide::Sell, "0.5".parse().unwrap())
+            .order_id(42)
+            .price("30000.1".parse().unwrap());
+        let order = trading_api(&transport).modify_order(modify).await.unwrap();
+        assert_eq!(order.order_id, 42);
+        assert_eq!(order.price.to_string(), "30000.1");
+
+        let request = &transport.requests()[0];
+        assert_eq!(request.method, Method::PUT);
+        assert_eq!(request.param("orderId").as_deref(), Some("42"));
+        assert_eq!(request.param("side").as_deref(), Some("SELL"));
+        assert_eq!(request.param("price").as_deref(), Some("30000.1"));
+        assert!(request.param("priceMatch").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_modify_batch_orders() {
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::PUT, "/fapi/v1/batchOrders", 200, &format!("[{}]", order_json(json!({}))));
+
+        let modify = ModifyOrderRequest::new("BTCUSDT".to_string(), OrderSide::Sell, "0.5".parse().unwrap())
+            .client_order_id("quote-1".to_string())
+            .price_match(PriceMatch::Queue);
+        let orders = trading_api(&transport).modify_batch_orders(vec![modify]).await.unwrap();
+        assert_eq!(orders.len(), 1);
+        assert!(orders[0].is_ok());
+
+        let batch = transport.requests()[0].param("batchOrders").unwrap();
+        assert_eq!(
+            batch,
+            r#"[{"origClientOrderId":"quote-1","symbol":"BTCUSDT","side":"SELL","quantity":"0.5","priceMatch":"QUEUE"}]"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_modify_order_requires_id_and_single_price() {
+        let transport = MemoryTransport::new();
+        let trading_api = trading_api(&transport);
+
+        let no_id = ModifyOrderRequest::new("BTCUSDT".to_string(), OrderSide::Buy, "1".parse().unwrap())
+            .price("100".parse().unwrap());
+        assert!(matches!(trading_api.modify_order(no_id).await, Err(BinanceError::InvalidParameter(_))));
+
+        let both = ModifyOrderRequest::new("BTCUSDT".to_string(), OrderSide::Buy, "1".parse().unwrap())
+            .order_id(1)
+            .price("100".parse().unwrap())
+            .price_match(PriceMatch::Opponent);
+        assert!(matches!(trading_api.modify_order(both).await, Err(BinanceError::InvalidParameter(_))));
+        assert!(transport.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_batch_orders_chunks_and_per_item_results() {
+        let transport = MemoryTransport::new();
+        let rejected = r#"{"code":-2019,"msg":"Margin is insufficient."}"#;
+        transport.push_json(
+            Method::POST,
+            "/fapi/v1/batchOrders",
+            200,
+            &format!("[{0},{1},{0},{0},{0}]", order_json(json!({})), rejected),
+        );
+        transport.push_json(Method::POST, "/fapi/v1/batchOrders", 200, &format!("[{0},{0}]", order_json(json!({}))));
+
+        let orders = (0..7)
+            .map(|_| {
+                let (quantity, price) = ("0.5".parse().unwrap(), "30000.1".parse().unwrap());
+                NewOrderRequest::limit("BTCUSDT".to_string(), OrderSide::Buy, quantity, price, LimitTimeInForce::Gtc).build()
+            })
+            .collect();
+        let results = trading_api(&transport).batch_orders(orders).await.unwrap();
+
+        assert_eq!(results.len(), 7);
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 6);
+        assert!(results[1].clone().into_result().unwrap_err().is_order_rejected());
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        let second: Vec<serde_json::Value> =
+            serde_json::from_str(&requests[1].param("batchOrders").unwrap()).unwrap();
+        assert_eq!(second.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_batch_orders_keeps_results_of_earlier_chunks_when_one_fails() {
+        let transport = MemoryTransport::new();
+        let order = order_json(json!({}));
+        transport.push_json(Method::POST, "/fapi/v1/batchOrders", 200, &format!("[{0},{0},{0},{0},{0}]", order));
+        transport.push_json(Method::POST, "/fapi/v1/batchOrders", 503, "Service Unavailable");
+        transport.push_json(Method::POST, "/fapi/v1/batchOrders", 200, &format!("[{}]", order));
+
+        let orders = (0..11)
+            .map(|_| NewOrderRequest::market("BTCUSDT".to_string(), OrderSide::Buy, "0.5".parse().unwrap()).build())
+            .collect();
+        let results = trading_api(&transport).batch_orders(orders).await.unwrap();
+
+        // The second chunk may or may not have been placed; the others are reported as usual
+        assert_eq!(results.len(), 11);
+        assert!(results[..5].iter().all(|r| r.is_ok()));
+        assert!(results[5..10].iter().all(|r| r.is_err() && r.is_execution_unknown()));
+        assert!(results[10].is_ok());
+        let err = results[5].clone().into_result().unwrap_err();
+        assert!(matches!(err, BinanceError::BatchRequest(_)));
+        assert_eq!(err.status(), Some(503));
+        assert_eq!(transport.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_batch_timeout_leaves_every_entry_unknown() {
+        let transport = MemoryTransport::new();
+        transport.push_json(
+            Method::POST,
+            "/fapi/v1/batchOrders",
+            400,
+            r#"{"code":-1007,"msg":"Timeout waiting for response from backend server."}"#,
+        );
+
+        let orders = (0..3)
+            .map(|_| NewOrderRequest::market("BTCUSDT".to_string(), OrderSide::Buy, "0.5".parse().unwrap()).build())
+            .collect();
+        let results = trading_api(&transport).batch_orders(orders).await.unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|r| r.is_err() && r.is_execution_unknown()));
+        let err = results[0].clone().into_result().unwrap_err();
+        assert_eq!(err.code(), Some(crate::error::BinanceErrorCode::Timeout));
+        assert!(!err.is_order_rejected());
+    }
+
+    #[cfg(feature = "decimal")]
+    #[tokio::test]
+    async fn test_risk_checker_rejects_batch_before_sending() {
+        use crate::state::{OrderManager, PositionTracker};
+        use crate::risk::{RiskLimits, RiskViolation, SymbolLimits};
+
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::POST, "/fapi/v1/batchOrders", 200, &format!("[{}]", order_json(json!({}))));
+        let limits = RiskLimits::new().defaults(SymbolLimits::new().max_order_notional("20000".parse().unwrap()));
+        let risk = RiskChecker::new(limits, OrderManager::new(), PositionTracker::new());
+        let trading_api = trading_api(&transport).with_risk_checker(risk);
+
+        let order = |quantity: &str| {
+            let (quantity, price) = (quantity.parse().unwrap(), "30000".parse().unwrap());
+            NewOrderRequest::limit("BTCUSDT".to_string(), OrderSide::Buy, quantity, price, LimitTimeInForce::Gtc).build()
+        };
+        let err = trading_api.batch_orders(vec![order("0.5"), order("5")]).await.unwrap_err();
+        match err {
+            BinanceError::RiskRejected(RiskViolation::MaxOrderNotional { notional, .. }) => {
+                assert_eq!(notional.to_string(), "150000")
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert!(transport.requests().is_empty());
+
+        assert_eq!(trading_api.batch_orders(vec![order("0.5")]).await.unwrap().len(), 1);
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_batch_orders() {
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::DELETE, "/fapi/v1/batchOrders", 400, r#"{"code":-1102,"msg":"Mandatory parameter missing."}"#);
+
+        let order_ids: Vec<u64> = (1..=12).collect();
+        let results = trading_api(&transport)
+            .cancel_batch_orders("BTCUSDT", &order_ids, &["quote-1".to_string()])
+            .await
+            .unwrap();
+
+        // Every chunk was rejected as a whole, so every id carries the error
+        assert_eq!(results.len(), 13);
+        assert!(results.iter().all(|r| r.is_err()));
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].param("orderIdList").as_deref(), Some("[1,2,3,4,5,6,7,8,9,10]"));
+        assert_eq!(requests[1].param("orderIdList").as_deref(), Some("[11,12]"));
+        assert_eq!(requests[2].param("origClientOrderIdList").as_deref(), Some(r#"["quote-1"]"#));
+
+        assert!(trading_api(&transport).cancel_batch_orders("BTCUSDT", &[], &[]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_countdown_cancel_all() {
+        let transport = MemoryTransport::new();
+        transport.push_json(
+            Method::POST,
+            "/fapi/v1/countdownCancelAll",
+            200,
+            r#"{"symbol":"BTCUSDT","countdownTime":"120000"}"#,
+        );
+
+        let response = trading_api(&transport)
+            .countdown_cancel_all("BTCUSDT", Duration::from_secs(120))
+            .await
+            .unwrap();
+        assert_eq!(response.countdown_time, "120000");
+        assert_eq!(transport.requests()[0].param("countdownTime").as_deref(), Some("120000"));
+    }
+
+    #[tokio::test]
+    async fn test_test_order() {
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::POST, "/fapi/v1/order/test", 200, "{}");
+
+        let order = NewOrderRequest::market("BTCUSDT".to_string(), OrderSide::Buy, "0.1".parse().unwrap());
+        trading_api(&transport).test_order(order).await.unwrap();
+
+        let request = &transport.requests()[0];
+        assert_eq!(request.endpoint, "/fapi/v1/order/test");
+        assert!(request.param("signature").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_never_sends_order_requests() {
+        let transport = MemoryTransport::new();
+        let client = HttpClient::new_with_credentials(credentials())
+            .with_transport(transport.clone())
+            .with_dry_run(true);
+        let trading_api = TradingApi::new(client);
+
+        let order = NewOrderRequest::limit(
+            "BTCUSDT".to_string(),
+            OrderSide::Sell,
+            "0.5".parse().unwrap(),
+            "30000.1".parse().unwrap(),
+            LimitTimeInForce::Gtx,
+        )
+        .client_order_id("quote-1".to_string())
+        .build();
+        trading_api.test_order(order.clone()).await.unwrap();
+        let placed = trading_api.new_order(order.clone()).await.unwrap();
+        assert_eq!(placed.status, OrderStatus::New);
+        assert_eq!(placed.side, OrderSide::Sell);
+        assert_eq!(placed.time_in_force, TimeInForce::Gtx);
+        assert_eq!(placed.client_order_id, "quote-1");
+        assert_eq!(placed.price.to_string(), "30000.1");
+        assert_eq!(placed.orig_qty.to_string(), "0.5");
+
+        let canceled = trading_api
+            .cancel_order(CancelOrderRequest::new("BTCUSDT".to_string()).order_id(placed.order_id))
+            .await
+            .unwrap();
+        assert_eq!(canceled.status, OrderStatus::Canceled);
+        assert_eq!(canceled.order_id, placed.order_id);
+
+        let batch = trading_api.batch_orders(vec![order; 6]).await.unwrap();
+        assert_eq!(batch.len(), 6);
+        assert!(batch.iter().all(|result| result.is_ok()));
+
+        let canceled = trading_api.cancel_batch_orders("BTCUSDT", &[1, 2], &[]).await.unwrap();
+        assert_eq!(canceled.len(), 2);
+
+        // Validation still applies in dry-run mode
+        let invalid = ModifyOrderRequest::new("BTCUSDT".to_string(), OrderSide::Buy, "1".parse().unwrap());
+        assert!(trading_api.modify_order(invalid).await.is_err());
+
+        assert!(transport.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_user_trades_stream_switches_to_from_id() {
+        let transport = MemoryTransport::new();
+        let trade = r#"{
+            "symbol": "BTCUSDT", "id": 500, "orderId": 42, "side": "BUY", "price": "30000", "qty": "0.1",
+            "realizedPnl": "0", "marginAsset": "USDT", "quoteQty": "3000", "commission": "1.2",
+            "commissionAsset": "USDT", "time": 1000, "positionSide": "BOTH", "buyer": true, "maker": false
+        }"#;
+        transport.push_json(Method::GET, "/fapi/v1/userTrades", 200, &format!("[{}]", trade));
+        transport.push_json(Method::GET, "/fapi/v1/userTrades", 200, "[]");
+
+        let trades: Vec<UserTrade> = trading_api(&transport)
+            .user_trades_stream("BTCUSDT", 0, Some(DAY_MS))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].id, 500);
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].param("startTime").as_deref(), Some("0"));
+        assert_eq!(requests[0].param("endTime").as_deref(), Some("86400000"));
+        assert_eq!(requests[1].param("fromId").as_deref(), Some("501"));
+        assert_eq!(requests[1].param("startTime"), None);
+    }
 }
@@ -0,0 +1,627 @@
+//! Event-driven backtesting over historical klines and aggregate trades.
+//!
+//! [`Backtest`] replays stored [`Kline`] and [`AggTrade`] series in time order
+//! through the matching engine behind [`PaperExchange`](crate::PaperExchange),
+//! so orders follow the same rules as in paper trading: LIMIT, MARKET, STOP,
+//! STOP_MARKET, TAKE_PROFIT, TAKE_PROFIT_MARKET and TRAILING_STOP_MARKET, with
+//! fees, cross margin and per-symbol leverage. Funding is settled on open
+//! positions at each historical [`FundingRate`] record.
+//!
+//! A kline is replayed as trades along open, low, high, close for a rising
+//! bar and open, high, low, close for a falling one, passing through any stop
+//! price on the way so that stops fill at their trigger price. Resting limit
+//! orders fill at their own price once the path goes through it. The
+//! [`Strategy`] sees each kline at its close, so a MARKET order it places then
+//! fills at once at the close price, like a LIMIT order that crosses it, while
+//! orders left resting trade from the next bar on.
+
+use crate::error::Result;
+use crate::paper::engine::Engine;
+use crate::paper::{transport, PaperConfig};
+use crate::types::common::{OrderSide, PositionSide};
+use crate::types::decimal::{in_range, Decimal, RoundingStrategy};
+use crate::types::market::{AggTrade, FundingRate, Kline};
+use crate::types::trading::{NewOrderRequest, Order};
+use crate::websocket::types::WebSocketMessage;
+use std::time::Duration;
+
+/// Decimals kept for drawdown fractions
+const RATIO_SCALE: u32 = 8;
+
+/// A market data record being replayed
+#[derive(Debug, Clone, Copy)]
+pub enum MarketEvent<'a> {
+    Kline { symbol: &'a str, kline: &'a Kline },
+    AggTrade { symbol: &'a str, trade: &'a AggTrade },
+}
+
+impl MarketEvent<'_> {
+    pub fn symbol(&self) -> &str {
+        match self {
+            MarketEvent::Kline { symbol, .. } | MarketEvent::AggTrade { symbol, .. } => symbol,
+        }
+    }
+
+    /// Replay time: a kline's close time or a trade's timestamp
+    pub fn time(&self) -> u64 {
+        match self {
+            MarketEvent::Kline { kline, .. } => kline.close_time,
+            MarketEvent::AggTrade { trade, .. } => trade.timestamp,
+        }
+    }
+}
+
+/// Trading logic driven by a [`Backtest`]
+pub trait Strategy {
+    /// Called for each kline and aggregate trade, once the engine has matched it
+    fn on_market(&mut self, ctx: &mut BacktestContext<'_>, event: &MarketEvent<'_>);
+
+    /// Called for each `ORDER_TRADE_UPDATE` and `ACCOUNT_UPDATE` of the simulated account
+    fn on_account(&mut self, _ctx: &mut BacktestContext<'_>, _event: &WebSocketMessage) {}
+}
+
+/// The simulated account as seen from a [`Strategy`]
+pub struct BacktestContext<'a> {
+    engine: &'a mut Engine,
+}
+
+impl BacktestContext<'_> {
+    /// Current replay time in milliseconds
+    pub fn time(&self) -> u64 {
+        self.engine.now()
+    }
+
+    pub fn new_order(&mut self, order: impl Into<NewOrderRequest>) -> Result<Order> {
+        self.engine.place(&order.into()).map(|order| transport::order(&order))
+    }
+
+    pub fn cancel_order(&mut self, symbol: &str, order_id: u64) -> Result<Order> {
+        self.engine.cancel(symbol, Some(order_id), None).map(|order| transport::order(&order))
+    }
+
+    pub fn cancel_all_orders(&mut self, symbol: &str) {
+        self.engine.cancel_all(symbol);
+    }
+
+    pub fn open_orders(&self, symbol: Option<&str>) -> Vec<Order> {
+        self.engine
+            .orders(symbol)
+            .filter(|order| order.status.is_open())
+            .map(transport::order)
+            .collect()
+    }
+
+    pub fn change_leverage(&mut self, symbol: &str, leverage: u32) -> Result<()> {
+        self.engine.set_leverage(symbol, leverage)
+    }
+
+    /// Signed position size; shorts are negative
+    pub fn position_amount(&self, symbol: &str, position_side: PositionSide) -> Decimal {
+        self.engine.position_size(symbol, position_side)
+    }
+
+    /// Last replayed price of `symbol`
+    pub fn last_price(&self, symbol: &str) -> Option<Decimal> {
+        self.engine.mark_price(symbol)
+    }
+
+    /// Wallet balance plus unrealized PnL
+    pub fn equity(&self) -> Result<Decimal> {
+        self.engine.margin()?.margin_balance()
+    }
+
+    pub fn available_balance(&self) -> Result<Decimal> {
+        self.engine.margin()?.available_balance()
+    }
+}
+
+/// One point of the equity curve
+#[derive(Debug, Clone, PartialEq)]
+pub struct EquityPoint {
+    pub time: u64,
+    pub wallet_balance: Decimal,
+    /// Wallet balance plus unrealized PnL
+    pub equity: Decimal,
+    /// Fall from the highest equity so far, as a fraction of it
+    pub drawdown: Decimal,
+}
+
+/// One fill of the simulated account
+#[derive(Debug, Clone, PartialEq)]
+pub struct BacktestTrade {
+    pub time: u64,
+    pub symbol: String,
+    pub order_id: u64,
+    pub trade_id: u64,
+    pub side: OrderSide,
+    pub position_side: PositionSide,
+    pub price: Decimal,
+    pub quantity: Decimal,
+    pub commission: Decimal,
+    pub realized_pnl: Decimal,
+    pub is_maker: bool,
+}
+
+/// Funding settled on the positions of one symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct FundingPayment {
+    pub time: u64,
+    pub symbol: String,
+    pub funding_rate: Decimal,
+    /// Balance change; negative when paid
+    pub amount: Decimal,
+}
+
+/// Outcome of a [`Backtest`] run
+#[derive(Debug, Clone)]
+pub struct BacktestReport {
+    pub initial_balance: Decimal,
+    pub final_balance: Decimal,
+    /// Final wallet balance plus unrealized PnL
+    pub final_equity: Decimal,
+    pub realized_pnl: Decimal,
+    pub total_commission: Decimal,
+    /// Net funding received; negative when paid
+    pub total_funding: Decimal,
+    /// Largest fall from a previous equity high, as a fraction of that high
+    pub max_drawdown: Decimal,
+    /// Largest fall from a previous equity high, in the margin asset
+    pub max_drawdown_amount: Decimal,
+    pub equity_curve: Vec<EquityPoint>,
+    pub trades: Vec<BacktestTrade>,
+    pub funding: Vec<FundingPayment>,
+}
+
+/// Historical data to replay and the account to replay it against
+#[derive(Debug, Clone)]
+pub struct Backtest {
+    config: PaperConfig,
+    klines: Vec<(String, Kline)>,
+    agg_trades: Vec<(String, AggTrade)>,
+    funding_rates: Vec<FundingRate>,
+    sample_interval: Duration,
+}
+
+impl Backtest {
+    pub fn new(config: PaperConfig) -> Self {
+        Self {
+            config,
+            klines: Vec::new(),
+            agg_trades: Vec::new(),
+            funding_rates: Vec::new(),
+            sample_interval: Duration::from_secs(60),
+        }
+    }
+
+    /// Add klines of `symbol`, e.g. from [`MarketApi::klines_stream`](crate::api::MarketApi::klines_stream)
+    pub fn klines(mut self, symbol: &str, klines: impl IntoIterator<Item = Kline>) -> Self {
+        self.klines.extend(klines.into_iter().map(|kline| (symbol.to_string(), kline)));
+        self
+    }
+
+    /// Add aggregate trades of `symbol`, e.g. from [`MarketApi::agg_trades_stream`](crate::api::MarketApi::agg_trades_stream)
+    pub fn agg_trades(mut self, symbol: &str, trades: impl IntoIterator<Item = AggTrade>) -> Self {
+        self.agg_trades.extend(trades.into_iter().map(|trade| (symbol.to_string(), trade)));
+        self
+    }
+
+    /// Add funding rate records, e.g. from [`MarketApi::funding_rate_history`](crate::api::MarketApi::funding_rate_history)
+    pub fn funding_rates(mut self, funding_rates: impl IntoIterator<Item = FundingRate>) -> Self {
+        self.funding_rates.extend(funding_rates);
+        self
+    }
+
+    /// Least time between two points of the equity curve (default 1 minute).
+    /// Drawdown is tracked on every event regardless.
+    pub fn sample_interval(mut self, sample_interval: Duration) -> Self {
+        self.sample_interval = sample_interval;
+        self
+    }
+
+    /// Replay all data through `strategy`.
+    ///
+    /// Fails if the account's balance, PnL, fee or funding totals overflow.
+    pub fn run<S: Strategy + ?Sized>(&self, strategy: &mut S) -> Result<BacktestReport> {
+        let mut steps: Vec<Step<'_>> = self
+            .klines
+            .iter()
+            .map(|(symbol, kline)| Step::Market(MarketEvent::Kline { symbol, kline }))
+            .chain(
+                self.agg_trades
+                    .iter()
+                    .map(|(symbol, trade)| Step::Market(MarketEvent::AggTrade { symbol, trade })),
+            )
+            .chain(self.funding_rates.iter().map(Step::Funding))
+            .collect();
+        // Stable, so ties keep klines before trades before funding
+        steps.sort_by_key(Step::time);
+
+        let mut engine = Engine::new(&self.config);
+        let mut recorder = Recorder::new(engine.margin()?.wallet_balance, self.sample_interval.as_millis() as u64);
+
+        for step in &steps {
+            engine.set_time(step.time());
+            match step {
+                Step::Funding(record) => {
+                    let amount = engine.settle_funding(&record.symbol, record.funding_rate, record.mark_price)?;
+                    if !amount.is_zero() {
+                        recorder.funding(record, amount)?;
+                    }
+                    dispatch(&mut engine, strategy, &mut recorder)?;
+                }
+                Step::Market(event) => {
+                    match event {
+                        MarketEvent::Kline { symbol, kline } => replay_kline(&mut engine, symbol, kline)?,
+                        MarketEvent::AggTrade { symbol, trade } => engine.on_trade(symbol, &trade.price, &trade.quantity)?,
+                    }
+                    dispatch(&mut engine, strategy, &mut recorder)?;
+                    strategy.on_market(&mut BacktestContext { engine: &mut engine }, event);
+                    dispatch(&mut engine, strategy, &mut recorder)?;
+                }
+            }
+            recorder.observe(step.time(), &engine)?;
+        }
+        recorder.finish(&engine)
+    }
+}
+
+/// An entry of the replay timeline
+enum Step<'a> {
+    Market(MarketEvent<'a>),
+    Funding(&'a FundingRate),
+}
+
+impl Step<'_> {
+    fn time(&self) -> u64 {
+        match self {
+            Step::Market(event) => event.time(),
+            Step::Funding(record) => record.funding_time,
+        }
+    }
+}
+
+/// Trade a kline's path through the engine, stopping at trigger prices it crosses
+fn replay_kline(engine: &mut Engine, symbol: &str, kline: &Kline) -> Result<()> {
+    let [open, high, low, close] = [kline.open, kline.high, kline.low, kline.close];
+    let volume = kline.volume;
+    let path = match close >= open {
+        true => [open, low, high, close],
+        false => [open, high, low, close],
+    };
+
+    engine.trade_at(symbol, open, volume)?;
+    for leg in path.windows(2) {
+        let (from, to) = (leg[0], leg[1]);
+        let mut stops: Vec<Decimal> = engine
+            .trigger_prices(symbol)?
+            .into_iter()
+            .filter(|price| (from.min(to) < *price) && (*price < from.max(to)))
+            .collect();
+        stops.sort();
+        if to < from {
+            stops.reverse();
+        }
+        stops.dedup();
+        for price in stops {
+            engine.trade_at(symbol, price, volume)?;
+        }
+        engine.trade_at(symbol, to, volume)?;
+    }
+    Ok(())
+}
+
+/// Hand engine events to the recorder and the strategy until none are left
+fn dispatch<S: Strategy + ?Sized>(engine: &mut Engine, strategy: &mut S, recorder: &mut Recorder) -> Result<()> {
+    loop {
+        let events = engine.drain_events();
+        if events.is_empty() {
+            return Ok(());
+        }
+        for event in &events {
+            recorder.record(event)?;
+            strategy.on_account(&mut BacktestContext { engine: &mut *engine }, event);
+        }
+    }
+}
+
+/// Builds the report while the replay runs
+struct Recorder {
+    initial_balance: Decimal,
+    sample_interval: u64,
+    last_sample: Option<u64>,
+    peak: Decimal,
+    max_drawdown: Decimal,
+    max_drawdown_amount: Decimal,
+    realized_pnl: Decimal,
+    commission: Decimal,
+    total_funding: Decimal,
+    equity_curve: Vec<EquityPoint>,
+    trades: Vec<BacktestTrade>,
+    funding: Vec<FundingPayment>,
+}
+
+impl Recorder {
+    fn new(initial_balance: Decimal, sample_interval: u64) -> Self {
+        Self {
+            initial_balance,
+            sample_interval,
+            last_sample: None,
+            peak: initial_balance,
+            max_drawdown: Decimal::ZERO,
+            max_drawdown_amount: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            commission: Decimal::ZERO,
+            total_funding: Decimal::ZERO,
+            equity_curve: Vec::new(),
+            trades: Vec::new(),
+            funding: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, event: &WebSocketMessage) -> Result<()> {
+        let WebSocketMessage::OrderUpdate(update) = event else {
+            return Ok(());
+        };
+        let order = &update.order;
+        if order.execution_type != "TRADE" {
+            return Ok(());
+        }
+        let realized_pnl = in_range(self.realized_pnl.checked_add(order.realized_profit))?;
+        let commission = in_range(self.commission.checked_add(order.commission_amount))?;
+        self.realized_pnl = realized_pnl;
+        self.commission = commission;
+        self.trades.push(BacktestTrade {
+            time: order.order_trade_time,
+            symbol: order.symbol.clone(),
+            order_id: order.order_id,
+            trade_id: order.trade_id,
+            side: order.side,
+            position_side: order.position_side,
+            price: order.last_filled_price,
+            quantity: order.last_filled_quantity,
+            commission: order.commission_amount,
+            realized_pnl: order.realized_profit,
+            is_maker: order.is_maker,
+        });
+        Ok(())
+    }
+
+    fn funding(&mut self, record: &FundingRate, amount: Decimal) -> Result<()> {
+        self.total_funding = in_range(self.total_funding.checked_add(amount))?;
+        self.funding.push(FundingPayment {
+            time: record.funding_time,
+            symbol: record.symbol.clone(),
+            funding_rate: record.funding_rate,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Track drawdown, and add a curve point once the sample interval has passed
+    fn observe(&mut self, time: u64, engine: &Engine) -> Result<()> {
+        let margin = engine.margin()?;
+        let equity = margin.margin_balance()?;
+        self.peak = self.peak.max(equity);
+        let drawdown_amount = in_range(self.peak.checked_sub(equity))?;
+        let drawdown = match self.peak.is_zero() || self.peak.is_negative() {
+            true => Decimal::ZERO,
+            false => in_range(drawdown_amount.checked_div(self.peak))?
+                .round_dp_with_strategy(RATIO_SCALE, RoundingStrategy::MidpointAwayFromZero),
+        };
+        self.max_drawdown = self.max_drawdown.max(drawdown);
+        self.max_drawdown_amount = self.max_drawdown_amount.max(drawdown_amount);
+
+        if self.last_sample.is_some_and(|last| time < last.saturating_add(self.sample_interval)) {
+            return Ok(());
+        }
+        self.last_sample = Some(time);
+        self.equity_curve.push(EquityPoint {
+            time,
+            wallet_balance: margin.wallet_balance,
+            equity,
+            drawdown: drawdown.normalize(),
+        });
+        Ok(())
+    }
+
+    fn finish(mut self, engine: &Engine) -> Result<BacktestReport> {
+        // The curve always ends on the final state
+        let now = engine.now();
+        if self.last_sample.is_some_and(|last| last != now) {
+            self.last_sample = None;
+            self.observe(now, engine)?;
+        }
+        let margin = engine.margin()?;
+        Ok(BacktestReport {
+            initial_balance: self.initial_balance,
+            final_balance: margin.wallet_balance,
+            final_equity: margin.margin_balance()?,
+            realized_pnl: self.realized_pnl,
+            total_commission: self.commission,
+            total_funding: self.total_funding,
+            max_drawdown: self.max_drawdown.normalize(),
+            max_drawdown_amount: self.max_drawdown_amount,
+            equity_curve: self.equity_curve,
+            trades: self.trades,
+            funding: self.funding,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::common::{OrderStatus, OrderType};
+    use crate::types::order_builder::{CallbackRate, LimitTimeInForce};
+
+    const HOUR: u64 = 3_600_000;
+
+    fn d(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    fn kline(hour: u64, open: &str, high: &str, low: &str, close: &str) -> Kline {
+        Kline {
+            open_time: hour * HOUR,
+            open: d(open),
+            high: d(high),
+            low: d(low),
+            close: d(close),
+            volume: d("100"),
+            close_time: (hour + 1) * HOUR - 1,
+            quote_asset_volume: d("0"),
+            number_of_trades: 0,
+            taker_buy_base_asset_volume: d("0"),
+            taker_buy_quote_asset_volume: d("0"),
+            ignore: "0".to_string(),
+        }
+    }
+
+    fn agg_trade(timestamp: u64, price: &str) -> AggTrade {
+        AggTrade {
+            agg_trade_id: timestamp,
+            price: d(price),
+            quantity: d("5"),
+            first_trade_id: timestamp,
+            last_trade_id: timestamp,
+            timestamp,
+            is_buyer_maker: false,
+        }
+    }
+
+    fn symbol() -> String {
+        "BTCUSDT".to_string()
+    }
+
+    /// Buys on the first bar with a stop-loss and a take-profit attached
+    #[derive(Default)]
+    struct Bracket {
+        take_profit: Option<u64>,
+    }
+
+    impl Strategy for Bracket {
+        fn on_market(&mut self, ctx: &mut BacktestContext<'_>, _event: &MarketEvent<'_>) {
+            if self.take_profit.is_some() {
+                return;
+            }
+            ctx.new_order(NewOrderRequest::market(symbol(), OrderSide::Buy, d("1"))).unwrap();
+            ctx.new_order(NewOrderRequest::stop_market(symbol(), OrderSide::Sell, d("1"), d("95")).reduce_only(true))
+                .unwrap();
+            let take_profit =
+                NewOrderRequest::take_profit(symbol(), OrderSide::Sell, d("1"), d("110"), d("110")).reduce_only(true);
+            self.take_profit = Some(ctx.new_order(take_profit).unwrap().order_id);
+        }
+    }
+
+    #[test]
+    fn test_klines_trigger_stops_at_their_price() {
+        let klines = vec![
+            kline(0, "99", "101", "98", "100"),
+            // Falls through the stop at 95 on the way from 104 to 90
+            kline(1, "100", "104", "90", "92"),
+            // Reaches the take-profit with nothing left to close
+            kline(2, "92", "111", "91", "108"),
+        ];
+        let mut strategy = Bracket::default();
+        let report = Backtest::new(PaperConfig::default()).klines("BTCUSDT", klines).run(&mut strategy).unwrap();
+
+        let fills: Vec<(String, String, String)> = report
+            .trades
+            .iter()
+            .map(|t| (t.price.to_string(), t.commission.to_string(), t.realized_pnl.to_string()))
+            .collect();
+        assert_eq!(
+            fills,
+            [
+                ("100".to_string(), "0.05".to_string(), "0".to_string()),
+                ("95".to_string(), "0.0475".to_string(), "-5".to_string()),
+            ]
+        );
+        assert_eq!(report.trades[1].time, 2 * HOUR - 1);
+
+        // 10000 - 5 - 0.0975 in fees, all lost by the end of the second bar
+        assert_eq!(report.final_balance.to_string(), "9994.9025");
+        assert_eq!(report.max_drawdown_amount.to_string(), "5.0975");
+        assert_eq!(report.max_drawdown.to_string(), "0.00050975");
+        let equity: Vec<String> = report.equity_curve.iter().map(|p| p.equity.to_string()).collect();
+        assert_eq!(equity, ["9999.95", "9994.9025", "9994.9025"]);
+        assert!(report.funding.is_empty());
+    }
+
+    /// Bids below the market, then trails the filled position with a 2% stop
+    struct Trail;
+
+    impl Strategy for Trail {
+        fn on_market(&mut self, ctx: &mut BacktestContext<'_>, _event: &MarketEvent<'_>) {
+            if ctx.time() == 1_000 {
+                let bid = NewOrderRequest::limit(symbol(), OrderSide::Buy, d("1"), d("99"), LimitTimeInForce::Gtc);
+                assert_eq!(ctx.new_order(bid).unwrap().status, OrderStatus::New);
+            }
+        }
+
+        fn on_account(&mut self, ctx: &mut BacktestContext<'_>, event: &WebSocketMessage) {
+            let WebSocketMessage::OrderUpdate(update) = event else {
+                return;
+            };
+            if update.order.order_type == OrderType::Limit && update.order.order_status == OrderStatus::Filled {
+                let callback_rate = CallbackRate::new(d("2")).unwrap();
+                let trailing = NewOrderRequest::trailing_stop(symbol(), OrderSide::Sell, d("1"), callback_rate, None)
+                    .reduce_only(true);
+                ctx.new_order(trailing).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_trailing_stop_and_funding_on_agg_trades() {
+        let trades = vec![
+            agg_trade(1_000, "100"),
+            agg_trade(2_000, "98.5"),
+            agg_trade(3_000, "102"),
+            agg_trade(4_000, "105"),
+            agg_trade(8 * HOUR + 1, "104"),
+            // 2% below the high of 105 is 102.9
+            agg_trade(8 * HOUR + 2, "102.5"),
+        ];
+        let funding = FundingRate {
+            symbol: "BTCUSDT".to_string(),
+            funding_rate: d("0.0001"),
+            funding_time: 8 * HOUR,
+            mark_price: Some(d("104")),
+        };
+        let report = Backtest::new(PaperConfig::default())
+            .agg_trades("BTCUSDT", trades)
+            .funding_rates([funding])
+            .run(&mut Trail)
+            .unwrap();
+
+        let fills: Vec<(u64, String, bool)> = report.trades.iter().map(|t| (t.time, t.price.to_string(), t.is_maker)).collect();
+        assert_eq!(fills, [(2_000, "99".to_string(), true), (8 * HOUR + 2, "102.5".to_string(), false)]);
+
+        // A long pays 1 * 104 * 0.0001 at the funding time
+        assert_eq!(report.funding.len(), 1);
+        assert_eq!(report.funding[0].amount.to_string(), "-0.0104");
+        assert_eq!(report.total_funding.to_string(), "-0.0104");
+
+        // 10000 - 0.0198 maker fee - 0.0104 funding + 3.5 - 0.05125 taker fee
+        assert_eq!(report.realized_pnl.to_string(), "3.5");
+        assert_eq!(report.final_balance.to_string(), "10003.41855");
+        assert_eq!(report.equity_curve.last().unwrap().time, 8 * HOUR + 2);
+    }
+
+    #[test]
+    fn test_overflowing_funding_total_is_an_error() {
+        let record = FundingRate {
+            symbol: symbol(),
+            funding_rate: d("0.0001"),
+            funding_time: 0,
+            mark_price: None,
+        };
+        let max = d("79228162514264337593543950335");
+        let mut recorder = Recorder::new(d("10000"), 0);
+        recorder.funding(&record, max).unwrap();
+        assert!(recorder.funding(&record, max).is_err());
+        assert_eq!(recorder.total_funding, max);
+        assert_eq!(recorder.funding.len(), 1);
+    }
+}
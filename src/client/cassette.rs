@@ -0,0 +1,406 @@
+use crate::client::transport::{Transport, TransportRequest, TransportResponse};
+use crate::error::{BinanceError, Result};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Parameters that change on every signed request and are ignored when matching
+const VOLATILE_PARAMS: &[&str] = &["timestamp", "signature", "recvWindow"];
+
+/// How a [`CassetteTransport`] uses its file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send requests through the inner transport and save every interaction
+    Record,
+    /// Serve responses from the file; never touch the network
+    Replay,
+    /// Replay if the file exists, otherwise record
+    Auto,
+}
+
+/// Recorded request, without headers so API keys never end up on disk
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub endpoint: String,
+    pub params: BTreeMap<String, String>,
+}
+
+impl RecordedRequest {
+    fn from_request(request: &TransportRequest) -> Self {
+        Self {
+            method: request.method.to_string(),
+            endpoint: request.endpoint.clone(),
+            params: request
+                .params()
+                .into_iter()
+                .filter(|(k, _)| !VOLATILE_PARAMS.contains(&k.as_str()))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+impl RecordedResponse {
+    fn from_response(response: &TransportResponse) -> Self {
+        Self {
+            status: response.status.as_u16(),
+            headers: response
+                .headers
+                .iter()
+                .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_string())))
+                .collect(),
+            body: response.body.clone(),
+        }
+    }
+
+    fn to_response(&self) -> TransportResponse {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (HeaderName::try_from(name.as_str()), HeaderValue::from_str(value)) {
+                headers.insert(name, value);
+            }
+        }
+        TransportResponse {
+            status: StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            headers,
+            body: self.body.clone(),
+        }
+    }
+}
+
+/// A single request/response pair
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cassette {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug)]
+struct CassetteState {
+    path: PathBuf,
+    interactions: Vec<Interaction>,
+    used: Vec<bool>,
+    /// Interactions were recorded since the file was last written
+    unsaved: bool,
+}
+
+impl CassetteState {
+    fn new(path: PathBuf, interactions: Vec<Interaction>) -> Self {
+        Self {
+            path,
+            used: vec![false; interactions.len()],
+            interactions,
+            unsaved: false,
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if !self.unsaved {
+            return Ok(());
+        }
+        let cassette = Cassette {
+            interactions: self.interactions.clone(),
+        };
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                BinanceError::Unknown(format!("Failed to create cassette directory {}: {}", parent.display(), e))
+            })?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&cassette)?)
+            .map_err(|e| BinanceError::Unknown(format!("Failed to write cassette {}: {}", self.path.display(), e)))?;
+        self.unsaved = false;
+        Ok(())
+    }
+}
+
+impl Drop for CassetteState {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            log::warn!("{}", e);
+        }
+    }
+}
+
+/// Transport that records real request/response pairs to a JSON file and
+/// replays them deterministically.
+///
+/// Requests are matched on method, endpoint and parameters, ignoring
+/// `timestamp`, `signature`, `recvWindow` and any set with
+/// [`ignore_params`](Self::ignore_params). Identical requests are served in
+/// recording order; the last match is repeated once all are used.
+///
+/// A recording is written when the last clone is dropped, or earlier with
+/// [`flush`](Self::flush).
+#[derive(Clone)]
+pub struct CassetteTransport {
+    path: PathBuf,
+    inner: Option<Arc<dyn Transport>>,
+    ignored_params: Vec<String>,
+    state: Arc<Mutex<CassetteState>>,
+}
+
+impl CassetteTransport {
+    /// Record interactions sent through `inner` to `path`, replacing any existing file
+    pub fn record(path: impl AsRef<Path>, inner: impl Transport + 'static) -> Self {
+        Self::recording(path.as_ref().to_path_buf(), Arc::new(inner))
+    }
+
+    /// Replay interactions from `path`
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| BinanceError::Unknown(format!("Failed to read cassette {}: {}", path.display(), e)))?;
+        let cassette: Cassette = serde_json::from_str(&content)?;
+
+        Ok(Self {
+            state: Arc::new(Mutex::new(CassetteState::new(path.clone(), cassette.interactions))),
+            path,
+            inner: None,
+            ignored_params: Vec::new(),
+        })
+    }
+
+    /// Create a cassette in the given mode
+    pub fn new(path: impl AsRef<Path>, mode: CassetteMode, inner: Arc<dyn Transport>) -> Result<Self> {
+        let path = path.as_ref();
+        match mode {
+            CassetteMode::Record => Ok(Self::recording(path.to_path_buf(), inner)),
+            CassetteMode::Replay => Self::replay(path),
+            CassetteMode::Auto if path.exists() => Self::replay(path),
+            CassetteMode::Auto => Ok(Self::recording(path.to_path_buf(), inner)),
+        }
+    }
+
+    fn recording(path: PathBuf, inner: Arc<dyn Transport>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(CassetteState::new(path.clone(), Vec::new()))),
+            path,
+            inner: Some(inner),
+            ignored_params: Vec::new(),
+        }
+    }
+
+    /// Also ignore these parameters when replaying, e.g. `newClientOrderId`
+    /// from a [`ClientOrderIdGenerator`](crate::api::ClientOrderIdGenerator)
+    /// or a `startTime` derived from the current time.
+    ///
+    /// They are still recorded.
+    pub fn ignore_params<I, S>(mut self, params: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.ignored_params.extend(params.into_iter().map(Into::into));
+        self
+    }
+
+    /// Write the interactions recorded so far to the file
+    pub fn flush(&self) -> Result<()> {
+        self.state.lock().unwrap().flush()
+    }
+
+    /// Whether requests go to the inner transport
+    pub fn is_recording(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Path of the cassette file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Interactions recorded or loaded so far
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.state.lock().unwrap().interactions.clone()
+    }
+
+    /// Whether a recorded request answers `request`
+    fn matches(&self, recorded: &RecordedRequest, request: &RecordedRequest) -> bool {
+        recorded.method == request.method
+            && recorded.endpoint == request.endpoint
+            && self.compared(&recorded.params).eq(self.compared(&request.params))
+    }
+
+    fn compared<'a>(&'a self, params: &'a BTreeMap<String, String>) -> impl Iterator<Item = (&'a String, &'a String)> {
+        params.iter().filter(move |(k, _)| !self.ignored_params.contains(k))
+    }
+}
+
+#[async_trait]
+impl Transport for CassetteTransport {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse> {
+        let recorded = RecordedRequest::from_request(&request);
+
+        if let Some(inner) = &self.inner {
+            let response = inner.send(request).await?;
+            let mut state = self.state.lock().unwrap();
+            state.interactions.push(Interaction {
+                request: recorded,
+                response: RecordedResponse::from_response(&response),
+            });
+            state.used.push(true);
+            state.unsaved = true;
+            return Ok(response);
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let matches = state
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| self.matches(&i.request, &recorded))
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        let index = matches
+            .iter()
+            .copied()
+            .find(|&index| !state.used[index])
+            .or_else(|| matches.last().copied())
+            .ok_or_else(|| {
+                BinanceError::Unknown(format!(
+                    "No recorded interaction for {} {} in {}",
+                    request.method,
+                    request.endpoint,
+                    self.path.display()
+                ))
+            })?;
+
+        state.used[index] = true;
+        Ok(state.interactions[index].response.to_response())
+    }
+}
+
+impl std::fmt::Debug for CassetteTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CassetteTransport")
+            .field("path", &self.path)
+            .field("recording", &self.is_recording())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::transport::MemoryTransport;
+    use reqwest::Method;
+
+    fn request(method: Method, endpoint: &str, query: &str) -> TransportRequest {
+        TransportRequest {
+            method,
+            base_url: "http://localhost".to_string(),
+            endpoint: endpoint.to_string(),
+            query: query.to_string(),
+            body: None,
+            headers: HeaderMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let path = std::env::temp_dir().join(format!(
+            "binance-futures-rs-cassette-{}.json",
+            std::process::id()
+        ));
+
+        let live = MemoryTransport::new();
+        live.push(
+            Method::GET,
+            "/fapi/v1/time",
+            TransportResponse::new(200, r#"{"serverTime":1}"#).header("x-mbx-used-weight-1m", "1"),
+        );
+        live.push_json(Method::GET, "/fapi/v1/time", 200, r#"{"serverTime":2}"#);
+        live.push_json(Method::GET, "/fapi/v2/account", 200, "{}");
+
+        let recorder = CassetteTransport::record(&path, live.clone());
+        recorder.send(request(Method::GET, "/fapi/v1/time", "")).await.unwrap();
+        recorder.send(request(Method::GET, "/fapi/v1/time", "")).await.unwrap();
+        recorder
+            .send(request(Method::GET, "/fapi/v2/account", "timestamp=1&signature=abc"))
+            .await
+            .unwrap();
+        assert_eq!(live.requests().len(), 3);
+        // Written once, when the last clone goes away
+        assert!(!path.exists());
+        drop(recorder);
+
+        let player = CassetteTransport::replay(&path).unwrap();
+        assert!(!player.is_recording());
+
+        let first = player.send(request(Method::GET, "/fapi/v1/time", "")).await.unwrap();
+        assert_eq!(first.body, r#"{"serverTime":1}"#);
+        assert_eq!(first.headers["x-mbx-used-weight-1m"], "1");
+        let second = player.send(request(Method::GET, "/fapi/v1/time", "")).await.unwrap();
+        assert_eq!(second.body, r#"{"serverTime":2}"#);
+
+        // Volatile parameters are ignored when matching
+        let account = player
+            .send(request(Method::GET, "/fapi/v2/account", "timestamp=99&signature=xyz"))
+            .await
+            .unwrap();
+        assert_eq!(account.body, "{}");
+
+        assert!(player
+            .send(request(Method::GET, "/fapi/v2/account", "symbol=BTCUSDT"))
+            .await
+            .is_err());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(CassetteTransport::replay(&path).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay_ignores_generated_client_order_ids() {
+        use crate::api::test_support::{credentials, order_json};
+        use crate::api::{ClientOrderIdGenerator, OrderSubmitter, TradingApi};
+        use crate::client::HttpClient;
+        use crate::types::common::OrderSide;
+        use crate::types::trading::NewOrderRequest;
+
+        let path = std::env::temp_dir().join(format!(
+            "binance-futures-rs-cassette-submit-{}.json",
+            std::process::id()
+        ));
+        let submit = |transport: CassetteTransport, prefix: &str| {
+            let trading = TradingApi::new(HttpClient::new_with_credentials(credentials()).with_transport(transport));
+            let submitter = OrderSubmitter::new(trading, ClientOrderIdGenerator::new(prefix).unwrap());
+            async move {
+                let order = NewOrderRequest::market("BTCUSDT".to_string(), OrderSide::Sell, "0.5".parse().unwrap());
+                submitter.submit(order).await
+            }
+        };
+
+        let live = MemoryTransport::new();
+        live.push_json(Method::POST, "/fapi/v1/order", 200, &order_json(serde_json::json!({"orderId": 42})));
+        let recorder = CassetteTransport::record(&path, live);
+        submit(recorder.clone(), "record-").await.unwrap();
+        recorder.flush().unwrap();
+        assert!(path.exists());
+
+        // Every run generates new ids, so they only match once ignored
+        let player = CassetteTransport::replay(&path).unwrap();
+        let err = submit(player, "replay-").await.unwrap_err();
+        assert!(err.to_string().contains("No recorded interaction"), "{}", err);
+        let player = CassetteTransport::replay(&path).unwrap().ignore_params(["newClientOrderId"]);
+        assert_eq!(submit(player, "replay-").await.unwrap().order_id, 42);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,32 +1,51 @@
 use crate::client::auth::{Credentials, Signer};
-use crate::error::{ApiErrorResponse, BinanceError, Result};
-use reqwest::{Client, Response};
+use crate::client::cassette::{CassetteMode, CassetteTransport};
+use crate::client::params::RequestParams;
+use crate::client::rate_limit::{self, RateLimiter};
+use crate::client::retry::RetryPolicy;
+use crate::client::time_sync::{TimeSync, MAX_RECV_WINDOW};
+use crate::client::transport::{ReqwestTransport, Transport, TransportRequest, TransportResponse};
+use crate::error::{ApiErrorResponse, BinanceError, BinanceErrorCode, Result};
+use crate::types::market::ServerTime;
+use crate::utils::get_timestamp;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{Client, Method, StatusCode};
 use serde::de::DeserializeOwned;
-use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
-const BASE_URL: &str = "https://fapi.binance.com";
-const TESTNET_URL: &str = "https://testnet.binancefuture.com";
+pub const BASE_URL: &str = "https://fapi.binance.com";
+pub const TESTNET_URL: &str = "https://testnet.binancefuture.com";
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
 
 #[derive(Clone)]
 pub struct HttpClient {
-    client: Client,
+    transport: Arc<dyn Transport>,
     base_url: String,
     signer: Option<Signer>,
+    time_sync: TimeSync,
+    recv_window: Option<u64>,
+    rate_limiter: RateLimiter,
+    retry_policy: RetryPolicy,
+    dry_run: bool,
 }
 
 impl HttpClient {
+    /// Create a client for the production endpoint.
+    ///
+    /// Panics if the underlying reqwest client cannot be created; use
+    /// [`HttpClient::builder`] to handle that error instead.
     pub fn new() -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
+        HttpClientBuilder::new()
             .build()
-            .expect("Failed to create HTTP client");
+            .expect("Failed to create HTTP client")
+    }
 
-        Self {
-            client,
-            base_url: BASE_URL.to_string(),
-            signer: None,
-        }
+    /// Create a builder for a custom client configuration
+    pub fn builder() -> HttpClientBuilder {
+        HttpClientBuilder::new()
     }
 
     pub fn new_with_credentials(credentials: Credentials) -> Self {
@@ -47,147 +66,353 @@ impl HttpClient {
         client
     }
 
+    /// Set the default `recvWindow` (milliseconds) sent with every signed request.
+    ///
+    /// A `recvWindow` entry in a request's own parameters overrides this value.
+    pub fn with_recv_window(mut self, recv_window: u64) -> Self {
+        self.recv_window = Some(recv_window);
+        self
+    }
+
+    /// Enable server time synchronization, re-syncing every `interval`.
+    ///
+    /// Signed requests are stamped with the corrected clock, and a -1021
+    /// rejection triggers an immediate re-sync and a single retry.
+    pub fn with_time_sync(self, interval: Duration) -> Self {
+        self.time_sync.enable(interval);
+        self
+    }
+
+    /// Send requests through a custom transport, e.g. a [`MemoryTransport`](crate::client::MemoryTransport) in tests
+    pub fn with_transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Use a specific rate limiter, e.g. one shared with another client
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Get the shared rate limiter
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.rate_limiter
+    }
+
+    /// Set the retry policy for transient failures
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Get the retry policy
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Enable or disable dry-run mode.
+    ///
+    /// In dry-run mode [`TradingApi`](crate::TradingApi) calls that place, modify
+    /// or cancel orders are validated and signed but only logged, never sent.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Whether dry-run mode is enabled
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Get the base URL requests are sent to
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Get the default `recvWindow`
+    pub fn recv_window(&self) -> Option<u64> {
+        self.recv_window
+    }
+
+    /// Get the shared server time synchronization state
+    pub fn time_sync(&self) -> &TimeSync {
+        &self.time_sync
+    }
+
     /// Make a public GET request (no authentication required)
-    pub async fn get_public<T>(&self, endpoint: &str, params: Option<HashMap<String, String>>) -> Result<T>
+    pub async fn get_public<T>(&self, endpoint: &str, params: Option<RequestParams>) -> Result<T>
     where
         T: DeserializeOwned,
     {
-        let url = format!("{}{}", self.base_url, endpoint);
-        let mut request = self.client.get(&url);
-
-        if let Some(params) = params {
-            request = request.query(&params);
+        let params = params.unwrap_or_default();
+        let mut attempt = 1;
+
+        loop {
+            match self.get_public_once(endpoint, &params).await {
+                Err(e) if self.retry_policy.should_retry(&Method::GET, endpoint, &e, attempt) => {
+                    tokio::time::sleep(self.retry_policy.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
         }
-
-        let response = request.send().await?;
-        self.handle_response(response).await
     }
 
-    /// Make a signed GET request (authentication required)
-    pub async fn get_signed<T>(&self, endpoint: &str, params: Option<HashMap<String, String>>) -> Result<T>
+    async fn get_public_once<T>(&self, endpoint: &str, params: &RequestParams) -> Result<T>
     where
         T: DeserializeOwned,
     {
-        let signer = self.signer.as_ref().ok_or_else(|| {
-            BinanceError::Authentication("No credentials provided for signed request".to_string())
-        })?;
-
-        let signed_params = if let Some(params) = params {
-            signer.sign_request(params)?
-        } else {
-            signer.sign_request(HashMap::new())?
+        self.acquire_rate_limit(&Method::GET, endpoint, params).await?;
+
+        let request = TransportRequest {
+            method: Method::GET,
+            base_url: self.base_url.clone(),
+            endpoint: endpoint.to_string(),
+            query: params.to_query_string(),
+            body: None,
+            headers: HeaderMap::new(),
         };
 
-        let url = format!("{}{}", self.base_url, endpoint);
-        let request = self.client
-            .get(&url)
-            .query(&signed_params)
-            .header("X-MBX-APIKEY", signer.get_api_key());
+        let response = self.transport.send(request).await?;
+        self.handle_response(endpoint, response)
+    }
 
-        let response = request.send().await?;
-        self.handle_response(response).await
+    /// Make a signed GET request (authentication required)
+    pub async fn get_signed<T>(&self, endpoint: &str, params: Option<RequestParams>) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.send_signed(Method::GET, endpoint, params).await
     }
 
     /// Make a signed POST request
-    pub async fn post_signed<T>(&self, endpoint: &str, params: Option<HashMap<String, String>>) -> Result<T>
+    pub async fn post_signed<T>(&self, endpoint: &str, params: Option<RequestParams>) -> Result<T>
     where
         T: DeserializeOwned,
     {
-        let signer = self.signer.as_ref().ok_or_else(|| {
-            BinanceError::Authentication("No credentials provided for signed request".to_string())
-        })?;
-
-        let signed_params = if let Some(params) = params {
-            signer.sign_request(params)?
-        } else {
-            signer.sign_request(HashMap::new())?
-        };
-
-        let url = format!("{}{}", self.base_url, endpoint);
-        let request = self.client
-            .post(&url)
-            .form(&signed_params)
-            .header("X-MBX-APIKEY", signer.get_api_key());
-
-        let response = request.send().await?;
-        self.handle_response(response).await
+        self.send_signed(Method::POST, endpoint, params).await
     }
 
     /// Make a signed PUT request
-    pub async fn put_signed<T>(&self, endpoint: &str, params: Option<HashMap<String, String>>) -> Result<T>
+    pub async fn put_signed<T>(&self, endpoint: &str, params: Option<RequestParams>) -> Result<T>
     where
         T: DeserializeOwned,
     {
-        let signer = self.signer.as_ref().ok_or(BinanceError::Authentication(
-            "No credentials provided for signed request".to_string(),
-        ))?;
+        self.send_signed(Method::PUT, endpoint, params).await
+    }
+
+    /// Make a signed DELETE request
+    pub async fn delete_signed<T>(&self, endpoint: &str, params: Option<RequestParams>) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.send_signed(Method::DELETE, endpoint, params).await
+    }
 
-        let url = format!("{}{}", self.base_url, endpoint);
-        let timestamp = crate::utils::get_timestamp();
-        
-        let mut query_params = params.unwrap_or_default();
-        query_params.insert("timestamp".to_string(), timestamp.to_string());
+    /// Measure the offset to Binance server time and apply it to signed requests.
+    ///
+    /// Returns the new offset in milliseconds (server time - local time).
+    pub async fn sync_time(&self) -> Result<i64> {
+        let local_send = get_timestamp();
+        let time: ServerTime = self.get_public("/fapi/v1/time", None).await?;
+        let local_recv = get_timestamp();
 
-        let query_string = crate::utils::build_query_string_from_map(&query_params);
-        let signature = signer.sign(&query_string)?;
-        query_params.insert("signature".to_string(), signature);
+        Ok(self.time_sync.record(local_send, time.server_time, local_recv))
+    }
 
-        let response = self
-            .client
-            .put(&url)
-            .query(&query_params)
-            .header("X-MBX-APIKEY", signer.get_api_key())
-            .send()
-            .await
-            .map_err(|e| BinanceError::Http(e))?;
+    /// Send a signed request, applying the retry policy
+    pub(crate) async fn send_signed<T>(
+        &self,
+        method: Method,
+        endpoint: &str,
+        params: Option<RequestParams>,
+    ) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let params = params.unwrap_or_default();
+        let mut attempt = 1;
+
+        loop {
+            match self.send_signed_synced(method.clone(), endpoint, params.clone()).await {
+                Err(e) if self.retry_policy.should_retry(&method, endpoint, &e, attempt) => {
+                    tokio::time::sleep(self.retry_policy.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
 
-        self.handle_response(response).await
+    /// Send a signed request, re-syncing time and retrying once on -1021
+    async fn send_signed_synced<T>(
+        &self,
+        method: Method,
+        endpoint: &str,
+        params: RequestParams,
+    ) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        match self.send_signed_once(method.clone(), endpoint, params.clone()).await {
+            Err(BinanceError::Api {
+                code: BinanceErrorCode::InvalidTimestamp,
+                ..
+            }) if self.time_sync.is_enabled() => {
+                // Timestamp outside recvWindow: the clock drifted, re-measure and try again
+                self.sync_time().await?;
+                self.send_signed_once(method, endpoint, params).await
+            }
+            result => result,
+        }
     }
 
-    /// Make a signed DELETE request
-    pub async fn delete_signed<T>(&self, endpoint: &str, params: Option<HashMap<String, String>>) -> Result<T>
+    async fn send_signed_once<T>(
+        &self,
+        method: Method,
+        endpoint: &str,
+        params: RequestParams,
+    ) -> Result<T>
     where
         T: DeserializeOwned,
     {
-        let signer = self.signer.as_ref().ok_or(BinanceError::Authentication(
-            "No credentials provided for signed request".to_string(),
-        ))?;
+        self.signer()?;
 
-        let url = format!("{}{}", self.base_url, endpoint);
-        let timestamp = crate::utils::get_timestamp();
-        
-        let mut query_params = params.unwrap_or_default();
-        query_params.insert("timestamp".to_string(), timestamp.to_string());
+        if self.time_sync.needs_sync() {
+            self.sync_time().await?;
+        }
 
-        let query_string = crate::utils::build_query_string_from_map(&query_params);
-        let signature = signer.sign(&query_string)?;
-        query_params.insert("signature".to_string(), signature);
+        self.acquire_rate_limit(&method, endpoint, &params).await?;
 
-        let response = self
-            .client
-            .delete(&url)
-            .query(&query_params)
-            .header("X-MBX-APIKEY", signer.get_api_key())
-            .send()
-            .await
-            .map_err(|e| BinanceError::Http(e))?;
+        let request = self.prepare_signed(method, endpoint, params)?;
+        let response = self.transport.send(request).await?;
+        self.handle_response(endpoint, response)
+    }
 
-        self.handle_response(response).await
+    /// Sign a request and log it instead of sending it, for dry-run mode
+    pub(crate) fn dry_run_signed(
+        &self,
+        method: Method,
+        endpoint: &str,
+        params: RequestParams,
+    ) -> Result<TransportRequest> {
+        let request = self.prepare_signed(method, endpoint, params)?;
+        log::info!(
+            target: "binance_futures_rs::dry_run",
+            "{} {} {}",
+            request.method,
+            request.url(),
+            request.body.as_deref().unwrap_or("")
+        );
+        Ok(request)
     }
 
-    async fn handle_response<T>(&self, response: Response) -> Result<T>
+    /// Build the signed wire request
+    fn prepare_signed(&self, method: Method, endpoint: &str, params: RequestParams) -> Result<TransportRequest> {
+        let signer = self.signer()?;
+
+        // A per-call recvWindow takes precedence over the default, so check whichever is sent
+        let recv_window = match params.get("recvWindow") {
+            Some(value) => Some(value.parse::<u64>().map_err(|_| {
+                BinanceError::InvalidParameter(format!("recvWindow {} is not a number of milliseconds", value))
+            })?),
+            None => self.recv_window,
+        };
+        if let Some(recv_window) = recv_window {
+            if recv_window > MAX_RECV_WINDOW {
+                return Err(BinanceError::InvalidParameter(format!(
+                    "recvWindow {} exceeds maximum of {}",
+                    recv_window, MAX_RECV_WINDOW
+                )));
+            }
+        }
+
+        let signed_params = signer.sign_request_with(params, self.time_sync.timestamp(), self.recv_window)?;
+
+        let mut headers = HeaderMap::new();
+        let api_key = HeaderValue::from_str(signer.get_api_key())
+            .map_err(|_| BinanceError::Authentication("API key contains invalid characters".to_string()))?;
+        headers.insert("X-MBX-APIKEY", api_key);
+
+        let encoded = signed_params.to_query_string();
+        let (query, body) = if method == Method::POST {
+            (String::new(), Some(encoded))
+        } else {
+            (encoded, None)
+        };
+
+        Ok(TransportRequest {
+            method,
+            base_url: self.base_url.clone(),
+            endpoint: endpoint.to_string(),
+            query,
+            body,
+            headers,
+        })
+    }
+
+    fn signer(&self) -> Result<&Signer> {
+        self.signer.as_ref().ok_or_else(|| {
+            BinanceError::Authentication("No credentials provided for signed request".to_string())
+        })
+    }
+
+    /// Wait for rate limit capacity for an endpoint, or fail if the wait is too long
+    async fn acquire_rate_limit(
+        &self,
+        method: &Method,
+        endpoint: &str,
+        params: &RequestParams,
+    ) -> Result<()> {
+        let weight = rate_limit::endpoint_weight(method, endpoint, params);
+        let orders = rate_limit::endpoint_order_count(method, endpoint, params);
+
+        while let Some(wait) = self.rate_limiter.reserve(weight, orders, self.time_sync.timestamp())? {
+            tokio::time::sleep(wait).await;
+        }
+        Ok(())
+    }
+
+    fn handle_response<T>(&self, endpoint: &str, response: TransportResponse) -> Result<T>
     where
         T: DeserializeOwned,
     {
-        let status = response.status();
-        let text = response.text().await?;
+        let status = response.status;
+        let now = self.time_sync.timestamp();
+        self.rate_limiter.update_from_headers(&response.headers, now);
+
+        if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::IM_A_TEAPOT {
+            let retry_after = rate_limit::retry_after(&response.headers);
+            if let Some(retry_after) = retry_after {
+                self.rate_limiter.block_for(retry_after, now);
+            }
+            return Err(if status == StatusCode::IM_A_TEAPOT {
+                BinanceError::IpBanned { retry_after }
+            } else {
+                BinanceError::RateLimit { retry_after }
+            });
+        }
+
+        let text = response.body;
 
         if status.is_success() {
             serde_json::from_str(&text).map_err(BinanceError::Json)
         } else {
             // Try to parse as API error response
             if let Ok(error_response) = serde_json::from_str::<ApiErrorResponse>(&text) {
-                Err(BinanceError::from(error_response))
+                Err(BinanceError::Api {
+                    code: BinanceErrorCode::from(error_response.code),
+                    msg: error_response.msg,
+                    status: Some(status.as_u16()),
+                    endpoint: Some(endpoint.to_string()),
+                })
+            } else if status.is_server_error() {
+                Err(BinanceError::Server {
+                    status: status.as_u16(),
+                    msg: text,
+                    endpoint: Some(endpoint.to_string()),
+                })
             } else {
                 Err(BinanceError::Unknown(format!(
                     "HTTP {} - {}",
@@ -199,6 +424,242 @@ impl HttpClient {
     }
 }
 
+/// Builder for [`HttpClient`]
+#[derive(Clone)]
+pub struct HttpClientBuilder {
+    base_url: String,
+    credentials: Option<Credentials>,
+    client: Option<Client>,
+    transport: Option<Arc<dyn Transport>>,
+    cassette: Option<(PathBuf, CassetteMode)>,
+    cassette_ignored_params: Vec<String>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<String>,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    tcp_nodelay: Option<bool>,
+    user_agent: Option<String>,
+    recv_window: Option<u64>,
+    time_sync_interval: Option<Duration>,
+    rate_limiter: Option<RateLimiter>,
+    retry_policy: RetryPolicy,
+    dry_run: bool,
+}
+
+impl HttpClientBuilder {
+    pub fn new() -> Self {
+        Self {
+            base_url: BASE_URL.to_string(),
+            credentials: None,
+            client: None,
+            transport: None,
+            cassette: None,
+            cassette_ignored_params: Vec::new(),
+            timeout: Some(DEFAULT_TIMEOUT),
+            connect_timeout: None,
+            proxy: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            tcp_nodelay: None,
+            user_agent: None,
+            recv_window: None,
+            time_sync_interval: None,
+            rate_limiter: None,
+            retry_policy: RetryPolicy::default(),
+            dry_run: false,
+        }
+    }
+
+    /// Send requests to an arbitrary base URL, e.g. a local mock server
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Use the testnet endpoint
+    pub fn testnet(self) -> Self {
+        self.base_url(TESTNET_URL)
+    }
+
+    /// Sign requests with these credentials
+    pub fn credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Use a pre-built reqwest client.
+    ///
+    /// The connection options of this builder (proxy, timeouts, pool,
+    /// TCP_NODELAY and user agent) are ignored in that case.
+    pub fn http_client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Send requests through a custom transport instead of reqwest
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Record interactions to, or replay them from, a cassette file
+    pub fn cassette(mut self, path: impl Into<PathBuf>, mode: CassetteMode) -> Self {
+        self.cassette = Some((path.into(), mode));
+        self
+    }
+
+    /// Parameters the cassette ignores when matching a replayed request,
+    /// see [`CassetteTransport::ignore_params`]
+    pub fn cassette_ignore_params<I, S>(mut self, params: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.cassette_ignored_params.extend(params.into_iter().map(Into::into));
+        self
+    }
+
+    /// Total request timeout (default 30 seconds)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for establishing a connection
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Route all requests through an HTTP(S) or SOCKS5 proxy,
+    /// e.g. `http://127.0.0.1:8080` or `socks5h://127.0.0.1:1080`
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    /// Maximum idle connections kept per host
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// How long idle connections are kept alive
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Enable or disable TCP_NODELAY
+    pub fn tcp_nodelay(mut self, enabled: bool) -> Self {
+        self.tcp_nodelay = Some(enabled);
+        self
+    }
+
+    /// Send a custom `User-Agent` header
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Default `recvWindow` (milliseconds) for signed requests
+    pub fn recv_window(mut self, recv_window: u64) -> Self {
+        self.recv_window = Some(recv_window);
+        self
+    }
+
+    /// Enable server time synchronization, re-syncing every `interval`
+    pub fn time_sync(mut self, interval: Duration) -> Self {
+        self.time_sync_interval = Some(interval);
+        self
+    }
+
+    /// Share a rate limiter with other clients
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Retry policy for transient failures
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sign and log order requests instead of sending them
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Build the client
+    pub fn build(self) -> Result<HttpClient> {
+        let base_url = self.base_url.trim_end_matches('/').to_string();
+        url::Url::parse(&base_url)
+            .map_err(|e| BinanceError::InvalidParameter(format!("Invalid base URL {}: {}", base_url, e)))?;
+
+        let transport: Arc<dyn Transport> = match (self.transport, self.client) {
+            (Some(transport), _) => transport,
+            (None, Some(client)) => Arc::new(ReqwestTransport::new(client)),
+            (None, None) => {
+                let mut builder = Client::builder();
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+                if let Some(proxy) = &self.proxy {
+                    builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+                }
+                if let Some(max) = self.pool_max_idle_per_host {
+                    builder = builder.pool_max_idle_per_host(max);
+                }
+                if let Some(timeout) = self.pool_idle_timeout {
+                    builder = builder.pool_idle_timeout(timeout);
+                }
+                if let Some(enabled) = self.tcp_nodelay {
+                    builder = builder.tcp_nodelay(enabled);
+                }
+                if let Some(user_agent) = &self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                Arc::new(ReqwestTransport::new(builder.build()?))
+            }
+        };
+
+        let transport: Arc<dyn Transport> = match self.cassette {
+            Some((path, mode)) => Arc::new(
+                CassetteTransport::new(path, mode, transport)?.ignore_params(self.cassette_ignored_params),
+            ),
+            None => transport,
+        };
+
+        let time_sync = TimeSync::new();
+        if let Some(interval) = self.time_sync_interval {
+            time_sync.enable(interval);
+        }
+
+        Ok(HttpClient {
+            transport,
+            base_url,
+            signer: self.credentials.map(Signer::new),
+            time_sync,
+            recv_window: self.recv_window,
+            rate_limiter: self.rate_limiter.unwrap_or_default(),
+            retry_policy: self.retry_policy,
+            dry_run: self.dry_run,
+        })
+    }
+}
+
+impl Default for HttpClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Default for HttpClient {
     fn default() -> Self {
         Self::new()
@@ -228,4 +689,152 @@ mod tests {
         let client = HttpClient::new_with_credentials(credentials);
         assert!(client.signer.is_some());
     }
+
+    #[test]
+    fn test_client_builder() {
+        let credentials = Credentials::new("test_key".to_string(), "test_secret".to_string());
+        let client = HttpClient::builder()
+            .base_url("http://127.0.0.1:8080/")
+            .credentials(credentials)
+            .proxy("socks5h://127.0.0.1:1080")
+            .timeout(Duration::from_secs(5))
+            .connect_timeout(Duration::from_secs(1))
+            .pool_max_idle_per_host(4)
+            .tcp_nodelay(true)
+            .user_agent("my-bot/1.0")
+            .recv_window(5000)
+            .build()
+            .unwrap();
+
+        assert_eq!(client.base_url(), "http://127.0.0.1:8080");
+        assert!(client.signer.is_some());
+        assert_eq!(client.recv_window(), Some(5000));
+
+        let client = HttpClient::builder().testnet().http_client(Client::new()).build().unwrap();
+        assert_eq!(client.base_url(), TESTNET_URL);
+        assert!(!client.is_dry_run());
+    }
+
+    #[test]
+    fn test_dry_run_signed() {
+        let credentials = Credentials::new("test_key".to_string(), "test_secret".to_string());
+        let client = HttpClient::builder().credentials(credentials).dry_run(true).build().unwrap();
+        assert!(client.is_dry_run());
+
+        let params = RequestParams::new().with("symbol", "BTCUSDT");
+        let request = client.dry_run_signed(Method::POST, "/fapi/v1/order", params).unwrap();
+        let body = request.body.unwrap();
+        assert!(body.starts_with("symbol=BTCUSDT&timestamp="));
+        assert!(body.contains("&signature="));
+        assert_eq!(request.headers["X-MBX-APIKEY"], "test_key");
+
+        let unsigned = HttpClient::new().with_dry_run(true);
+        assert!(unsigned.dry_run_signed(Method::POST, "/fapi/v1/order", RequestParams::new()).is_err());
+    }
+
+    #[test]
+    fn test_client_builder_errors() {
+        assert!(matches!(
+            HttpClient::builder().base_url("not a url").build(),
+            Err(BinanceError::InvalidParameter(_))
+        ));
+        assert!(HttpClient::builder().proxy("::bad proxy::").build().is_err());
+    }
+
+    #[test]
+    fn test_client_time_settings() {
+        let client = HttpClient::new()
+            .with_recv_window(5000)
+            .with_time_sync(Duration::from_secs(60));
+        assert_eq!(client.recv_window(), Some(5000));
+        assert!(client.time_sync().is_enabled());
+        assert_eq!(client.time_sync().interval(), Duration::from_secs(60));
+
+        // Clones share the measured offset
+        let clone = client.clone();
+        client.time_sync().record(0, 250, 0);
+        assert_eq!(clone.time_sync().offset_ms(), 250);
+    }
+
+    #[tokio::test]
+    async fn test_signed_request_through_transport() {
+        let transport = crate::client::MemoryTransport::new();
+        transport.push_json(Method::POST, "/fapi/v1/leverage", 200, r#"{"leverage":10}"#);
+
+        let credentials = Credentials::new("test_key".to_string(), "test_secret".to_string());
+        let client = HttpClient::new_with_credentials(credentials)
+            .with_recv_window(5000)
+            .with_transport(transport.clone());
+
+        let params = RequestParams::new().with("symbol", "BTCUSDT");
+        let result: serde_json::Value = client.post_signed("/fapi/v1/leverage", Some(params)).await.unwrap();
+        assert_eq!(result["leverage"], 10);
+
+        let request = &transport.requests()[0];
+        assert_eq!(request.headers["X-MBX-APIKEY"], "test_key");
+        assert!(request.query.is_empty());
+        assert_eq!(request.param("recvWindow").as_deref(), Some("5000"));
+        assert!(request.param("timestamp").is_some());
+        assert!(request.param("signature").is_some());
+        // The body is sent exactly as signed: parameters in insertion order, signature last
+        let body = request.body.as_deref().unwrap();
+        assert!(body.starts_with("symbol=BTCUSDT&recvWindow=5000&timestamp="));
+        let (signed, signature) = body.rsplit_once("&signature=").unwrap();
+        let signer = Signer::new(Credentials::new("test_key".to_string(), "test_secret".to_string()));
+        assert_eq!(signer.sign(signed).unwrap(), signature);
+    }
+
+    #[tokio::test]
+    async fn test_error_responses_through_transport() {
+        let transport = crate::client::MemoryTransport::new();
+        transport.push_json(
+            Method::GET,
+            "/fapi/v1/depth",
+            400,
+            r#"{"code":-1121,"msg":"Invalid symbol."}"#,
+        );
+        transport.push(
+            Method::GET,
+            "/fapi/v1/klines",
+            TransportResponse::new(429, "").header("Retry-After", "7"),
+        );
+
+        let client = HttpClient::new()
+            .with_retry_policy(RetryPolicy::none())
+            .with_transport(transport);
+
+        let err = client.get_public::<serde_json::Value>("/fapi/v1/depth", None).await.unwrap_err();
+        assert_eq!(err.code(), Some(BinanceErrorCode::BadSymbol));
+        assert_eq!(err.status(), Some(400));
+
+        let err = client.get_public::<serde_json::Value>("/fapi/v1/klines", None).await.unwrap_err();
+        assert!(matches!(
+            err,
+            BinanceError::RateLimit { retry_after: Some(d) } if d == Duration::from_secs(7)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_recv_window_too_large() {
+        let credentials = Credentials::new("test_key".to_string(), "test_secret".to_string());
+        let client = HttpClient::new_with_credentials(credentials).with_recv_window(MAX_RECV_WINDOW + 1);
+        let result: Result<serde_json::Value> = client.get_signed("/fapi/v2/account", None).await;
+        assert!(matches!(result, Err(BinanceError::InvalidParameter(_))));
+    }
+
+    #[tokio::test]
+    async fn test_per_call_recv_window_too_large() {
+        let transport = crate::client::MemoryTransport::new();
+        let credentials = Credentials::new("test_key".to_string(), "test_secret".to_string());
+        let client = HttpClient::new_with_credentials(credentials)
+            .with_transport(transport.clone())
+            .with_recv_window(5000);
+
+        for recv_window in [(MAX_RECV_WINDOW + 1).to_string(), "5s".to_string()] {
+            let params = RequestParams::new().with("recvWindow", recv_window);
+            let result: Result<serde_json::Value> = client.get_signed("/fapi/v2/account", Some(params)).await;
+            assert!(matches!(result, Err(BinanceError::InvalidParameter(_))));
+        }
+        assert!(transport.requests().is_empty());
+    }
 }
@@ -1,5 +1,17 @@
 pub mod auth;
+pub mod cassette;
 pub mod http;
+pub mod params;
+pub mod rate_limit;
+pub mod retry;
+pub mod time_sync;
+pub mod transport;
 
-pub use auth::{Credentials, Signer};
-pub use http::HttpClient;
+pub use auth::{Credentials, KeyType, Signer};
+pub use cassette::{CassetteMode, CassetteTransport};
+pub use http::{HttpClient, HttpClientBuilder};
+pub use params::RequestParams;
+pub use rate_limit::RateLimiter;
+pub use retry::RetryPolicy;
+pub use time_sync::TimeSync;
+pub use transport::{MemoryTransport, ReqwestTransport, Transport, TransportRequest, TransportResponse};
@@ -0,0 +1,180 @@
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Ordered request parameters.
+///
+/// Parameters keep their insertion order and are percent-encoded (RFC 3986,
+/// UTF-8) by [`to_query_string`](RequestParams::to_query_string). The signer
+/// signs exactly that string and the client sends exactly that string, so the
+/// signature always matches what goes on the wire.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestParams {
+    params: Vec<(String, String)>,
+}
+
+impl RequestParams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a parameter, replacing the value in place if the key already exists
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        let key = key.into();
+        let value = value.into();
+        match self.params.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = value,
+            None => self.params.push((key, value)),
+        }
+    }
+
+    /// Set a parameter if `value` is present
+    pub fn insert_opt<V: ToString>(&mut self, key: impl Into<String>, value: Option<V>) {
+        if let Some(value) = value {
+            self.insert(key, value.to_string());
+        }
+    }
+
+    /// Builder-style [`insert`](RequestParams::insert)
+    pub fn with(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.insert(key, value.to_string());
+        self
+    }
+
+    /// Builder-style [`insert_opt`](RequestParams::insert_opt)
+    pub fn with_opt<V: ToString>(mut self, key: impl Into<String>, value: Option<V>) -> Self {
+        self.insert_opt(key, value);
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.params.iter().any(|(k, _)| k == key)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let index = self.params.iter().position(|(k, _)| k == key)?;
+        Some(self.params.remove(index).1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.params.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Percent-encoded `key=value&...` string in insertion order
+    pub fn to_query_string(&self) -> String {
+        self.params
+            .iter()
+            .map(|(k, v)| format!("{}={}", percent_encode(k), percent_encode(v)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+impl fmt::Display for RequestParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_query_string())
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for RequestParams {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut params = Self::new();
+        for (key, value) in iter {
+            params.insert(key, value);
+        }
+        params
+    }
+}
+
+/// Converts a map by sorting its keys, so the result is deterministic
+impl From<HashMap<String, String>> for RequestParams {
+    fn from(map: HashMap<String, String>) -> Self {
+        let mut params: Vec<_> = map.into_iter().collect();
+        params.sort_by(|a, b| a.0.cmp(&b.0));
+        Self { params }
+    }
+}
+
+/// Serializes as a JSON object in insertion order, as used by `batchOrders`
+impl Serialize for RequestParams {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.params.len()))?;
+        for (key, value) in &self.params {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Percent-encode everything except RFC 3986 unreserved characters
+fn percent_encode(input: &str) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insertion_order_and_replace() {
+        let mut params = RequestParams::new();
+        params.insert("symbol", "BTCUSDT");
+        params.insert("side", "BUY");
+        params.insert("symbol", "ETHUSDT");
+        params.insert_opt("limit", Some(5));
+        params.insert_opt::<u32>("fromId", None);
+
+        assert_eq!(params.to_query_string(), "symbol=ETHUSDT&side=BUY&limit=5");
+        assert_eq!(params.get("side"), Some("BUY"));
+        assert_eq!(params.remove("side").as_deref(), Some("BUY"));
+        assert_eq!(params.len(), 2);
+    }
+
+    #[test]
+    fn test_percent_encoding() {
+        let params = RequestParams::new()
+            .with("newClientOrderId", "my order/1+2")
+            .with("batchOrders", r#"[{"symbol":"BTCUSDT"}]"#)
+            .with("note", "价格~");
+
+        assert_eq!(
+            params.to_query_string(),
+            "newClientOrderId=my%20order%2F1%2B2\
+             &batchOrders=%5B%7B%22symbol%22%3A%22BTCUSDT%22%7D%5D\
+             &note=%E4%BB%B7%E6%A0%BC~"
+        );
+    }
+
+    #[test]
+    fn test_serialize_and_from_map() {
+        let params = RequestParams::new().with("type", "LIMIT").with("price", "1.5");
+        assert_eq!(serde_json::to_string(&params).unwrap(), r#"{"type":"LIMIT","price":"1.5"}"#);
+
+        let mut map = HashMap::new();
+        map.insert("b".to_string(), "2".to_string());
+        map.insert("a".to_string(), "1".to_string());
+        assert_eq!(RequestParams::from(map).to_query_string(), "a=1&b=2");
+    }
+}
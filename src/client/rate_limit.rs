@@ -0,0 +1,564 @@
+use crate::client::params::RequestParams;
+use crate::error::{BinanceError, Result};
+use crate::types::market::RateLimit;
+use reqwest::header::HeaderMap;
+use reqwest::Method;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const USED_WEIGHT_HEADER: &str = "x-mbx-used-weight-";
+const ORDER_COUNT_HEADER: &str = "x-mbx-order-count-";
+
+/// Default time to wait for a window to roll over before rejecting a request
+pub const DEFAULT_MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// Kind of limit tracked by the rate limiter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitKind {
+    /// IP request weight (`REQUEST_WEIGHT`)
+    RequestWeight,
+    /// Account order count (`ORDERS`)
+    Orders,
+}
+
+impl RateLimitKind {
+    fn unit(&self) -> &'static str {
+        match self {
+            RateLimitKind::RequestWeight => "weight",
+            RateLimitKind::Orders => "order count",
+        }
+    }
+}
+
+/// Usage of a single rate limit window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitWindow {
+    pub kind: RateLimitKind,
+    pub interval_ms: u64,
+    pub limit: u32,
+    pub used: u32,
+    bucket: u64,
+}
+
+impl RateLimitWindow {
+    /// A window of `interval_ms`; limiters ignore windows with a zero interval
+    pub fn new(kind: RateLimitKind, interval_ms: u64, limit: u32) -> Self {
+        Self {
+            kind,
+            interval_ms,
+            limit,
+            used: 0,
+            bucket: 0,
+        }
+    }
+
+    fn roll(&mut self, now_ms: u64) {
+        let bucket = now_ms / self.interval_ms;
+        if bucket != self.bucket {
+            self.bucket = bucket;
+            self.used = 0;
+        }
+    }
+
+    fn until_reset(&self, now_ms: u64) -> Duration {
+        let next = (now_ms / self.interval_ms + 1) * self.interval_ms;
+        Duration::from_millis(next - now_ms)
+    }
+}
+
+#[derive(Debug)]
+struct RateLimiterState {
+    enabled: bool,
+    max_wait: Duration,
+    windows: Vec<RateLimitWindow>,
+    blocked_until_ms: u64,
+}
+
+/// Client-side rate limiter fed by Binance usage headers.
+///
+/// Binance reports the weight and order count used in the current window
+/// through `X-MBX-USED-WEIGHT-*` and `X-MBX-ORDER-COUNT-*` headers. The
+/// limiter keeps those counters, reserves each endpoint's weight before the
+/// request is sent, and delays (up to `max_wait`) or rejects requests that
+/// would exceed a limit. State is shared between clones so all `HttpClient`
+/// instances derived from one client draw from the same budget.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    inner: Arc<Mutex<RateLimiterState>>,
+}
+
+impl RateLimiter {
+    /// Create a limiter with the default USDⓈ-M futures limits
+    /// (2400 weight/minute, 300 orders/10s, 1200 orders/minute)
+    pub fn new() -> Self {
+        Self::with_windows(vec![
+            RateLimitWindow::new(RateLimitKind::RequestWeight, 60_000, 2400),
+            RateLimitWindow::new(RateLimitKind::Orders, 10_000, 300),
+            RateLimitWindow::new(RateLimitKind::Orders, 60_000, 1200),
+        ])
+    }
+
+    /// Create a limiter with custom windows. Windows with a zero interval are skipped.
+    pub fn with_windows(windows: Vec<RateLimitWindow>) -> Self {
+        let windows = windows.into_iter().filter(valid_interval).collect();
+        Self {
+            inner: Arc::new(Mutex::new(RateLimiterState {
+                enabled: true,
+                max_wait: DEFAULT_MAX_WAIT,
+                windows,
+                blocked_until_ms: 0,
+            })),
+        }
+    }
+
+    /// Create a limiter that only records usage and never delays or rejects
+    pub fn disabled() -> Self {
+        let limiter = Self::new();
+        limiter.set_enabled(false);
+        limiter
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.inner.lock().unwrap().enabled = enabled;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.inner.lock().unwrap().enabled
+    }
+
+    /// Set the longest delay a request may wait for capacity before being rejected
+    pub fn set_max_wait(&self, max_wait: Duration) {
+        self.inner.lock().unwrap().max_wait = max_wait;
+    }
+
+    /// Replace the tracked windows with the limits from `ExchangeInfo.rate_limits`.
+    ///
+    /// Usage already recorded for a matching window is kept.
+    pub fn apply_exchange_limits(&self, rate_limits: &[RateLimit]) {
+        let mut state = self.inner.lock().unwrap();
+        let windows = rate_limits
+            .iter()
+            .filter_map(|limit| {
+                let kind = match limit.rate_limit_type.as_str() {
+                    "REQUEST_WEIGHT" => RateLimitKind::RequestWeight,
+                    "ORDERS" => RateLimitKind::Orders,
+                    _ => return None,
+                };
+                let unit_ms = interval_unit_ms(&limit.interval)?;
+                let interval_ms = unit_ms * limit.interval_num.max(1) as u64;
+                if interval_ms == 0 {
+                    return None;
+                }
+
+                let mut window = RateLimitWindow::new(kind, interval_ms, limit.limit);
+                if let Some(existing) = state
+                    .windows
+                    .iter()
+                    .find(|w| w.kind == kind && w.interval_ms == interval_ms)
+                {
+                    window.used = existing.used;
+                    window.bucket = existing.bucket;
+                }
+                Some(window)
+            })
+            .collect::<Vec<_>>();
+
+        if !windows.is_empty() {
+            state.windows = windows;
+        }
+    }
+
+    /// Snapshot of the tracked windows
+    pub fn windows(&self) -> Vec<RateLimitWindow> {
+        self.inner.lock().unwrap().windows.clone()
+    }
+
+    /// Reserve capacity for a request at `now_ms`.
+    ///
+    /// Returns `Ok(None)` when the request may be sent, `Ok(Some(wait))` when
+    /// the caller should sleep and try again, and an error when the required
+    /// wait exceeds `max_wait` or the request costs more than a whole window allows.
+    pub fn reserve(&self, weight: u32, orders: u32, now_ms: u64) -> Result<Option<Duration>> {
+        let mut state = self.inner.lock().unwrap();
+
+        if state.enabled {
+            // Such a request would never fit, however long it waits
+            for window in &state.windows {
+                let cost = match window.kind {
+                    RateLimitKind::RequestWeight => weight,
+                    RateLimitKind::Orders => orders,
+                };
+                if cost > window.limit {
+                    return Err(BinanceError::InvalidParameter(format!(
+                        "Request {} of {} exceeds the limit of {} per {}ms",
+                        window.kind.unit(),
+                        cost,
+                        window.limit,
+                        window.interval_ms
+                    )));
+                }
+            }
+        }
+
+        if state.enabled && state.blocked_until_ms > now_ms {
+            let wait = Duration::from_millis(state.blocked_until_ms - now_ms);
+            return if wait > state.max_wait {
+                Err(BinanceError::RateLimit { retry_after: Some(wait) })
+            } else {
+                Ok(Some(wait))
+            };
+        }
+
+        for window in state.windows.iter_mut() {
+            window.roll(now_ms);
+        }
+
+        if state.enabled {
+            let mut wait = Duration::ZERO;
+            for window in &state.windows {
+                let cost = match window.kind {
+                    RateLimitKind::RequestWeight => weight,
+                    RateLimitKind::Orders => orders,
+                };
+                // `used` comes from the server and may be anything up to u32::MAX
+                if cost > 0 && window.used.saturating_add(cost) > window.limit {
+                    wait = wait.max(window.until_reset(now_ms));
+                }
+            }
+
+            if !wait.is_zero() {
+                return if wait > state.max_wait {
+                    Err(BinanceError::RateLimit { retry_after: Some(wait) })
+                } else {
+                    Ok(Some(wait))
+                };
+            }
+        }
+
+        for window in state.windows.iter_mut() {
+            window.used = window.used.saturating_add(match window.kind {
+                RateLimitKind::RequestWeight => weight,
+                RateLimitKind::Orders => orders,
+            });
+        }
+        Ok(None)
+    }
+
+    /// Update usage from the `X-MBX-USED-WEIGHT-*` and `X-MBX-ORDER-COUNT-*` response headers
+    pub fn update_from_headers(&self, headers: &HeaderMap, now_ms: u64) {
+        let mut state = self.inner.lock().unwrap();
+
+        for (name, value) in headers {
+            let name = name.as_str();
+            let (kind, suffix) = if let Some(suffix) = name.strip_prefix(USED_WEIGHT_HEADER) {
+                (RateLimitKind::RequestWeight, suffix)
+            } else if let Some(suffix) = name.strip_prefix(ORDER_COUNT_HEADER) {
+                (RateLimitKind::Orders, suffix)
+            } else {
+                continue;
+            };
+
+            let (Some(interval_ms), Some(used)) = (
+                parse_interval_suffix(suffix),
+                value.to_str().ok().and_then(|v| v.parse::<u32>().ok()),
+            ) else {
+                continue;
+            };
+
+            if let Some(window) = state
+                .windows
+                .iter_mut()
+                .find(|w| w.kind == kind && w.interval_ms == interval_ms)
+            {
+                window.roll(now_ms);
+                // Requests still in flight were reserved locally but not yet
+                // counted by the server, so never lower the local figure.
+                window.used = window.used.max(used);
+            }
+        }
+    }
+
+    /// Block all requests until `now_ms + retry_after` (after a 429 or 418)
+    pub fn block_for(&self, retry_after: Duration, now_ms: u64) {
+        let mut state = self.inner.lock().unwrap();
+        let until = now_ms.saturating_add(retry_after.as_millis().try_into().unwrap_or(u64::MAX));
+        state.blocked_until_ms = state.blocked_until_ms.max(until);
+    }
+}
+
+/// Whether a window has a usable interval, warning about one that does not
+fn valid_interval(window: &RateLimitWindow) -> bool {
+    if window.interval_ms == 0 {
+        log::warn!("Ignoring {:?} rate limit window with a zero interval", window.kind);
+        return false;
+    }
+    true
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn interval_unit_ms(interval: &str) -> Option<u64> {
+    match interval {
+        "SECOND" => Some(1_000),
+        "MINUTE" => Some(60_000),
+        "HOUR" => Some(3_600_000),
+        "DAY" => Some(86_400_000),
+        _ => None,
+    }
+}
+
+/// Parse a header interval suffix such as `1m` or `10s` into milliseconds
+fn parse_interval_suffix(suffix: &str) -> Option<u64> {
+    let unit = suffix.chars().last()?;
+    let num: u64 = suffix[..suffix.len() - unit.len_utf8()].parse().ok()?;
+    let unit_ms = match unit.to_ascii_lowercase() {
+        's' => 1_000,
+        'm' => 60_000,
+        'h' => 3_600_000,
+        'd' => 86_400_000,
+        _ => return None,
+    };
+    Some(num * unit_ms)
+}
+
+/// Request weight of an endpoint, following the Binance USDⓈ-M futures documentation
+pub fn endpoint_weight(method: &Method, endpoint: &str, params: &RequestParams) -> u32 {
+    let has_symbol = params.contains_key("symbol");
+    let limit = params.get("limit").and_then(|l| l.parse::<u32>().ok());
+
+    match (method.as_str(), endpoint) {
+        (_, "/fapi/v1/depth") => match limit.unwrap_or(500) {
+            0..=50 => 2,
+            51..=100 => 5,
+            101..=500 => 10,
+            _ => 20,
+        },
+        (_, "/fapi/v1/klines")
+        | (_, "/fapi/v1/continuousKlines")
+        | (_, "/fapi/v1/indexPriceKlines")
+        | (_, "/fapi/v1/markPriceKlines") => match limit.unwrap_or(500) {
+            0..=99 => 1,
+            100..=499 => 2,
+            500..=1000 => 5,
+            _ => 10,
+        },
+        (_, "/fapi/v1/trades") => 5,
+        (_, "/fapi/v1/historicalTrades") => 20,
+        (_, "/fapi/v1/aggTrades") => 20,
+        (_, "/fapi/v1/ticker/24hr") => if has_symbol { 1 } else { 40 },
+        (_, "/fapi/v1/ticker/price") => if has_symbol { 1 } else { 2 },
+        (_, "/fapi/v1/premiumIndex") => if has_symbol { 1 } else { 10 },
+        ("GET", "/fapi/v1/openOrders") => if has_symbol { 1 } else { 40 },
+        ("GET", "/fapi/v1/allOrders") => 5,
+        ("POST", "/fapi/v1/batchOrders") | ("PUT", "/fapi/v1/batchOrders") => 5,
+        (_, "/fapi/v1/userTrades") => 5,
+        (_, "/fapi/v1/income") => 30,
+        (_, "/fapi/v2/account") => 5,
+        (_, "/fapi/v2/balance") => 5,
+        (_, "/fapi/v2/positionRisk") => 5,
+        (_, "/fapi/v1/adlQuantile") => 5,
+        (_, "/fapi/v1/commissionRate") => 20,
+        (_, "/fapi/v1/forceOrders") => if has_symbol { 20 } else { 50 },
+        (_, "/fapi/v1/apiTradingStatus") => if has_symbol { 1 } else { 10 },
+        (_, "/fapi/v1/countdownCancelAll") => 10,
+        ("GET", "/fapi/v1/positionSide/dual") => 30,
+        ("GET", "/fapi/v1/multiAssetsMargin") => 30,
+        ("POST", "/fapi/v1/order") | ("PUT", "/fapi/v1/order") | ("POST", "/fapi/v1/order/test") => 0,
+        _ => 1,
+    }
+}
+
+/// Number of orders a request counts against the `ORDERS` limits
+pub fn endpoint_order_count(method: &Method, endpoint: &str, params: &RequestParams) -> u32 {
+    match (method.as_str(), endpoint) {
+        ("POST", "/fapi/v1/order") | ("PUT", "/fapi/v1/order") => 1,
+        ("POST", "/fapi/v1/batchOrders") | ("PUT", "/fapi/v1/batchOrders") => params
+            .get("batchOrders")
+            .and_then(|orders| serde_json::from_str::<Vec<serde_json::Value>>(orders).ok())
+            .map(|orders| orders.len() as u32)
+            .unwrap_or(1),
+        _ => 0,
+    }
+}
+
+/// Parse a `Retry-After` header value (seconds)
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_reserve_delays_until_window_reset() {
+        let limiter = RateLimiter::with_windows(vec![RateLimitWindow::new(
+            RateLimitKind::RequestWeight,
+            60_000,
+            10,
+        )]);
+
+        assert_eq!(limiter.reserve(6, 0, 1_000).unwrap(), None);
+        assert_eq!(limiter.reserve(4, 0, 2_000).unwrap(), None);
+        // Window is full: wait until the minute rolls over
+        assert_eq!(
+            limiter.reserve(1, 0, 30_000).unwrap(),
+            Some(Duration::from_millis(30_000))
+        );
+        // New window
+        assert_eq!(limiter.reserve(1, 0, 60_000).unwrap(), None);
+    }
+
+    #[test]
+    fn test_reserve_rejects_beyond_max_wait() {
+        let limiter = RateLimiter::with_windows(vec![RateLimitWindow::new(
+            RateLimitKind::Orders,
+            10_000,
+            1,
+        )]);
+        limiter.set_max_wait(Duration::from_secs(1));
+
+        assert_eq!(limiter.reserve(1, 1, 0).unwrap(), None);
+        // Weight-only requests are not affected by the order window
+        assert_eq!(limiter.reserve(1, 0, 0).unwrap(), None);
+        assert!(matches!(
+            limiter.reserve(0, 1, 0),
+            Err(BinanceError::RateLimit { retry_after: Some(_) })
+        ));
+    }
+
+    #[test]
+    fn test_reserve_rejects_cost_above_window_limit() {
+        let limiter = RateLimiter::with_windows(vec![
+            RateLimitWindow::new(RateLimitKind::RequestWeight, 60_000, 10),
+            RateLimitWindow::new(RateLimitKind::Orders, 10_000, 5),
+        ]);
+
+        // Rejected at once rather than delayed forever, even in an empty window
+        assert!(matches!(limiter.reserve(11, 0, 0), Err(BinanceError::InvalidParameter(_))));
+        let err = limiter.reserve(1, 6, 0).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid parameter: Request order count of 6 exceeds the limit of 5 per 10000ms");
+        assert_eq!(limiter.reserve(10, 5, 0).unwrap(), None);
+
+        limiter.set_enabled(false);
+        assert_eq!(limiter.reserve(11, 6, 0).unwrap(), None);
+    }
+
+    #[test]
+    fn test_update_from_headers() {
+        let limiter = RateLimiter::new();
+        let mut headers = HeaderMap::new();
+        headers.insert("X-MBX-USED-WEIGHT-1M", HeaderValue::from_static("2399"));
+        headers.insert("X-MBX-ORDER-COUNT-10S", HeaderValue::from_static("7"));
+        headers.insert("X-MBX-ORDER-COUNT-1M", HeaderValue::from_static("12"));
+        limiter.update_from_headers(&headers, 5_000);
+
+        let windows = limiter.windows();
+        assert_eq!(windows[0].used, 2399);
+        assert_eq!(windows[1].used, 7);
+        assert_eq!(windows[2].used, 12);
+
+        assert_eq!(limiter.reserve(1, 0, 5_000).unwrap(), None);
+        assert!(limiter.reserve(1, 0, 5_000).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_server_reported_usage_near_u32_max() {
+        let limiter = RateLimiter::new();
+        let mut headers = HeaderMap::new();
+        headers.insert("X-MBX-USED-WEIGHT-1M", HeaderValue::from_str(&u32::MAX.to_string()).unwrap());
+        limiter.update_from_headers(&headers, 5_000);
+
+        // Waits for the window to reset instead of overflowing
+        assert_eq!(limiter.reserve(1, 0, 5_000).unwrap(), Some(Duration::from_millis(55_000)));
+        limiter.set_enabled(false);
+        assert_eq!(limiter.reserve(1, 0, 5_000).unwrap(), None);
+        assert_eq!(limiter.windows()[0].used, u32::MAX);
+    }
+
+    #[test]
+    fn test_block_for_retry_after() {
+        let limiter = RateLimiter::new();
+        limiter.block_for(Duration::from_secs(120), 0);
+        assert!(matches!(
+            limiter.reserve(1, 0, 1_000),
+            Err(BinanceError::RateLimit { .. })
+        ));
+        assert_eq!(limiter.reserve(1, 0, 120_000).unwrap(), None);
+    }
+
+    #[test]
+    fn test_zero_interval_windows_are_skipped() {
+        let limiter = RateLimiter::with_windows(vec![
+            RateLimitWindow::new(RateLimitKind::RequestWeight, 0, 10),
+            RateLimitWindow::new(RateLimitKind::Orders, 10_000, 1),
+        ]);
+        assert_eq!(limiter.windows().len(), 1);
+        assert_eq!(limiter.reserve(100, 0, 5_000).unwrap(), None);
+    }
+
+    #[test]
+    fn test_apply_exchange_limits() {
+        let limiter = RateLimiter::new();
+        limiter.apply_exchange_limits(&[
+            RateLimit {
+                rate_limit_type: "REQUEST_WEIGHT".to_string(),
+                interval: "MINUTE".to_string(),
+                interval_num: 1,
+                limit: 1200,
+            },
+            RateLimit {
+                rate_limit_type: "ORDERS".to_string(),
+                interval: "SECOND".to_string(),
+                interval_num: 10,
+                limit: 100,
+            },
+        ]);
+
+        let windows = limiter.windows();
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0].limit, 1200);
+        assert_eq!(windows[1].interval_ms, 10_000);
+        assert_eq!(windows[1].limit, 100);
+    }
+
+    #[test]
+    fn test_endpoint_weight() {
+        let mut params = RequestParams::new();
+        assert_eq!(endpoint_weight(&Method::GET, "/fapi/v1/ticker/24hr", &params), 40);
+        assert_eq!(endpoint_weight(&Method::GET, "/fapi/v1/depth", &params), 10);
+        params.insert("symbol".to_string(), "BTCUSDT".to_string());
+        params.insert("limit".to_string(), "1000".to_string());
+        assert_eq!(endpoint_weight(&Method::GET, "/fapi/v1/ticker/24hr", &params), 1);
+        assert_eq!(endpoint_weight(&Method::GET, "/fapi/v1/depth", &params), 20);
+        assert_eq!(endpoint_weight(&Method::GET, "/fapi/v1/klines", &params), 5);
+
+        let mut batch = RequestParams::new();
+        batch.insert("batchOrders".to_string(), "[{},{},{}]".to_string());
+        assert_eq!(endpoint_order_count(&Method::POST, "/fapi/v1/batchOrders", &batch), 3);
+        assert_eq!(endpoint_order_count(&Method::GET, "/fapi/v1/order", &batch), 0);
+    }
+
+    #[test]
+    fn test_parse_headers() {
+        assert_eq!(parse_interval_suffix("1m"), Some(60_000));
+        assert_eq!(parse_interval_suffix("10s"), Some(10_000));
+        assert_eq!(parse_interval_suffix("x"), None);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("Retry-After", HeaderValue::from_static("30"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(30)));
+    }
+}
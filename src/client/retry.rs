@@ -0,0 +1,187 @@
+use crate::error::{BinanceError, BinanceErrorCode};
+use reqwest::Method;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// Retry policy with exponential backoff for transient failures.
+///
+/// By default only safe cases are retried: transport errors on GET requests,
+/// 5xx responses and -1001 (disconnected). Order placement and amendment are
+/// never retried unless `retry_order_placement` is set, because a failed
+/// attempt may still have reached the matching engine.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between attempts
+    pub max_backoff: Duration,
+    /// Factor applied to the delay after each attempt
+    pub multiplier: f64,
+    /// Randomize each delay between half and the full backoff
+    pub jitter: bool,
+    /// Also retry `POST`/`PUT` on `/fapi/v1/order` and `/fapi/v1/batchOrders`
+    pub retry_order_placement: bool,
+}
+
+impl RetryPolicy {
+    /// Policy that never retries
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn retry_order_placement(mut self, retry_order_placement: bool) -> Self {
+        self.retry_order_placement = retry_order_placement;
+        self
+    }
+
+    /// Decide whether a failed attempt (1-based) should be retried
+    pub fn should_retry(&self, method: &Method, endpoint: &str, error: &BinanceError, attempt: u32) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+
+        if is_order_placement(method, endpoint) && !self.retry_order_placement {
+            return false;
+        }
+
+        match error {
+            BinanceError::Http(e) => *method == Method::GET && (e.is_timeout() || e.is_connect() || e.is_request()),
+            BinanceError::Server { .. } => true,
+            BinanceError::Api { code, .. } => *code == BinanceErrorCode::Disconnected,
+            _ => false,
+        }
+    }
+
+    /// Delay before the next attempt after `attempt` (1-based) failed
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exp = self.multiplier.max(1.0).powi(attempt.saturating_sub(1) as i32);
+        let backoff = self.initial_backoff.mul_f64(exp).min(self.max_backoff);
+
+        if self.jitter {
+            let half = backoff / 2;
+            half + half.mul_f64(random_unit())
+        } else {
+            backoff
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2.0,
+            jitter: true,
+            retry_order_placement: false,
+        }
+    }
+}
+
+/// Whether a request places or amends orders
+pub fn is_order_placement(method: &Method, endpoint: &str) -> bool {
+    (*method == Method::POST || *method == Method::PUT)
+        && matches!(endpoint, "/fapi/v1/order" | "/fapi/v1/batchOrders")
+}
+
+/// Random value in `[0, 1)` without pulling in an RNG dependency
+fn random_unit() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(crate::utils::get_timestamp());
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_error() -> BinanceError {
+        BinanceError::Server {
+            status: 503,
+            msg: "Service Unavailable".to_string(),
+            endpoint: None,
+        }
+    }
+
+    #[test]
+    fn test_default_retries_safe_cases() {
+        let policy = RetryPolicy::default();
+
+        assert!(policy.should_retry(&Method::GET, "/fapi/v1/depth", &server_error(), 1));
+        assert!(policy.should_retry(&Method::DELETE, "/fapi/v1/order", &server_error(), 1));
+
+        let disconnected = BinanceError::api(
+            -1001,
+            "Internal error; unable to process your request. Please try again.",
+        );
+        assert!(policy.should_retry(&Method::GET, "/fapi/v2/account", &disconnected, 2));
+        // Attempts exhausted
+        assert!(!policy.should_retry(&Method::GET, "/fapi/v2/account", &disconnected, 3));
+
+        let rejected = BinanceError::api(-2019, "Margin is insufficient.");
+        assert!(!policy.should_retry(&Method::GET, "/fapi/v2/account", &rejected, 1));
+        assert!(!policy.should_retry(
+            &Method::GET,
+            "/fapi/v1/depth",
+            &BinanceError::RateLimit { retry_after: None },
+            1
+        ));
+    }
+
+    #[test]
+    fn test_order_placement_not_retried_by_default() {
+        let policy = RetryPolicy::default();
+        assert!(!policy.should_retry(&Method::POST, "/fapi/v1/order", &server_error(), 1));
+        assert!(!policy.should_retry(&Method::POST, "/fapi/v1/batchOrders", &server_error(), 1));
+
+        let policy = policy.retry_order_placement(true);
+        assert!(policy.should_retry(&Method::POST, "/fapi/v1/order", &server_error(), 1));
+
+        assert!(!RetryPolicy::none().should_retry(&Method::GET, "/fapi/v1/depth", &server_error(), 1));
+    }
+
+    #[test]
+    fn test_backoff_delay() {
+        let policy = RetryPolicy::default()
+            .backoff(Duration::from_millis(100), Duration::from_millis(350))
+            .jitter(false);
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(350));
+
+        let policy = policy.jitter(true);
+        for attempt in 1..5 {
+            let delay = policy.delay(attempt);
+            assert!(delay >= Duration::from_millis(50));
+            assert!(delay <= Duration::from_millis(350));
+        }
+    }
+}
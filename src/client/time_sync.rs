@@ -0,0 +1,164 @@
+use crate::utils::get_timestamp;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Default interval between automatic server time re-syncs
+pub const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Largest `recvWindow` accepted by Binance (milliseconds)
+pub const MAX_RECV_WINDOW: u64 = 60_000;
+
+/// Tracks the offset between the local clock and Binance server time.
+///
+/// The state is shared between clones so every `HttpClient` derived from
+/// the same instance stamps requests with the same corrected clock.
+#[derive(Clone, Debug)]
+pub struct TimeSync {
+    inner: Arc<TimeSyncState>,
+}
+
+#[derive(Debug)]
+struct TimeSyncState {
+    enabled: AtomicBool,
+    interval_ms: AtomicU64,
+    offset_ms: AtomicI64,
+    round_trip_ms: AtomicU64,
+    last_sync: Mutex<Option<Instant>>,
+}
+
+impl TimeSync {
+    /// Create a disabled time sync with a zero offset
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(TimeSyncState {
+                enabled: AtomicBool::new(false),
+                interval_ms: AtomicU64::new(DEFAULT_SYNC_INTERVAL.as_millis() as u64),
+                offset_ms: AtomicI64::new(0),
+                round_trip_ms: AtomicU64::new(0),
+                last_sync: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Enable automatic syncing, re-syncing once `interval` has elapsed
+    pub fn enable(&self, interval: Duration) {
+        self.inner.interval_ms.store(interval.as_millis() as u64, Ordering::Relaxed);
+        self.inner.enabled.store(true, Ordering::Relaxed);
+    }
+
+    /// Disable automatic syncing; the last measured offset is kept
+    pub fn disable(&self) {
+        self.inner.enabled.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.inner.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Interval between automatic re-syncs
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.inner.interval_ms.load(Ordering::Relaxed))
+    }
+
+    /// Current offset in milliseconds (server time - local time)
+    pub fn offset_ms(&self) -> i64 {
+        self.inner.offset_ms.load(Ordering::Relaxed)
+    }
+
+    /// Round-trip time of the last sync request in milliseconds
+    pub fn round_trip_ms(&self) -> u64 {
+        self.inner.round_trip_ms.load(Ordering::Relaxed)
+    }
+
+    /// Local timestamp corrected by the measured offset
+    pub fn timestamp(&self) -> u64 {
+        (get_timestamp() as i64 + self.offset_ms()).max(0) as u64
+    }
+
+    /// Check whether a sync is due (enabled and never synced or stale)
+    pub fn needs_sync(&self) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+        match *self.inner.last_sync.lock().unwrap() {
+            Some(last_sync) => last_sync.elapsed() >= self.interval(),
+            None => true,
+        }
+    }
+
+    /// Record a measurement taken around a `/fapi/v1/time` call.
+    ///
+    /// `local_send` and `local_recv` are local millisecond timestamps taken
+    /// immediately before sending and after receiving the response. The
+    /// server is assumed to have stamped its reply halfway through the
+    /// round trip.
+    pub fn record(&self, local_send: u64, server_time: u64, local_recv: u64) -> i64 {
+        let (offset, round_trip) = compute_offset(local_send, server_time, local_recv);
+        self.inner.offset_ms.store(offset, Ordering::Relaxed);
+        self.inner.round_trip_ms.store(round_trip, Ordering::Relaxed);
+        *self.inner.last_sync.lock().unwrap() = Some(Instant::now());
+        offset
+    }
+
+    /// Force the next signed request to re-sync
+    pub fn invalidate(&self) {
+        *self.inner.last_sync.lock().unwrap() = None;
+    }
+}
+
+impl Default for TimeSync {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compute the clock offset and round-trip time for a sync measurement
+pub fn compute_offset(local_send: u64, server_time: u64, local_recv: u64) -> (i64, u64) {
+    let round_trip = local_recv.saturating_sub(local_send);
+    let midpoint = local_send + round_trip / 2;
+    (server_time as i64 - midpoint as i64, round_trip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_offset() {
+        // Server is 500ms ahead, 100ms round trip
+        assert_eq!(compute_offset(1_000, 1_550, 1_100), (500, 100));
+        // Server is behind
+        assert_eq!(compute_offset(1_000, 900, 1_000), (-100, 0));
+        // Clock went backwards during the request
+        assert_eq!(compute_offset(1_000, 1_000, 990), (0, 0));
+    }
+
+    #[test]
+    fn test_time_sync_state() {
+        let sync = TimeSync::new();
+        assert!(!sync.needs_sync());
+
+        sync.enable(Duration::from_secs(60));
+        assert!(sync.needs_sync());
+
+        sync.record(1_000, 2_050, 1_100);
+        assert_eq!(sync.offset_ms(), 1_000);
+        assert_eq!(sync.round_trip_ms(), 100);
+        assert!(!sync.needs_sync());
+
+        // Clones share state
+        let clone = sync.clone();
+        clone.invalidate();
+        assert!(sync.needs_sync());
+    }
+
+    #[test]
+    fn test_corrected_timestamp() {
+        let sync = TimeSync::new();
+        sync.record(0, 10_000, 0);
+        let local = get_timestamp();
+        let corrected = sync.timestamp();
+        assert!(corrected >= local + 10_000);
+    }
+}
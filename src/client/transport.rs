@@ -0,0 +1,249 @@
+use crate::error::{BinanceError, Result};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use reqwest::{Client, Method, StatusCode};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// A fully prepared HTTP request, with query string and body already encoded
+#[derive(Debug, Clone)]
+pub struct TransportRequest {
+    pub method: Method,
+    pub base_url: String,
+    pub endpoint: String,
+    /// URL-encoded query string, without the leading `?`
+    pub query: String,
+    /// URL-encoded form body
+    pub body: Option<String>,
+    pub headers: HeaderMap,
+}
+
+impl TransportRequest {
+    /// Full request URL including the query string
+    pub fn url(&self) -> String {
+        if self.query.is_empty() {
+            format!("{}{}", self.base_url, self.endpoint)
+        } else {
+            format!("{}{}?{}", self.base_url, self.endpoint, self.query)
+        }
+    }
+
+    /// Decoded query and body parameters, in the order they are sent
+    pub fn params(&self) -> Vec<(String, String)> {
+        let body = self.body.as_deref().unwrap_or_default();
+        url::form_urlencoded::parse(self.query.as_bytes())
+            .chain(url::form_urlencoded::parse(body.as_bytes()))
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect()
+    }
+
+    /// Value of a single parameter
+    pub fn param(&self, key: &str) -> Option<String> {
+        self.params().into_iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+}
+
+/// Raw HTTP response returned by a [`Transport`]
+#[derive(Debug, Clone)]
+pub struct TransportResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl TransportResponse {
+    /// Create a response with a status code and body
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Add a response header
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::from_str(value)) {
+            self.headers.insert(name, value);
+        }
+        self
+    }
+}
+
+/// Sends prepared requests on behalf of [`HttpClient`](crate::client::HttpClient).
+///
+/// The default implementation is [`ReqwestTransport`]. Tests can use
+/// [`MemoryTransport`] or a [`CassetteTransport`](crate::client::CassetteTransport)
+/// to run without network access.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse>;
+}
+
+/// Transport backed by a reqwest client
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Get the underlying reqwest client
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse> {
+        let mut builder = self
+            .client
+            .request(request.method.clone(), request.url())
+            .headers(request.headers);
+
+        if let Some(body) = request.body {
+            builder = builder
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(body);
+        }
+
+        let response = builder.send().await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.text().await?;
+
+        Ok(TransportResponse { status, headers, body })
+    }
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    routes: Vec<(Method, String, VecDeque<TransportResponse>)>,
+    requests: Vec<TransportRequest>,
+}
+
+/// In-memory transport that serves canned responses and records every request.
+///
+/// Responses queued for the same method and endpoint are returned in order;
+/// the last one keeps being returned once the queue is drained. Clones share
+/// their state, so a test can keep a handle after passing one to a client.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryTransport {
+    inner: Arc<Mutex<MemoryState>>,
+}
+
+impl MemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a response for `method endpoint`
+    pub fn push(&self, method: Method, endpoint: &str, response: TransportResponse) {
+        let mut state = self.inner.lock().unwrap();
+        match state
+            .routes
+            .iter_mut()
+            .find(|(m, e, _)| *m == method && e == endpoint)
+        {
+            Some((_, _, responses)) => responses.push_back(response),
+            None => state
+                .routes
+                .push((method, endpoint.to_string(), VecDeque::from([response]))),
+        }
+    }
+
+    /// Queue a response with a JSON body
+    pub fn push_json(&self, method: Method, endpoint: &str, status: u16, body: &str) {
+        self.push(
+            method,
+            endpoint,
+            TransportResponse::new(status, body).header("content-type", "application/json"),
+        );
+    }
+
+    /// Requests received so far
+    pub fn requests(&self) -> Vec<TransportRequest> {
+        self.inner.lock().unwrap().requests.clone()
+    }
+
+    /// Forget the recorded requests
+    pub fn clear_requests(&self) {
+        self.inner.lock().unwrap().requests.clear();
+    }
+}
+
+#[async_trait]
+impl Transport for MemoryTransport {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse> {
+        let mut state = self.inner.lock().unwrap();
+        let response = state
+            .routes
+            .iter_mut()
+            .find(|(m, e, _)| *m == request.method && *e == request.endpoint)
+            .and_then(|(_, _, responses)| {
+                if responses.len() > 1 {
+                    responses.pop_front()
+                } else {
+                    responses.front().cloned()
+                }
+            });
+
+        let response = response.ok_or_else(|| {
+            BinanceError::Unknown(format!(
+                "No response queued for {} {}",
+                request.method, request.endpoint
+            ))
+        })?;
+
+        state.requests.push(request);
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: Method, endpoint: &str, query: &str) -> TransportRequest {
+        TransportRequest {
+            method,
+            base_url: "http://localhost".to_string(),
+            endpoint: endpoint.to_string(),
+            query: query.to_string(),
+            body: None,
+            headers: HeaderMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_request_url_and_params() {
+        let mut req = request(Method::POST, "/fapi/v1/order", "symbol=BTCUSDT");
+        req.body = Some("side=BUY&price=1%2B2".to_string());
+        assert_eq!(req.url(), "http://localhost/fapi/v1/order?symbol=BTCUSDT");
+        assert_eq!(req.param("price").as_deref(), Some("1+2"));
+        assert_eq!(req.params().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_memory_transport() {
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::GET, "/fapi/v1/ping", 200, "{}");
+        transport.push_json(Method::GET, "/fapi/v1/time", 200, r#"{"serverTime":1}"#);
+        transport.push_json(Method::GET, "/fapi/v1/time", 200, r#"{"serverTime":2}"#);
+
+        let response = transport.send(request(Method::GET, "/fapi/v1/time", "")).await.unwrap();
+        assert_eq!(response.body, r#"{"serverTime":1}"#);
+        // Last response is repeated once the queue is drained
+        for _ in 0..2 {
+            let response = transport.send(request(Method::GET, "/fapi/v1/time", "")).await.unwrap();
+            assert_eq!(response.body, r#"{"serverTime":2}"#);
+        }
+
+        assert!(transport.send(request(Method::POST, "/fapi/v1/ping", "")).await.is_err());
+        assert_eq!(transport.requests().len(), 3);
+    }
+}
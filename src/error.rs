@@ -1,3 +1,8 @@
+#[cfg(feature = "decimal")]
+use crate::risk::RiskViolation;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -8,31 +13,154 @@ pub enum BinanceError {
     #[error("JSON parsing error: {0}")]
     Json(#[from] serde_json::Error),
 
+    /// Error returned by Binance with a `{code, msg}` body.
+    ///
+    /// `status` and `endpoint` are filled in for errors received over HTTP.
     #[error("API error: {code} - {msg}")]
-    Api { code: i32, msg: String },
+    Api {
+        code: BinanceErrorCode,
+        msg: String,
+        status: Option<u16>,
+        endpoint: Option<String>,
+    },
+
+    /// 5xx response without a Binance error body
+    #[error("Server error: HTTP {status} - {msg}")]
+    Server {
+        status: u16,
+        msg: String,
+        endpoint: Option<String>,
+    },
 
     #[error("Authentication error: {0}")]
     Authentication(String),
 
-    #[error("Rate limit exceeded")]
-    RateLimit,
+    /// Request rate limit hit (HTTP 429), or a request was held back locally
+    /// because it would exceed a limit
+    #[error("Rate limit exceeded{}", fmt_retry_after(.retry_after))]
+    RateLimit { retry_after: Option<Duration> },
+
+    /// IP banned for repeatedly violating rate limits (HTTP 418)
+    #[error("IP banned{}", fmt_retry_after(.retry_after))]
+    IpBanned { retry_after: Option<Duration> },
 
     #[error("Invalid parameter: {0}")]
     InvalidParameter(String),
 
+    /// Order held back by a local pre-trade risk check; nothing was sent
+    #[cfg(feature = "decimal")]
+    #[error("Risk check failed: {0}")]
+    RiskRejected(RiskViolation),
+
     #[error("WebSocket error: {0}")]
     WebSocket(String),
     
     #[error("Timeout error")]
     Timeout,
+
+    /// A batch request failed as a whole, shared by every entry it carried
+    #[error("Batch request failed: {0}")]
+    BatchRequest(Arc<BinanceError>),
     
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
 
+fn fmt_retry_after(retry_after: &Option<Duration>) -> String {
+    match retry_after {
+        Some(retry_after) => format!(", retry after {}s", retry_after.as_secs()),
+        None => String::new(),
+    }
+}
+
 pub type Result<T> = std::result::Result<T, BinanceError>;
 
-#[derive(Debug, serde::Deserialize)]
+impl BinanceError {
+    /// Create an API error from a raw code and message
+    pub fn api(code: i32, msg: impl Into<String>) -> Self {
+        BinanceError::Api {
+            code: BinanceErrorCode::from(code),
+            msg: msg.into(),
+            status: None,
+            endpoint: None,
+        }
+    }
+
+    /// Binance error code, if this is an API error
+    pub fn code(&self) -> Option<BinanceErrorCode> {
+        match self {
+            BinanceError::Api { code, .. } => Some(*code),
+            BinanceError::BatchRequest(err) => err.code(),
+            _ => None,
+        }
+    }
+
+    /// HTTP status of the failed response, if known
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            BinanceError::Api { status, .. } => *status,
+            BinanceError::Server { status, .. } => Some(*status),
+            BinanceError::RateLimit { .. } => Some(429),
+            BinanceError::IpBanned { .. } => Some(418),
+            BinanceError::Http(e) => e.status().map(|s| s.as_u16()),
+            BinanceError::BatchRequest(err) => err.status(),
+            _ => None,
+        }
+    }
+
+    /// Endpoint of the failed request, if known
+    pub fn endpoint(&self) -> Option<&str> {
+        match self {
+            BinanceError::Api { endpoint, .. } | BinanceError::Server { endpoint, .. } => endpoint.as_deref(),
+            BinanceError::BatchRequest(err) => err.endpoint(),
+            _ => None,
+        }
+    }
+
+    /// Whether the request may succeed if sent again later
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            BinanceError::Api { code, .. } => code.is_retryable(),
+            BinanceError::Server { .. } | BinanceError::RateLimit { .. } | BinanceError::Timeout => true,
+            BinanceError::Http(e) => e.is_timeout() || e.is_connect(),
+            BinanceError::BatchRequest(err) => err.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// Whether an order was rejected by the exchange
+    pub fn is_order_rejected(&self) -> bool {
+        matches!(self, BinanceError::Api { code, .. } if code.is_order_rejected())
+    }
+
+    /// Whether the request may have been executed even though it failed.
+    ///
+    /// True for timeouts, 5xx responses, unreadable responses and -1006/-1007,
+    /// where an order may or may not exist.
+    pub fn is_execution_unknown(&self) -> bool {
+        match self {
+            BinanceError::Api { code, .. } => {
+                matches!(code, BinanceErrorCode::UnexpectedResponse | BinanceErrorCode::Timeout)
+            }
+            BinanceError::Server { .. } | BinanceError::Timeout | BinanceError::Json(_) => true,
+            BinanceError::Http(e) => !e.is_connect() && !e.is_builder(),
+            BinanceError::BatchRequest(err) => err.is_execution_unknown(),
+            _ => false,
+        }
+    }
+
+    /// Whether the request failed because of invalid or unauthorized credentials
+    pub fn is_auth_failure(&self) -> bool {
+        match self {
+            BinanceError::Api { code, .. } => code.is_auth_failure(),
+            BinanceError::Authentication(_) => true,
+            BinanceError::BatchRequest(err) => err.is_auth_failure(),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
 pub struct ApiErrorResponse {
     pub code: i32,
     pub msg: String,
@@ -40,9 +168,380 @@ pub struct ApiErrorResponse {
 
 impl From<ApiErrorResponse> for BinanceError {
     fn from(err: ApiErrorResponse) -> Self {
-        BinanceError::Api {
-            code: err.code,
-            msg: err.msg,
+        BinanceError::api(err.code, err.msg)
+    }
+}
+
+macro_rules! error_codes {
+    ($($(#[$meta:meta])* $name:ident = $code:literal,)*) => {
+        /// Documented Binance USDⓈ-M futures error codes
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum BinanceErrorCode {
+            $($(#[$meta])* $name,)*
+            /// Code not covered by this enum
+            Unknown(i32),
+        }
+
+        impl BinanceErrorCode {
+            /// Raw numeric code
+            pub fn code(&self) -> i32 {
+                match self {
+                    $(BinanceErrorCode::$name => $code,)*
+                    BinanceErrorCode::Unknown(code) => *code,
+                }
+            }
+        }
+
+        impl From<i32> for BinanceErrorCode {
+            fn from(code: i32) -> Self {
+                match code {
+                    $($code => BinanceErrorCode::$name,)*
+                    code => BinanceErrorCode::Unknown(code),
+                }
+            }
         }
+    };
+}
+
+error_codes! {
+    // 10xx - General server or network issues
+    /// An unknown error occurred while processing the request
+    UnknownError = -1000,
+    /// Internal error; unable to process the request
+    Disconnected = -1001,
+    /// Not authorized to execute this request
+    Unauthorized = -1002,
+    /// Too many requests
+    TooManyRequests = -1003,
+    DuplicateIp = -1004,
+    NoSuchIp = -1005,
+    UnexpectedResponse = -1006,
+    /// Timeout waiting for the backend; execution status unknown
+    Timeout = -1007,
+    /// Request throttled by system-level protection
+    ServerBusy = -1008,
+    ErrorMessageReceived = -1010,
+    NonWhiteList = -1011,
+    InvalidMessage = -1013,
+    UnknownOrderComposition = -1014,
+    /// Too many new orders
+    TooManyOrders = -1015,
+    ServiceShuttingDown = -1016,
+    UnsupportedOperation = -1020,
+    /// Timestamp outside of recvWindow
+    InvalidTimestamp = -1021,
+    /// Signature for this request is not valid
+    InvalidSignature = -1022,
+    StartTimeGreaterThanEndTime = -1023,
+
+    // 11xx - Request issues
+    IllegalChars = -1100,
+    TooManyParameters = -1101,
+    MandatoryParamEmptyOrMalformed = -1102,
+    UnknownParam = -1103,
+    UnreadParameters = -1104,
+    ParamEmpty = -1105,
+    ParamNotRequired = -1106,
+    BadAsset = -1108,
+    BadAccount = -1109,
+    BadInstrumentType = -1110,
+    /// Precision is over the maximum defined for this asset
+    BadPrecision = -1111,
+    NoDepth = -1112,
+    WithdrawNotNegative = -1113,
+    TifNotRequired = -1114,
+    InvalidTif = -1115,
+    InvalidOrderType = -1116,
+    InvalidSide = -1117,
+    EmptyNewClOrdId = -1118,
+    EmptyOrgClOrdId = -1119,
+    BadInterval = -1120,
+    BadSymbol = -1121,
+    InvalidListenKey = -1125,
+    MoreThanXxHours = -1127,
+    OptionalParamsBadCombo = -1128,
+    InvalidParameter = -1130,
+    InvalidNewOrderRespType = -1136,
+
+    // 20xx - Processing issues
+    NewOrderRejected = -2010,
+    /// Cancel rejected, usually an unknown order
+    CancelRejected = -2011,
+    NoSuchOrder = -2013,
+    BadApiKeyFmt = -2014,
+    /// Invalid API key, IP, or permissions for action
+    RejectedMbxKey = -2015,
+    NoTradingWindow = -2016,
+    BalanceNotSufficient = -2018,
+    /// Margin is insufficient
+    MarginNotSufficient = -2019,
+    UnableToFill = -2020,
+    OrderWouldImmediatelyTrigger = -2021,
+    /// ReduceOnly order is rejected
+    ReduceOnlyReject = -2022,
+    UserInLiquidation = -2023,
+    PositionNotSufficient = -2024,
+    MaxOpenOrderExceeded = -2025,
+    ReduceOnlyOrderTypeNotSupported = -2026,
+    MaxLeverageRatio = -2027,
+    MinLeverageRatio = -2028,
+
+    // 40xx - Filters and other issues
+    InvalidOrderStatus = -4000,
+    PriceLessThanZero = -4001,
+    PriceGreaterThanMaxPrice = -4002,
+    QtyLessThanZero = -4003,
+    QtyLessThanMinQty = -4004,
+    QtyGreaterThanMaxQty = -4005,
+    StopPriceLessThanZero = -4006,
+    StopPriceGreaterThanMaxPrice = -4007,
+    TickSizeLessThanZero = -4008,
+    MaxPriceLessThanMinPrice = -4009,
+    MaxQtyLessThanMinQty = -4010,
+    StepSizeLessThanZero = -4011,
+    MaxNumOrdersLessThanZero = -4012,
+    PriceLessThanMinPrice = -4013,
+    PriceNotIncreasedByTickSize = -4014,
+    InvalidClOrdIdLen = -4015,
+    PriceHigherThanMultiplierUp = -4016,
+    MultiplierUpLessThanZero = -4017,
+    MultiplierDownLessThanZero = -4018,
+    CompositeScaleOverflow = -4019,
+    TargetStrategyInvalid = -4020,
+    InvalidDepthLimit = -4021,
+    WrongMarketStatus = -4022,
+    QtyNotIncreasedByStepSize = -4023,
+    PriceLowerThanMultiplierDown = -4024,
+    MultiplierDecimalLessThanZero = -4025,
+    CommissionInvalid = -4026,
+    InvalidAccountType = -4027,
+    InvalidLeverage = -4028,
+    InvalidTickSizePrecision = -4029,
+    InvalidStepSizePrecision = -4030,
+    InvalidWorkingType = -4031,
+    ExceedMaxCancelOrderSize = -4032,
+    InsuranceAccountNotFound = -4033,
+    InvalidBalanceType = -4044,
+    MaxStopOrderExceeded = -4045,
+    NoNeedToChangeMarginType = -4046,
+    ThereExistsOpenOrders = -4047,
+    ThereExistsQuantity = -4048,
+    AddIsolatedMarginReject = -4049,
+    CrossBalanceInsufficient = -4050,
+    IsolatedBalanceInsufficient = -4051,
+    NoNeedToChangeAutoAddMargin = -4052,
+    AutoAddCrossedMarginReject = -4053,
+    AddIsolatedMarginNoPositionReject = -4054,
+    AmountMustBePositive = -4055,
+    InvalidApiKeyType = -4056,
+    InvalidRsaPublicKey = -4057,
+    MaxPriceTooLarge = -4058,
+    NoNeedToChangePositionSide = -4059,
+    InvalidPositionSide = -4060,
+    /// Order's position side does not match the account's position mode
+    PositionSideNotMatch = -4061,
+    ReduceOnlyConflict = -4062,
+    PositionSideChangeExistsOpenOrders = -4067,
+    PositionSideChangeExistsQuantity = -4068,
+    InvalidBatchPlaceOrderSize = -4082,
+    PlaceBatchOrdersFail = -4083,
+    UpcomingMethod = -4084,
+    InvalidPriceSpreadThreshold = -4086,
+    ReduceOnlyOrderPermission = -4087,
+    NoPlaceOrderPermission = -4088,
+    InvalidContractType = -4104,
+    InvalidClientTranIdLen = -4114,
+    DuplicatedClientTranId = -4115,
+    ReduceOnlyMarginCheckFailed = -4118,
+    MarketOrderReject = -4131,
+    InvalidActivationPrice = -4135,
+    QuantityExistsWithClosePosition = -4137,
+    ReduceOnlyMustBeTrue = -4138,
+    OrderTypeCannotBeMkt = -4139,
+    InvalidOpeningPositionStatus = -4140,
+    SymbolAlreadyClosed = -4141,
+    StrategyInvalidTriggerPrice = -4142,
+    InvalidPair = -4144,
+    IsolatedLeverageRejectWithPosition = -4161,
+    /// Order's notional must be no smaller than the minimum notional
+    MinNotional = -4164,
+    InvalidTimeInterval = -4165,
+    IsolatedRejectWithJointMargin = -4167,
+    JointMarginRejectWithIsolated = -4168,
+    JointMarginRejectWithMb = -4169,
+    JointMarginRejectWithOpenOrder = -4170,
+    NoNeedToChangeJointMargin = -4171,
+    JointMarginRejectWithNegativeBalance = -4172,
+    PriceHigherThanStopMultiplierUp = -4183,
+    PriceLowerThanStopMultiplierDown = -4184,
+    CoolingOffPeriod = -4192,
+    AdjustLeverageKycFailed = -4202,
+    AdjustLeverageOneMonthFailed = -4203,
+    AdjustLeverageXDaysFailed = -4205,
+    AdjustLeverageKycLimit = -4206,
+    AdjustLeverageAccountSymbolFailed = -4208,
+    AdjustLeverageSymbolFailed = -4209,
+    StopPriceHigherThanPriceMultiplierLimit = -4210,
+    StopPriceLowerThanPriceMultiplierLimit = -4211,
+    TradingQuantitativeRule = -4400,
+    ComplianceRestriction = -4401,
+    ComplianceBlackSymbolRestriction = -4402,
+    AdjustLeverageComplianceFailed = -4403,
+
+    // 50xx - Order execution issues
+    FokOrderReject = -5021,
+    GtxOrderReject = -5022,
+    MoveOrderNotAllowedSymbolReason = -5024,
+    LimitOrderOnly = -5025,
+    ExceedMaximumModifyOrderLimit = -5026,
+    SameOrder = -5027,
+    /// Timestamp outside recvWindow when reaching the matching engine
+    MeRecvWindowReject = -5028,
+    InvalidPriceMatch = -5037,
+    UnsupportedOrderTypePriceMatch = -5038,
+    InvalidSelfTradePreventionMode = -5039,
+    FutureGoodTillDate = -5040,
+    BboOrderReject = -5041,
+}
+
+impl BinanceErrorCode {
+    /// Whether the request may succeed if sent again later.
+    ///
+    /// Note that -1007 (timeout) leaves the execution status unknown, so
+    /// order placement should be reconciled rather than blindly resent.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            BinanceErrorCode::Disconnected
+                | BinanceErrorCode::TooManyRequests
+                | BinanceErrorCode::Timeout
+                | BinanceErrorCode::ServerBusy
+                | BinanceErrorCode::InvalidTimestamp
+                | BinanceErrorCode::MeRecvWindowReject
+        )
+    }
+
+    /// Whether the code means the exchange rejected an order
+    pub fn is_order_rejected(&self) -> bool {
+        use BinanceErrorCode::*;
+        matches!(
+            self,
+            BadPrecision
+                | TifNotRequired
+                | InvalidTif
+                | InvalidOrderType
+                | InvalidSide
+                | NewOrderRejected
+                | BalanceNotSufficient
+                | MarginNotSufficient
+                | UnableToFill
+                | OrderWouldImmediatelyTrigger
+                | ReduceOnlyReject
+                | UserInLiquidation
+                | PositionNotSufficient
+                | MaxOpenOrderExceeded
+                | ReduceOnlyOrderTypeNotSupported
+                | MaxLeverageRatio
+                | MinLeverageRatio
+                | PriceLessThanZero
+                | PriceGreaterThanMaxPrice
+                | QtyLessThanZero
+                | QtyLessThanMinQty
+                | QtyGreaterThanMaxQty
+                | StopPriceLessThanZero
+                | StopPriceGreaterThanMaxPrice
+                | PriceLessThanMinPrice
+                | PriceNotIncreasedByTickSize
+                | InvalidClOrdIdLen
+                | PriceHigherThanMultiplierUp
+                | QtyNotIncreasedByStepSize
+                | PriceLowerThanMultiplierDown
+                | MaxStopOrderExceeded
+                | PositionSideNotMatch
+                | ReduceOnlyConflict
+                | ReduceOnlyOrderPermission
+                | NoPlaceOrderPermission
+                | ReduceOnlyMarginCheckFailed
+                | MarketOrderReject
+                | InvalidActivationPrice
+                | QuantityExistsWithClosePosition
+                | ReduceOnlyMustBeTrue
+                | OrderTypeCannotBeMkt
+                | InvalidOpeningPositionStatus
+                | SymbolAlreadyClosed
+                | StrategyInvalidTriggerPrice
+                | MinNotional
+                | PriceHigherThanStopMultiplierUp
+                | PriceLowerThanStopMultiplierDown
+                | CoolingOffPeriod
+                | StopPriceHigherThanPriceMultiplierLimit
+                | StopPriceLowerThanPriceMultiplierLimit
+                | TradingQuantitativeRule
+                | ComplianceRestriction
+                | ComplianceBlackSymbolRestriction
+                | FokOrderReject
+                | GtxOrderReject
+                | LimitOrderOnly
+                | InvalidPriceMatch
+                | UnsupportedOrderTypePriceMatch
+                | InvalidSelfTradePreventionMode
+                | FutureGoodTillDate
+                | BboOrderReject
+        )
+    }
+
+    /// Whether the code means the API key, signature or permissions were rejected
+    pub fn is_auth_failure(&self) -> bool {
+        matches!(
+            self,
+            BinanceErrorCode::Unauthorized
+                | BinanceErrorCode::InvalidSignature
+                | BinanceErrorCode::BadApiKeyFmt
+                | BinanceErrorCode::RejectedMbxKey
+                | BinanceErrorCode::InvalidApiKeyType
+                | BinanceErrorCode::InvalidRsaPublicKey
+        )
+    }
+}
+
+impl fmt::Display for BinanceErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_code_round_trip() {
+        assert_eq!(BinanceErrorCode::from(-2019), BinanceErrorCode::MarginNotSufficient);
+        assert_eq!(BinanceErrorCode::from(-4164), BinanceErrorCode::MinNotional);
+        assert_eq!(BinanceErrorCode::MinNotional.code(), -4164);
+        assert_eq!(BinanceErrorCode::from(-9999), BinanceErrorCode::Unknown(-9999));
+        assert_eq!(BinanceErrorCode::Unknown(-9999).code(), -9999);
+    }
+
+    #[test]
+    fn test_error_classification() {
+        assert!(BinanceError::api(-1021, "Timestamp outside recvWindow").is_retryable());
+        assert!(BinanceError::api(-2022, "ReduceOnly Order is rejected.").is_order_rejected());
+        assert!(!BinanceError::api(-2011, "Unknown order sent.").is_order_rejected());
+        assert!(BinanceError::api(-2015, "Invalid API-key, IP, or permissions for action.").is_auth_failure());
+        assert!(!BinanceError::api(-4164, "Order's notional must be no smaller than 5.0").is_retryable());
+        assert!(BinanceError::api(-1007, "Timeout waiting for response from backend server.").is_execution_unknown());
+        assert!(BinanceError::Timeout.is_execution_unknown());
+        assert!(!BinanceError::api(-2019, "Margin is insufficient.").is_execution_unknown());
+    }
+
+    #[test]
+    fn test_api_error_display() {
+        let err = BinanceError::from(ApiErrorResponse {
+            code: -2019,
+            msg: "Margin is insufficient.".to_string(),
+        });
+        assert_eq!(err.to_string(), "API error: -2019 - Margin is insufficient.");
+        assert_eq!(err.code(), Some(BinanceErrorCode::MarginNotSufficient));
     }
 }
@@ -0,0 +1,934 @@
+//! Execution algorithms that work a parent order through child orders.
+//!
+//! [`Twap`] spreads the parent over time, [`Iceberg`] shows one clip of it at
+//! a time; both run in a background task controlled by an [`ExecutionHandle`].
+
+use crate::api::trading::TradingApi;
+use crate::error::{BinanceError, BinanceErrorCode, Result};
+use crate::types::common::{NewOrderRespType, OrderSide, OrderType, PositionSide};
+use crate::types::decimal::{in_range, Decimal, RoundingStrategy};
+use crate::types::order_builder::LimitTimeInForce;
+use crate::types::trading::{CancelOrderRequest, NewOrderRequest, Order, QueryOrderRequest};
+use crate::validation::OrderValidator;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// Decimals kept in average fill prices
+const PRICE_SCALE: u32 = 8;
+/// Decimals kept when splitting the parent quantity, before rounding to the step size
+const QUANTITY_SCALE: u32 = 16;
+/// Decimals kept in slippage, in basis points
+const SLIPPAGE_SCALE: u32 = 4;
+
+/// Lifecycle of an execution algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionState {
+    Running,
+    Paused,
+    /// Schedule finished or the parent quantity was filled
+    Completed,
+    Canceled,
+    /// Stopped by an error, returned from [`ExecutionHandle::wait`]
+    Failed,
+}
+
+impl ExecutionState {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, ExecutionState::Completed | ExecutionState::Canceled | ExecutionState::Failed)
+    }
+}
+
+/// Progress of a parent order worked by [`Twap`] or [`Iceberg`]
+#[derive(Debug, Clone)]
+pub struct ExecutionProgress {
+    pub state: ExecutionState,
+    pub side: OrderSide,
+    pub target_qty: Decimal,
+    pub filled_qty: Decimal,
+    /// Volume-weighted average fill price, zero before the first fill
+    pub avg_price: Decimal,
+    /// Reference for slippage: the configured benchmark, else the first fill's price
+    pub benchmark_price: Option<Decimal>,
+    /// Number of child orders placed so far
+    pub child_orders: u32,
+}
+
+impl ExecutionProgress {
+    pub fn remaining_qty(&self) -> Result<Decimal> {
+        in_range(self.target_qty.checked_sub(self.filled_qty))
+    }
+
+    /// Slippage of the average fill price against the benchmark, in basis points.
+    ///
+    /// Positive means worse than the benchmark: paid more on a buy, received less on a sell.
+    pub fn slippage_bps(&self) -> Option<Decimal> {
+        let benchmark = self.benchmark_price?;
+        let avg_price = self.avg_price;
+        if avg_price.is_zero() {
+            return None;
+        }
+        let diff = match self.side {
+            OrderSide::Buy => avg_price.checked_sub(benchmark)?,
+            OrderSide::Sell => benchmark.checked_sub(avg_price)?,
+        };
+        div(diff.checked_mul(Decimal::new(10_000, 0))?, benchmark, SLIPPAGE_SCALE)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Run,
+    Pause,
+    Cancel,
+}
+
+/// Handle to a running [`Twap`] or [`Iceberg`].
+///
+/// Dropping the handle cancels the execution.
+pub struct ExecutionHandle {
+    control: watch::Sender<Control>,
+    progress: watch::Receiver<ExecutionProgress>,
+    task: JoinHandle<Result<()>>,
+}
+
+impl ExecutionHandle {
+    /// Stop placing child orders; a resting iceberg clip is cancelled
+    pub fn pause(&self) {
+        self.control.send_if_modified(|control| replace_if_running(control, Control::Pause));
+    }
+
+    pub fn resume(&self) {
+        self.control
+            .send_if_modified(|control| *control == Control::Pause && replace_if_running(control, Control::Run));
+    }
+
+    /// Cancel any resting child order and stop; use [`wait`](Self::wait) for the final progress
+    pub fn cancel(&self) {
+        let _ = self.control.send(Control::Cancel);
+    }
+
+    /// Latest progress
+    pub fn progress(&self) -> ExecutionProgress {
+        self.progress.borrow().clone()
+    }
+
+    /// Wait until the progress changes; `None` once the execution has finished
+    pub async fn changed(&mut self) -> Option<ExecutionProgress> {
+        self.progress.changed().await.ok()?;
+        Some(self.progress.borrow_and_update().clone())
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+
+    /// Wait for the execution to finish and return the final progress, or the error that stopped it
+    pub async fn wait(mut self) -> Result<ExecutionProgress> {
+        (&mut self.task)
+            .await
+            .map_err(|e| BinanceError::Unknown(format!("Execution task failed: {}", e)))??;
+        Ok(self.progress.borrow().clone())
+    }
+}
+
+fn replace_if_running(control: &mut Control, next: Control) -> bool {
+    if *control == Control::Cancel || *control == next {
+        return false;
+    }
+    *control = next;
+    true
+}
+
+/// Time-weighted execution: splits a parent quantity into `slices` child
+/// orders spaced evenly over `duration`.
+///
+/// Each slice brings the filled quantity up to the schedule, rounded down to
+/// the LOT_SIZE step, so slices too small to trade and unfilled IOC children
+/// roll into later ones. A slice above the lot size filter's maxQty is sent
+/// as several children of at most maxQty. Every child is checked against the
+/// symbol filters before it is sent; one below MIN_NOTIONAL also rolls into
+/// the next slice, and fails the execution on the last one. MARKET children
+/// are only checked against MIN_NOTIONAL once a reference price is known:
+/// the [`benchmark_price`](Self::benchmark_price) or else the first fill's.
+/// Children are MARKET orders, or LIMIT IOC with
+/// [`limit_price`](Self::limit_price). Pausing holds the schedule.
+#[derive(Debug, Clone)]
+pub struct Twap {
+    order: ChildTemplate,
+    quantity: Decimal,
+    duration: Duration,
+    slices: u32,
+    limit_price: Option<Decimal>,
+}
+
+impl Twap {
+    pub fn new(symbol: impl Into<String>, side: OrderSide, quantity: Decimal, duration: Duration, slices: u32) -> Self {
+        Self {
+            order: ChildTemplate::new(symbol.into(), side),
+            quantity,
+            duration,
+            slices,
+            limit_price: None,
+        }
+    }
+
+    /// Send children as LIMIT IOC at this price instead of MARKET
+    pub fn limit_price(mut self, price: Decimal) -> Self {
+        self.limit_price = Some(price);
+        self
+    }
+
+    pub fn position_side(mut self, position_side: PositionSide) -> Self {
+        self.order.position_side = Some(position_side);
+        self
+    }
+
+    pub fn reduce_only(mut self, reduce_only: bool) -> Self {
+        self.order.reduce_only = Some(reduce_only);
+        self
+    }
+
+    /// Price slippage is measured against, e.g. the mark price at the start
+    pub fn benchmark_price(mut self, price: Decimal) -> Self {
+        self.order.benchmark_price = Some(price);
+        self
+    }
+
+    /// How often a child that is not final yet is queried (default 1 second)
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.order.poll_interval = poll_interval;
+        self
+    }
+
+    /// Start working the order in a background task. Must be called within a Tokio runtime.
+    pub fn spawn(self, trading: TradingApi, validator: &OrderValidator) -> Result<ExecutionHandle> {
+        if self.slices == 0 {
+            return Err(BinanceError::InvalidParameter("TWAP needs at least one slice".to_string()));
+        }
+        let limit = self.limit_price.map(|price| (price, LimitTimeInForce::Ioc));
+        let order_type = if limit.is_some() { OrderType::Limit } else { OrderType::Market };
+        let lot = Lot::new(validator, &self.order.symbol, &self.quantity, order_type)?;
+        // The schedule's children, not the parent, are what the filters apply to
+        let slice = lot.round_down(div(lot.total, Decimal::new(self.slices, 0), QUANTITY_SCALE).unwrap_or(lot.total))?;
+        let first = self.order.child(lot.clamp(slice.max(lot.min)), limit);
+        lot.check(&first, self.limit_price.or(self.order.benchmark_price).as_ref())?;
+
+        let interval = self.duration / self.slices;
+        let (runner, control, progress) = Runner::start(trading, self.order, &self.quantity);
+        let task = tokio::spawn(runner.run_twap(lot, self.slices, interval, limit));
+        Ok(ExecutionHandle { control, progress, task })
+    }
+}
+
+/// Iceberg execution: keeps one LIMIT clip of at most `clip` resting at
+/// `price` and places the next one when it fills, until the parent quantity
+/// is done. Pausing cancels the resting clip; resuming places a new one.
+/// A clip the exchange ends at once without any fill (expired or rejected)
+/// fails the execution instead of being placed again.
+#[derive(Debug, Clone)]
+pub struct Iceberg {
+    order: ChildTemplate,
+    quantity: Decimal,
+    price: Decimal,
+    clip: Decimal,
+}
+
+impl Iceberg {
+    pub fn new(symbol: impl Into<String>, side: OrderSide, quantity: Decimal, price: Decimal, clip: Decimal) -> Self {
+        Self {
+            order: ChildTemplate::new(symbol.into(), side),
+            quantity,
+            price,
+            clip,
+        }
+    }
+
+    pub fn position_side(mut self, position_side: PositionSide) -> Self {
+        self.order.position_side = Some(position_side);
+        self
+    }
+
+    pub fn reduce_only(mut self, reduce_only: bool) -> Self {
+        self.order.reduce_only = Some(reduce_only);
+        self
+    }
+
+    /// Price slippage is measured against; defaults to the first fill's price
+    pub fn benchmark_price(mut self, price: Decimal) -> Self {
+        self.order.benchmark_price = Some(price);
+        self
+    }
+
+    /// How often the resting clip is queried for fills (default 1 second)
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.order.poll_interval = poll_interval;
+        self
+    }
+
+    /// Start working the order in a background task. Must be called within a Tokio runtime.
+    pub fn spawn(self, trading: TradingApi, validator: &OrderValidator) -> Result<ExecutionHandle> {
+        let lot = Lot::new(validator, &self.order.symbol, &self.quantity, OrderType::Limit)?;
+        let clip = lot.tradable(&self.clip, "Clip")?;
+        if lot.clamp(clip) != clip {
+            return Err(BinanceError::InvalidParameter(format!(
+                "Clip {} is above the max quantity {}",
+                self.clip,
+                lot.max.unwrap_or(clip)
+            )));
+        }
+        let first = self.order.child(self.clip, Some((self.price, LimitTimeInForce::Gtc)));
+        lot.check(&first, None)?;
+
+        let (runner, control, progress) = Runner::start(trading, self.order, &self.quantity);
+        let task = tokio::spawn(runner.run_iceberg(lot, clip, self.price));
+        Ok(ExecutionHandle { control, progress, task })
+    }
+}
+
+/// Fields shared by every child order
+#[derive(Debug, Clone)]
+struct ChildTemplate {
+    symbol: String,
+    side: OrderSide,
+    position_side: Option<PositionSide>,
+    reduce_only: Option<bool>,
+    benchmark_price: Option<Decimal>,
+    poll_interval: Duration,
+}
+
+impl ChildTemplate {
+    fn new(symbol: String, side: OrderSide) -> Self {
+        Self {
+            symbol,
+            side,
+            position_side: None,
+            reduce_only: None,
+            benchmark_price: None,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// A MARKET child, or a LIMIT child at the given price and time in force
+    fn child(&self, quantity: Decimal, limit: Option<(Decimal, LimitTimeInForce)>) -> NewOrderRequest {
+        let symbol = self.symbol.clone();
+        let mut order: NewOrderRequest = match limit {
+            Some((price, time_in_force)) => NewOrderRequest::limit(symbol, self.side, quantity, price, time_in_force)
+                .new_order_resp_type(NewOrderRespType::Result)
+                .into(),
+            None => NewOrderRequest::market(symbol, self.side, quantity)
+                .new_order_resp_type(NewOrderRespType::Result)
+                .into(),
+        };
+        order.position_side = self.position_side;
+        order.reduce_only = self.reduce_only;
+        order
+    }
+}
+
+/// Parent quantity and the symbol's lot size filter
+struct Lot {
+    validator: OrderValidator,
+    order_type: OrderType,
+    total: Decimal,
+    min: Decimal,
+    max: Option<Decimal>,
+}
+
+impl Lot {
+    fn new(validator: &OrderValidator, symbol: &str, quantity: &Decimal, order_type: OrderType) -> Result<Self> {
+        if validator.symbol() != symbol {
+            return Err(BinanceError::InvalidParameter(format!(
+                "Validator is for {}, not {}",
+                validator.symbol(),
+                symbol
+            )));
+        }
+        let mut lot = Self {
+            validator: validator.clone(),
+            order_type,
+            total: Decimal::ZERO,
+            min: validator.min_quantity(order_type).unwrap_or(Decimal::ZERO),
+            max: validator.max_quantity(order_type),
+        };
+        lot.total = lot.tradable(quantity, "Quantity")?;
+        Ok(lot)
+    }
+
+    /// Check that `quantity` can be sent as is, without rounding
+    fn tradable(&self, quantity: &Decimal, name: &str) -> Result<Decimal> {
+        let value = *quantity;
+        if value.is_zero() || value < self.min || self.round_down(value)? != value {
+            return Err(BinanceError::InvalidParameter(format!(
+                "{} {} must be a multiple of the step size and at least {}",
+                name, quantity, self.min
+            )));
+        }
+        Ok(value)
+    }
+
+    fn round_down(&self, quantity: Decimal) -> Result<Decimal> {
+        self.validator.round_quantity(&quantity, self.order_type)
+    }
+
+    /// Whether `quantity` is large enough to send
+    fn is_tradable(&self, quantity: Decimal) -> bool {
+        !quantity.is_zero() && !quantity.is_negative() && quantity >= self.min
+    }
+
+    /// Cap a child quantity at maxQty
+    fn clamp(&self, quantity: Decimal) -> Decimal {
+        self.max.map_or(quantity, |max| quantity.min(max))
+    }
+
+    /// Check a child order against the symbol filters; `reference` prices MARKET children for MIN_NOTIONAL
+    fn check(&self, child: &NewOrderRequest, reference: Option<&Decimal>) -> Result<()> {
+        self.validator.validate(child, reference)
+    }
+}
+
+/// State of one running execution
+struct Runner {
+    trading: TradingApi,
+    order: ChildTemplate,
+    filled: Decimal,
+    notional: Decimal,
+    progress: watch::Sender<ExecutionProgress>,
+    control: watch::Receiver<Control>,
+}
+
+impl Runner {
+    fn start(
+        trading: TradingApi,
+        order: ChildTemplate,
+        quantity: &Decimal,
+    ) -> (Runner, watch::Sender<Control>, watch::Receiver<ExecutionProgress>) {
+        let (control_tx, control_rx) = watch::channel(Control::Run);
+        let (progress_tx, progress_rx) = watch::channel(ExecutionProgress {
+            state: ExecutionState::Running,
+            side: order.side,
+            target_qty: *quantity,
+            filled_qty: Decimal::ZERO,
+            avg_price: Decimal::ZERO,
+            benchmark_price: order.benchmark_price,
+            child_orders: 0,
+        });
+
+        let runner = Runner {
+            trading,
+            order,
+            filled: Decimal::ZERO,
+            notional: Decimal::ZERO,
+            progress: progress_tx,
+            control: control_rx,
+        };
+        (runner, control_tx, progress_rx)
+    }
+
+    async fn run_twap(
+        mut self,
+        lot: Lot,
+        slices: u32,
+        interval: Duration,
+        limit: Option<(Decimal, LimitTimeInForce)>,
+    ) -> Result<()> {
+        let result = self.twap(&lot, slices, interval, limit).await;
+        self.finish(result)
+    }
+
+    async fn twap(
+        &mut self,
+        lot: &Lot,
+        slices: u32,
+        interval: Duration,
+        limit: Option<(Decimal, LimitTimeInForce)>,
+    ) -> Result<ExecutionState> {
+        let slice_count = Decimal::new(slices, 0);
+
+        for slice in 1..=slices {
+            if self.wait_while_paused().await == Control::Cancel {
+                return Ok(ExecutionState::Canceled);
+            }
+
+            // Catch up with the schedule: slice/slices of the total should be filled after this child
+            let target = if slice == slices {
+                lot.total
+            } else {
+                let scheduled = in_range(lot.total.checked_mul(Decimal::new(slice, 0)))?;
+                div(scheduled, slice_count, QUANTITY_SCALE).unwrap_or(lot.total)
+            };
+            let mut quantity = lot.round_down(in_range(target.checked_sub(self.filled))?)?;
+
+            // Above maxQty the slice goes out as several children
+            while lot.is_tradable(quantity) {
+                let size = lot.clamp(quantity);
+                let child = self.order.child(size, limit);
+                let reference = limit.map(|(price, _)| price).or(self.progress.borrow().benchmark_price);
+                match lot.check(&child, reference.as_ref()) {
+                    Ok(()) => {}
+                    // Too small a notional waits for the next slice to add to it
+                    Err(e) if slice < slices && e.code() == Some(BinanceErrorCode::MinNotional) => break,
+                    Err(e) => return Err(e),
+                }
+                let placed = self.place(child).await?;
+                self.await_final(placed).await?;
+
+                quantity = in_range(quantity.checked_sub(size))?;
+                if lot.is_tradable(quantity) && self.wait_while_paused().await == Control::Cancel {
+                    return Ok(ExecutionState::Canceled);
+                }
+            }
+
+            if slice < slices && self.sleep(interval).await == Control::Cancel {
+                return Ok(ExecutionState::Canceled);
+            }
+        }
+        Ok(ExecutionState::Completed)
+    }
+
+    async fn run_iceberg(mut self, lot: Lot, clip: Decimal, price: Decimal) -> Result<()> {
+        let mut resting = None;
+        let result = self.iceberg(&lot, clip, &price, &mut resting).await;
+        // Never leave a clip behind, whatever stopped the execution
+        if let Some(order) = resting {
+            if let Err(e) = self.cancel_child(&order).await {
+                return self.finish(Err(e));
+            }
+        }
+        self.finish(result)
+    }
+
+    async fn iceberg(
+        &mut self,
+        lot: &Lot,
+        clip: Decimal,
+        price: &Decimal,
+        resting: &mut Option<Order>,
+    ) -> Result<ExecutionState> {
+        loop {
+            let remaining = in_range(lot.total.checked_sub(self.filled))?;
+            if !lot.is_tradable(remaining) {
+                return Ok(ExecutionState::Completed);
+            }
+
+            let control = *self.control.borrow();
+            match control {
+                Control::Run => {}
+                Control::Pause => {
+                    if let Some(order) = resting.clone() {
+                        self.cancel_child(&order).await?;
+                        *resting = None;
+                    }
+                    if self.wait_while_paused().await == Control::Cancel {
+                        return Ok(ExecutionState::Canceled);
+                    }
+                    continue;
+                }
+                Control::Cancel => return Ok(ExecutionState::Canceled),
+            }
+
+            if resting.is_none() {
+                let child = self.order.child(clip.min(remaining), Some((*price, LimitTimeInForce::Gtc)));
+                lot.check(&child, None)?;
+                let placed = self.place(child).await?;
+                if placed.status.is_final() {
+                    // Re-placing a clip the exchange expires or rejects outright would only loop
+                    if placed.executed_qty.is_zero() {
+                        return Err(BinanceError::Unknown(format!(
+                            "Iceberg clip {} ended {:?} without a fill",
+                            placed.order_id, placed.status
+                        )));
+                    }
+                    continue;
+                }
+                *resting = Some(placed);
+            }
+
+            match self.sleep(self.order.poll_interval).await {
+                Control::Run => {}
+                Control::Pause => continue,
+                Control::Cancel => return Ok(ExecutionState::Canceled),
+            }
+
+            if let Some(order) = resting.clone() {
+                let updated = self.query(&order).await?;
+                self.record(&order, &updated)?;
+                // A filled or externally cancelled clip is replaced on the next pass
+                *resting = updated.status.is_open().then_some(updated);
+            }
+        }
+    }
+
+    /// Place a child order and count its immediate fills
+    async fn place(&mut self, child: NewOrderRequest) -> Result<Order> {
+        let order = self.trading.new_order(child).await?;
+        self.progress.send_modify(|progress| progress.child_orders += 1);
+        self.record_fill(order.executed_qty, quote(&order)?)?;
+        Ok(order)
+    }
+
+    /// Poll a child until it is final, counting its fills
+    async fn await_final(&mut self, mut order: Order) -> Result<()> {
+        while order.status.is_open() {
+            tokio::time::sleep(self.order.poll_interval).await;
+            let updated = self.query(&order).await?;
+            self.record(&order, &updated)?;
+            order = updated;
+        }
+        Ok(())
+    }
+
+    async fn query(&self, order: &Order) -> Result<Order> {
+        let query = QueryOrderRequest::new(order.symbol.clone()).order_id(order.order_id);
+        self.trading.query_order(query).await
+    }
+
+    /// Cancel a resting child and count any fills it got before the cancel.
+    ///
+    /// Fails unless the child is known to be off the book.
+    async fn cancel_child(&mut self, order: &Order) -> Result<()> {
+        let cancel = CancelOrderRequest::new(order.symbol.clone()).order_id(order.order_id);
+        let last = match self.trading.cancel_order(cancel).await {
+            Ok(canceled) => canceled,
+            // Unknown order: most likely filled in the meantime
+            Err(e) if matches!(e.code(), Some(BinanceErrorCode::CancelRejected | BinanceErrorCode::NoSuchOrder)) => {
+                let last = self.query(order).await?;
+                if last.status.is_open() {
+                    return Err(BinanceError::Unknown(format!(
+                        "Child order {} is still {:?} after its cancel was rejected",
+                        order.order_id, last.status
+                    )));
+                }
+                last
+            }
+            Err(e) => return Err(e),
+        };
+        self.record(order, &last)
+    }
+
+    /// Count the fills between two snapshots of the same child
+    fn record(&mut self, before: &Order, after: &Order) -> Result<()> {
+        let quantity = in_range(after.executed_qty.checked_sub(before.executed_qty))?;
+        let notional = in_range(quote(after)?.checked_sub(quote(before)?))?;
+        self.record_fill(quantity, notional)
+    }
+
+    fn record_fill(&mut self, quantity: Decimal, notional: Decimal) -> Result<()> {
+        if quantity.is_zero() || quantity.is_negative() {
+            return Ok(());
+        }
+        let filled = in_range(self.filled.checked_add(quantity))?;
+        let total_notional = in_range(self.notional.checked_add(notional))?;
+        let avg_price = div(total_notional, filled, PRICE_SCALE).unwrap_or(Decimal::ZERO);
+        let fill_price = div(notional, quantity, PRICE_SCALE).unwrap_or(Decimal::ZERO);
+        self.filled = filled;
+        self.notional = total_notional;
+
+        self.progress.send_modify(|progress| {
+            progress.filled_qty = filled;
+            progress.avg_price = avg_price;
+            progress.benchmark_price.get_or_insert(fill_price);
+        });
+        Ok(())
+    }
+
+    /// Block while paused; returns `Run` to go on or `Cancel`
+    async fn wait_while_paused(&mut self) -> Control {
+        loop {
+            let control = *self.control.borrow_and_update();
+            if control != Control::Pause {
+                self.set_state(ExecutionState::Running);
+                return control;
+            }
+            self.set_state(ExecutionState::Paused);
+            if self.control.changed().await.is_err() {
+                // The handle was dropped
+                return Control::Cancel;
+            }
+        }
+    }
+
+    /// Sleep for `duration`, waking early on pause or cancel
+    async fn sleep(&mut self, duration: Duration) -> Control {
+        tokio::select! {
+            _ = tokio::time::sleep(duration) => *self.control.borrow(),
+            changed = self.control.changed() => match changed {
+                Ok(()) => *self.control.borrow(),
+                // The handle was dropped
+                Err(_) => Control::Cancel,
+            },
+        }
+    }
+
+    fn set_state(&self, state: ExecutionState) {
+        self.progress.send_if_modified(|progress| {
+            let changed = progress.state != state;
+            progress.state = state;
+            changed
+        });
+    }
+
+    fn finish(&self, result: Result<ExecutionState>) -> Result<()> {
+        match result {
+            Ok(state) => {
+                self.set_state(state);
+                Ok(())
+            }
+            Err(e) => {
+                self.set_state(ExecutionState::Failed);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Quote quantity filled by an order, from `cumQuote` or else `executedQty * avgPrice`
+fn quote(order: &Order) -> Result<Decimal> {
+    if order.cum_quote.is_zero() {
+        in_range(order.executed_qty.checked_mul(order.avg_price))
+    } else {
+        Ok(order.cum_quote)
+    }
+}
+
+/// `a / b` with at most `scale` decimals, halves rounded away from zero; `None` if `b` is zero
+fn div(a: Decimal, b: Decimal, scale: u32) -> Option<Decimal> {
+    let quotient = a.checked_div(b)?;
+    Some(quotient.round_dp_with_strategy(scale, RoundingStrategy::MidpointAwayFromZero).normalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::{self, trading_api};
+    use crate::client::MemoryTransport;
+    use crate::types::market::SymbolInfo;
+    use reqwest::Method;
+    use serde_json::json;
+
+    fn validator() -> OrderValidator {
+        let symbol: SymbolInfo = serde_json::from_str(
+            r#"{
+                "symbol": "BTCUSDT", "status": "TRADING", "baseAsset": "BTC", "quoteAsset": "USDT",
+                "marginAsset": "USDT", "pricePrecision": 2, "quantityPrecision": 3,
+                "baseAssetPrecision": 8, "quotePrecision": 8,
+                "filters": [
+                    {"minPrice":"0.10","maxPrice":"1000000","filterType":"PRICE_FILTER","tickSize":"0.10"},
+                    {"stepSize":"0.001","filterType":"LOT_SIZE","maxQty":"1000","minQty":"0.001"},
+                    {"stepSize":"0.001","filterType":"MARKET_LOT_SIZE","maxQty":"120","minQty":"0.001"},
+                    {"notional":"50","filterType":"MIN_NOTIONAL"}
+                ],
+                "orderTypes": ["LIMIT", "MARKET"], "timeInForce": ["GTC", "IOC"]
+            }"#,
+        )
+        .unwrap();
+        symbol.validator()
+    }
+
+    fn order_json(status: &str, executed_qty: &str, cum_quote: &str) -> String {
+        test_support::order_json(json!({
+            "orderId": 7, "status": status, "clientOrderId": "child", "price": "30000", "origQty": "0.1",
+            "executedQty": executed_qty, "cumQuote": cum_quote, "side": "BUY"
+        }))
+    }
+
+    fn sent_quantities(transport: &MemoryTransport) -> Vec<String> {
+        transport
+            .requests()
+            .iter()
+            .filter(|r| r.method == Method::POST)
+            .filter_map(|r| r.param("quantity"))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_twap_slices_on_lot_size_grid() {
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::POST, "/fapi/v1/order", 200, &order_json("FILLED", "0.003", "90.03"));
+        transport.push_json(Method::POST, "/fapi/v1/order", 200, &order_json("FILLED", "0.003", "90.06"));
+        transport.push_json(Method::POST, "/fapi/v1/order", 200, &order_json("FILLED", "0.004", "120.12"));
+
+        let progress = Twap::new("BTCUSDT", OrderSide::Buy, "0.01".parse().unwrap(), Duration::from_millis(30), 3)
+            .benchmark_price("30000".parse().unwrap())
+            .spawn(trading_api(&transport), &validator())
+            .unwrap()
+            .wait()
+            .await
+            .unwrap();
+
+        // 0.01 / 3 rounded down to 0.001 steps, the last slice takes the rest
+        assert_eq!(sent_quantities(&transport), vec!["0.003", "0.003", "0.004"]);
+        assert_eq!(progress.state, ExecutionState::Completed);
+        assert_eq!(progress.child_orders, 3);
+        assert_eq!(progress.filled_qty.to_string(), "0.010");
+        assert_eq!(progress.avg_price.to_string(), "30021");
+        assert_eq!(progress.slippage_bps().unwrap().to_string(), "7");
+        assert_eq!(transport.requests()[0].param("type").as_deref(), Some("MARKET"));
+    }
+
+    #[tokio::test]
+    async fn test_twap_splits_slices_above_max_qty() {
+        let transport = MemoryTransport::new();
+        for (executed_qty, cum_quote) in [("120", "3600000"), ("5", "150000"), ("120", "3600000"), ("5", "150000")] {
+            transport.push_json(Method::POST, "/fapi/v1/order", 200, &order_json("FILLED", executed_qty, cum_quote));
+        }
+
+        // Each slice of 125 is above MARKET_LOT_SIZE maxQty 120; the parent would fail it outright
+        let progress = Twap::new("BTCUSDT", OrderSide::Buy, "250".parse().unwrap(), Duration::from_millis(10), 2)
+            .spawn(trading_api(&transport), &validator())
+            .unwrap()
+            .wait()
+            .await
+            .unwrap();
+
+        assert_eq!(sent_quantities(&transport), vec!["120", "5.000", "120", "5.000"]);
+        assert_eq!(progress.state, ExecutionState::Completed);
+        assert_eq!(progress.child_orders, 4);
+        assert_eq!(progress.filled_qty.to_string(), "250");
+    }
+
+    #[test]
+    fn test_twap_checks_children_against_min_notional() {
+        let transport = MemoryTransport::new();
+        // 0.004 at 30000 clears MIN_NOTIONAL 50, each 0.001 slice does not
+        let twap = Twap::new("BTCUSDT", OrderSide::Buy, "0.004".parse().unwrap(), Duration::from_secs(60), 4)
+            .limit_price("30000".parse().unwrap());
+        let err = twap.spawn(trading_api(&transport), &validator()).err().unwrap();
+        assert_eq!(err.code(), Some(BinanceErrorCode::MinNotional));
+        assert!(transport.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_iceberg_replenishes_clip_on_fill() {
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::POST, "/fapi/v1/order", 200, &order_json("NEW", "0", "0"));
+        transport.push_json(Method::GET, "/fapi/v1/order", 200, &order_json("FILLED", "0.1", "3000"));
+        transport.push_json(Method::GET, "/fapi/v1/order", 200, &order_json("FILLED", "0.1", "3000"));
+        transport.push_json(Method::GET, "/fapi/v1/order", 200, &order_json("FILLED", "0.05", "1500"));
+
+        let iceberg = Iceberg::new(
+            "BTCUSDT",
+            OrderSide::Buy,
+            "0.25".parse().unwrap(),
+            "30000".parse().unwrap(),
+            "0.1".parse().unwrap(),
+        );
+        let progress = iceberg
+            .poll_interval(Duration::from_millis(1))
+            .spawn(trading_api(&transport), &validator())
+            .unwrap()
+            .wait()
+            .await
+            .unwrap();
+
+        assert_eq!(sent_quantities(&transport), vec!["0.1", "0.1", "0.05"]);
+        assert_eq!(progress.state, ExecutionState::Completed);
+        assert_eq!(progress.filled_qty.to_string(), "0.25");
+        assert_eq!(progress.avg_price.to_string(), "30000");
+    }
+
+    #[tokio::test]
+    async fn test_iceberg_fails_on_clip_final_without_fill() {
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::POST, "/fapi/v1/order", 200, &order_json("EXPIRED", "0", "0"));
+
+        let iceberg = Iceberg::new(
+            "BTCUSDT",
+            OrderSide::Buy,
+            "1".parse().unwrap(),
+            "30000".parse().unwrap(),
+            "0.1".parse().unwrap(),
+        );
+        let mut handle = iceberg.spawn(trading_api(&transport), &validator()).unwrap();
+        while handle.changed().await.is_some() {}
+
+        assert_eq!(handle.progress().state, ExecutionState::Failed);
+        assert!(handle.wait().await.is_err());
+        assert_eq!(sent_quantities(&transport), vec!["0.1"]);
+    }
+
+    #[tokio::test]
+    async fn test_iceberg_pause_cancels_clip() {
+        let transport = MemoryTransport::new();
+        transport.push_json(Method::POST, "/fapi/v1/order", 200, &order_json("NEW", "0", "0"));
+        transport.push_json(Method::GET, "/fapi/v1/order", 200, &order_json("NEW", "0", "0"));
+        transport.push_json(Method::DELETE, "/fapi/v1/order", 200, &order_json("CANCELED", "0", "0"));
+
+        let iceberg = Iceberg::new(
+            "BTCUSDT",
+            OrderSide::Buy,
+            "1".parse().unwrap(),
+            "30000".parse().unwrap(),
+            "0.1".parse().unwrap(),
+        );
+        let mut handle = iceberg
+            .poll_interval(Duration::from_millis(5))
+            .spawn(trading_api(&transport), &validator())
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        handle.pause();
+        while handle.progress().state != ExecutionState::Paused {
+            handle.changed().await.unwrap();
+        }
+        let deletes = transport.requests().iter().filter(|r| r.method == Method::DELETE).count();
+        assert_eq!(deletes, 1);
+
+        handle.cancel();
+        let progress = handle.wait().await.unwrap();
+        assert_eq!(progress.state, ExecutionState::Canceled);
+        assert_eq!(progress.child_orders, 1);
+    }
+
+    #[tokio::test]
+    async fn test_iceberg_fails_when_clip_stays_open() {
+        let cancel_rejected = r#"{"code":-2011,"msg":"Unknown order sent."}"#;
+        for (cancel_status, cancel_body, queried) in [
+            (400, cancel_rejected, Some("NEW")),
+            (400, cancel_rejected, Some("FILLED")),
+            (503, "Service Unavailable", None),
+        ] {
+            let transport = MemoryTransport::new();
+            transport.push_json(Method::POST, "/fapi/v1/order", 200, &order_json("NEW", "0", "0"));
+            transport.push_json(Method::DELETE, "/fapi/v1/order", cancel_status, cancel_body);
+            if let Some(status) = queried {
+                transport.push_json(Method::GET, "/fapi/v1/order", 200, &order_json(status, "0.1", "3000"));
+            }
+
+            let iceberg = Iceberg::new(
+                "BTCUSDT",
+                OrderSide::Buy,
+                "1".parse().unwrap(),
+                "30000".parse().unwrap(),
+                "0.1".parse().unwrap(),
+            );
+            let mut handle = iceberg
+                .poll_interval(Duration::from_secs(60))
+                .spawn(trading_api(&transport), &validator())
+                .unwrap();
+            while handle.progress().child_orders == 0 {
+                handle.changed().await.unwrap();
+            }
+            handle.cancel();
+            let result = handle.wait().await;
+
+            // Only a clip known to be off the book lets the execution end cleanly
+            match queried {
+                Some("FILLED") => assert_eq!(result.unwrap().state, ExecutionState::Canceled),
+                _ => assert!(result.is_err(), "{cancel_status} {queried:?}"),
+            }
+            let queries = transport.requests().iter().filter(|r| r.method == Method::GET).count();
+            assert_eq!(queries, queried.iter().count());
+        }
+    }
+
+    #[test]
+    fn test_quantity_off_step_is_rejected() {
+        let transport = MemoryTransport::new();
+        let twap = Twap::new("BTCUSDT", OrderSide::Sell, "0.0015".parse().unwrap(), Duration::from_secs(60), 4);
+        assert!(matches!(
+            twap.spawn(trading_api(&transport), &validator()),
+            Err(BinanceError::InvalidParameter(_))
+        ));
+    }
+}
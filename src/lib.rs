@@ -30,8 +30,8 @@
 //!     println!("Server time: {}", time.server_time);
 //!     
 //!     // Get 24hr ticker
-//!     let ticker = client.market().ticker_24hr("BTCUSDT").await?;
-//!     println!("BTC price: {}", ticker.last_price);
+//!     let ticker = client.market().ticker_24hr(Some("BTCUSDT")).await?;
+//!     println!("BTC price: {}", ticker[0].last_price);
 //!     
 //!     Ok(())
 //! }
@@ -40,7 +40,7 @@
 //! ### Authenticated Trading
 //!
 //! ```rust,no_run
-//! use binance_futures_rs::{BinanceClient, Credentials, OrderSide, OrderType, TimeInForce};
+//! use binance_futures_rs::{BinanceClient, Credentials, LimitTimeInForce, NewOrderRequest, OrderSide};
 //!
 //! #[tokio::main]
 //! async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -51,15 +51,14 @@
 //!     let client = BinanceClient::new_with_credentials(credentials);
 //!     
 //!     // Place a limit order
-//!     let order = client.trading().new_order(
-//!         "BTCUSDT",
+//!     let request = NewOrderRequest::limit(
+//!         "BTCUSDT".to_string(),
 //!         OrderSide::Buy,
-//!         OrderType::Limit,
-//!         Some(TimeInForce::Gtc),
-//!         Some("0.001".to_string()),
-//!         Some("50000.0".to_string()),
-//!         None, None, None, None,
-//!     ).await?;
+//!         "0.001".parse()?,
+//!         "50000.0".parse()?,
+//!         LimitTimeInForce::Gtc,
+//!     );
+//!     let order = client.trading().new_order(request).await?;
 //!     
 //!     println!("Order placed: {}", order.order_id);
 //!     Ok(())
@@ -100,7 +99,7 @@
 //! ## Quick Start
 //!
 //! ```rust,no_run
-//! use binance_futures_rs::{BinanceClient, KlineInterval};
+//! use binance_futures_rs::{BinanceClient, Credentials};
 //!
 //! #[tokio::main]
 //! async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -127,53 +126,135 @@
 //! ```
 
 pub mod api;
+#[cfg(feature = "decimal")]
+pub mod backtest;
 pub mod client;
 pub mod error;
+#[cfg(feature = "decimal")]
+pub mod execution;
+#[cfg(feature = "decimal")]
+pub mod paper;
+#[cfg(feature = "decimal")]
+pub mod risk;
+#[cfg(feature = "decimal")]
+pub mod state;
 pub mod types;
 pub mod utils;
+#[cfg(feature = "decimal")]
+pub mod validation;
 pub mod websocket;
 
 /// Main client for interacting with Binance Futures API
 pub struct BinanceClient {
     http_client: HttpClient,
+    #[cfg(feature = "decimal")]
+    risk_checker: Option<RiskChecker>,
 }
 
-pub use api::{AccountApi, MarketApi, TradingApi};
-pub use client::{Credentials, HttpClient};
-pub use error::{BinanceError, Result};
+pub use api::{
+    AccountApi, ClientOrderIdGenerator, CountdownHeartbeat, HeartbeatError, HeartbeatHandle, MarketApi,
+    OrderSubmitter, TradingApi,
+};
+#[cfg(feature = "decimal")]
+pub use backtest::{
+    Backtest, BacktestContext, BacktestReport, BacktestTrade, EquityPoint, FundingPayment, MarketEvent, Strategy,
+};
+pub use client::{
+    CassetteMode, CassetteTransport, Credentials, HttpClient, HttpClientBuilder, KeyType, MemoryTransport,
+    RateLimiter, RequestParams, RetryPolicy, TimeSync, Transport,
+};
+pub use error::{BinanceError, BinanceErrorCode, Result};
+#[cfg(feature = "decimal")]
+pub use execution::{ExecutionHandle, ExecutionProgress, ExecutionState, Iceberg, Twap};
+#[cfg(feature = "decimal")]
+pub use paper::{PaperConfig, PaperExchange};
+#[cfg(feature = "decimal")]
+pub use risk::{RiskChecker, RiskLimits, RiskViolation, SymbolLimits};
+#[cfg(feature = "decimal")]
+pub use state::{
+    OrderFill, OrderManager, Position, PositionFill, PositionMismatch, PositionTracker, ReconcileHandle, ReconcileReport,
+    TrackedOrder,
+};
 pub use types::*;
-pub use websocket::{StreamBuilder, WebSocketClient, WebSocketMessage, UserDataStream, UserDataStreamConfig};
+#[cfg(feature = "decimal")]
+pub use validation::OrderValidator;
+pub use websocket::{StreamBuilder, WebSocketClient, WebSocketClientBuilder, WebSocketMessage, UserDataStream, UserDataStreamConfig};
 
 
 impl BinanceClient {
     /// Create a new client for public endpoints (no authentication)
     pub fn new() -> Self {
-        Self {
-            http_client: HttpClient::new(),
-        }
+        Self::from_http_client(HttpClient::new())
     }
 
     /// Create a new client with credentials for authenticated endpoints
     pub fn new_with_credentials(credentials: Credentials) -> Self {
-        Self {
-            http_client: HttpClient::new_with_credentials(credentials),
-        }
+        Self::from_http_client(HttpClient::new_with_credentials(credentials))
     }
 
     /// Create a new testnet client
     pub fn testnet() -> Self {
-        Self {
-            http_client: HttpClient::testnet(),
-        }
+        Self::from_http_client(HttpClient::testnet())
     }
 
     /// Create a new testnet client with credentials
     pub fn testnet_with_credentials(credentials: Credentials) -> Self {
+        Self::from_http_client(HttpClient::testnet_with_credentials(credentials))
+    }
+
+    /// Create a builder for a custom client configuration
+    pub fn builder() -> BinanceClientBuilder {
+        BinanceClientBuilder::new()
+    }
+
+    /// Create a client from an existing HTTP client
+    pub fn from_http_client(http_client: HttpClient) -> Self {
         Self {
-            http_client: HttpClient::testnet_with_credentials(credentials),
+            http_client,
+            #[cfg(feature = "decimal")]
+            risk_checker: None,
         }
     }
 
+    /// Set the default `recvWindow` (milliseconds) for signed requests
+    pub fn with_recv_window(mut self, recv_window: u64) -> Self {
+        self.http_client = self.http_client.with_recv_window(recv_window);
+        self
+    }
+
+    /// Enable periodic server time synchronization for signed requests
+    pub fn with_time_sync(mut self, interval: std::time::Duration) -> Self {
+        self.http_client = self.http_client.with_time_sync(interval);
+        self
+    }
+
+    /// Measure the offset to Binance server time now
+    pub async fn sync_time(&self) -> Result<i64> {
+        self.http_client.sync_time().await
+    }
+
+    /// Set the retry policy used by all API clients created from this client
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.http_client = self.http_client.with_retry_policy(retry_policy);
+        self
+    }
+
+    /// Run every order placed through [`trading`](Self::trading) past these risk checks
+    #[cfg(feature = "decimal")]
+    pub fn with_risk_checker(mut self, risk_checker: RiskChecker) -> Self {
+        self.risk_checker = Some(risk_checker);
+        self
+    }
+
+    /// Load the rate limits from `exchangeInfo` into the shared rate limiter
+    pub async fn load_rate_limits(&self) -> Result<()> {
+        let exchange_info = self.market().exchange_info().await?;
+        self.http_client
+            .rate_limiter()
+            .apply_exchange_limits(&exchange_info.rate_limits);
+        Ok(())
+    }
+
     /// Get market data API
     pub fn market(&self) -> MarketApi {
         MarketApi::new(self.http_client.clone())
@@ -181,7 +262,12 @@ impl BinanceClient {
 
     /// Get trading API client
     pub fn trading(&self) -> TradingApi {
-        TradingApi::new(self.http_client.clone())
+        let trading = TradingApi::new(self.http_client.clone());
+        #[cfg(feature = "decimal")]
+        if let Some(risk_checker) = &self.risk_checker {
+            return trading.with_risk_checker(risk_checker.clone());
+        }
+        trading
     }
 
     /// Get account API client
@@ -202,6 +288,144 @@ impl Default for BinanceClient {
     }
 }
 
+/// Builder for [`BinanceClient`]
+#[derive(Clone, Default)]
+pub struct BinanceClientBuilder {
+    http: HttpClientBuilder,
+}
+
+impl BinanceClientBuilder {
+    pub fn new() -> Self {
+        Self {
+            http: HttpClientBuilder::new(),
+        }
+    }
+
+    /// Send requests to an arbitrary base URL, e.g. a local mock server
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.http = self.http.base_url(base_url);
+        self
+    }
+
+    /// Use the testnet endpoint
+    pub fn testnet(mut self) -> Self {
+        self.http = self.http.testnet();
+        self
+    }
+
+    /// Sign requests with these credentials
+    pub fn credentials(mut self, credentials: Credentials) -> Self {
+        self.http = self.http.credentials(credentials);
+        self
+    }
+
+    /// Use a pre-built reqwest client
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http = self.http.http_client(client);
+        self
+    }
+
+    /// Send requests through a custom transport instead of reqwest
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.http = self.http.transport(transport);
+        self
+    }
+
+    /// Record interactions to, or replay them from, a cassette file
+    pub fn cassette(mut self, path: impl Into<std::path::PathBuf>, mode: CassetteMode) -> Self {
+        self.http = self.http.cassette(path, mode);
+        self
+    }
+
+    /// Parameters the cassette ignores when matching a replayed request,
+    /// e.g. `newClientOrderId`
+    pub fn cassette_ignore_params<I, S>(mut self, params: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.http = self.http.cassette_ignore_params(params);
+        self
+    }
+
+    /// Total request timeout (default 30 seconds)
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.http = self.http.timeout(timeout);
+        self
+    }
+
+    /// Timeout for establishing a connection
+    pub fn connect_timeout(mut self, connect_timeout: std::time::Duration) -> Self {
+        self.http = self.http.connect_timeout(connect_timeout);
+        self
+    }
+
+    /// Route all requests through an HTTP(S) or SOCKS5 proxy
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.http = self.http.proxy(proxy);
+        self
+    }
+
+    /// Maximum idle connections kept per host
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.http = self.http.pool_max_idle_per_host(max);
+        self
+    }
+
+    /// How long idle connections are kept alive
+    pub fn pool_idle_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.http = self.http.pool_idle_timeout(timeout);
+        self
+    }
+
+    /// Enable or disable TCP_NODELAY
+    pub fn tcp_nodelay(mut self, enabled: bool) -> Self {
+        self.http = self.http.tcp_nodelay(enabled);
+        self
+    }
+
+    /// Send a custom `User-Agent` header
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.http = self.http.user_agent(user_agent);
+        self
+    }
+
+    /// Default `recvWindow` (milliseconds) for signed requests
+    pub fn recv_window(mut self, recv_window: u64) -> Self {
+        self.http = self.http.recv_window(recv_window);
+        self
+    }
+
+    /// Enable server time synchronization, re-syncing every `interval`
+    pub fn time_sync(mut self, interval: std::time::Duration) -> Self {
+        self.http = self.http.time_sync(interval);
+        self
+    }
+
+    /// Share a rate limiter with other clients
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.http = self.http.rate_limiter(rate_limiter);
+        self
+    }
+
+    /// Retry policy for transient failures
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.http = self.http.retry_policy(retry_policy);
+        self
+    }
+
+    /// Sign and log order requests instead of sending them
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.http = self.http.dry_run(dry_run);
+        self
+    }
+
+    /// Build the client
+    pub fn build(self) -> Result<BinanceClient> {
+        Ok(BinanceClient::from_http_client(self.http.build()?))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -210,7 +434,6 @@ mod tests {
     fn test_client_creation() {
         let client = BinanceClient::new();
         let _market_api = client.market();
-        assert!(true);
     }
 
     #[test]
@@ -219,14 +442,24 @@ mod tests {
         let client = BinanceClient::new_with_credentials(credentials);
         let _trading_api = client.trading();
         let _account_api = client.account();
-        assert!(true);
+    }
+
+    #[test]
+    fn test_client_builder() {
+        let client = BinanceClient::builder()
+            .base_url("http://localhost:9000")
+            .credentials(Credentials::new("test_key".to_string(), "test_secret".to_string()))
+            .timeout(std::time::Duration::from_secs(10))
+            .user_agent("my-bot/1.0")
+            .build()
+            .unwrap();
+        assert_eq!(client.http_client().base_url(), "http://localhost:9000");
     }
 
     #[test]
     fn test_testnet_client() {
         let client = BinanceClient::testnet();
         let _market_api = client.market();
-        assert!(true);
     }
 
 }
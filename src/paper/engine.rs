@@ -0,0 +1,1111 @@
+//! Matching and margin accounting behind [`PaperExchange`](super::PaperExchange)
+
+use super::PaperConfig;
+use crate::error::{BinanceError, BinanceErrorCode, Result};
+use crate::types::common::{OrderSide, OrderStatus, OrderType, PositionSide, TimeInForce};
+use crate::types::decimal::{in_range, Decimal, RoundingStrategy};
+use crate::types::order_builder::CallbackRate;
+use crate::types::trading::{ModifyOrderRequest, NewOrderRequest};
+use crate::utils::get_timestamp;
+use crate::websocket::types::{
+    AccountUpdate, AccountUpdateData, BalanceUpdate, OrderUpdate, OrderUpdateData, PositionUpdate, WebSocketMessage,
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// Decimals kept for entry and average prices
+const PRICE_SCALE: u32 = 8;
+
+const HUNDRED: Decimal = Decimal::new(100, 0);
+
+/// Price levels of one symbol
+#[derive(Debug, Default)]
+struct Book {
+    bids: BTreeMap<Decimal, Decimal>,
+    asks: BTreeMap<Decimal, Decimal>,
+}
+
+impl Book {
+    fn update(levels: &mut BTreeMap<Decimal, Decimal>, updates: &[[Decimal; 2]]) {
+        for [price, quantity] in updates {
+            if quantity.is_zero() {
+                levels.remove(price);
+            } else {
+                levels.insert(*price, *quantity);
+            }
+        }
+    }
+
+    /// Drop levels left behind on the other side of the newest best prices
+    fn uncross(&mut self, bids_changed: bool) {
+        let (Some(bid), Some(ask)) = (self.best_bid(), self.best_ask()) else {
+            return;
+        };
+        if bid < ask {
+            return;
+        }
+        if bids_changed {
+            self.asks.retain(|price, _| *price > bid);
+        } else {
+            self.bids.retain(|price, _| *price < ask);
+        }
+    }
+
+    fn best_bid(&self) -> Option<Decimal> {
+        self.bids.keys().next_back().copied()
+    }
+
+    fn best_ask(&self) -> Option<Decimal> {
+        self.asks.keys().next().copied()
+    }
+
+    /// Levels an order on `side` trades against, best first
+    fn opposite(&self, side: OrderSide) -> Vec<(Decimal, Decimal)> {
+        match side {
+            OrderSide::Buy => self.asks.iter().map(|(p, q)| (*p, *q)).collect(),
+            OrderSide::Sell => self.bids.iter().rev().map(|(p, q)| (*p, *q)).collect(),
+        }
+    }
+
+    fn consume(&mut self, side: OrderSide, price: Decimal, quantity: Decimal) -> Result<()> {
+        let levels = match side {
+            OrderSide::Buy => &mut self.asks,
+            OrderSide::Sell => &mut self.bids,
+        };
+        if let Some(level) = levels.get_mut(&price) {
+            *level = in_range(level.checked_sub(quantity))?;
+            if level.is_zero() || level.is_negative() {
+                levels.remove(&price);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A simulated order
+#[derive(Debug, Clone)]
+pub(crate) struct SimOrder {
+    pub(crate) order_id: u64,
+    pub(crate) client_order_id: String,
+    pub(crate) symbol: String,
+    pub(crate) side: OrderSide,
+    pub(crate) position_side: PositionSide,
+    pub(crate) order_type: OrderType,
+    pub(crate) time_in_force: TimeInForce,
+    /// Limit price, zero for market orders
+    pub(crate) price: Decimal,
+    pub(crate) quantity: Decimal,
+    /// Trigger price of stop and take-profit orders, zero otherwise
+    pub(crate) stop_price: Decimal,
+    pub(crate) activation_price: Option<Decimal>,
+    /// Trailing distance of a trailing stop, in percent
+    pub(crate) callback_rate: Option<Decimal>,
+    /// Best price seen since a trailing stop activated
+    trailing_extreme: Option<Decimal>,
+    /// Whether a conditional order has triggered and now works as a plain order
+    triggered: bool,
+    pub(crate) executed: Decimal,
+    pub(crate) cum_quote: Decimal,
+    pub(crate) status: OrderStatus,
+    pub(crate) reduce_only: bool,
+    pub(crate) time: u64,
+    pub(crate) update_time: u64,
+}
+
+impl SimOrder {
+    pub(crate) fn remaining(&self) -> Result<Decimal> {
+        in_range(self.quantity.checked_sub(self.executed))
+    }
+
+    /// Zero before the first fill. The average lies between the fill
+    /// prices, so only a zero executed quantity leaves no quotient.
+    pub(crate) fn avg_price(&self) -> Decimal {
+        div(self.cum_quote, self.executed).unwrap_or(Decimal::ZERO)
+    }
+
+    /// Whether a trade at `price` goes through this order's limit
+    fn crossed_by(&self, price: Decimal) -> bool {
+        match self.side {
+            OrderSide::Buy => price < self.price,
+            OrderSide::Sell => price > self.price,
+        }
+    }
+
+    /// Whether this order can take liquidity at `price`
+    fn accepts(&self, price: Decimal) -> bool {
+        match (self.has_limit(), self.side) {
+            (false, _) => true,
+            (true, OrderSide::Buy) => price <= self.price,
+            (true, OrderSide::Sell) => price >= self.price,
+        }
+    }
+
+    /// Whether the order trades at a limit price, once triggered for STOP and TAKE_PROFIT
+    fn has_limit(&self) -> bool {
+        matches!(self.order_type, OrderType::Limit | OrderType::Stop | OrderType::TakeProfit)
+    }
+
+    /// Whether the order is in the book: plain orders always, conditional ones once triggered
+    fn working(&self) -> bool {
+        matches!(self.order_type, OrderType::Limit | OrderType::Market) || self.triggered
+    }
+
+    /// Whether an unfilled remainder expires instead of resting
+    fn expires(&self) -> bool {
+        !self.has_limit() || self.time_in_force == TimeInForce::Ioc
+    }
+
+    /// Whether a trade at `price` triggers this conditional order,
+    /// moving a trailing stop along with the price
+    fn reaches_trigger(&mut self, price: Decimal) -> Result<bool> {
+        let buy = self.side == OrderSide::Buy;
+        Ok(match self.order_type {
+            OrderType::Stop | OrderType::StopMarket if buy => price >= self.stop_price,
+            OrderType::Stop | OrderType::StopMarket => price <= self.stop_price,
+            OrderType::TakeProfit | OrderType::TakeProfitMarket if buy => price <= self.stop_price,
+            OrderType::TakeProfit | OrderType::TakeProfitMarket => price >= self.stop_price,
+            OrderType::TrailingStopMarket => {
+                let extreme = match self.trailing_extreme {
+                    Some(extreme) if buy => extreme.min(price),
+                    Some(extreme) => extreme.max(price),
+                    None => {
+                        let activated = self.activation_price.is_none_or(|activation| match buy {
+                            true => price <= activation,
+                            false => price >= activation,
+                        });
+                        if !activated {
+                            return Ok(false);
+                        }
+                        price
+                    }
+                };
+                self.trailing_extreme = Some(extreme);
+                self.trailing_trigger()?.is_some_and(|trigger| match buy {
+                    true => price >= trigger,
+                    false => price <= trigger,
+                })
+            }
+            _ => false,
+        })
+    }
+
+    /// Price at which an activated trailing stop triggers
+    fn trailing_trigger(&self) -> Result<Option<Decimal>> {
+        let (Some(extreme), Some(callback_rate)) = (self.trailing_extreme, self.callback_rate) else {
+            return Ok(None);
+        };
+        let offset = in_range(mul(extreme, callback_rate).and_then(|distance| div(distance, HUNDRED)))?;
+        let trigger = match self.side {
+            OrderSide::Buy => extreme.checked_add(offset),
+            OrderSide::Sell => extreme.checked_sub(offset),
+        };
+        in_range(trigger).map(Some)
+    }
+}
+
+/// A simulated position; shorts have a negative size
+#[derive(Debug, Clone)]
+pub(crate) struct SimPosition {
+    pub(crate) symbol: String,
+    pub(crate) position_side: PositionSide,
+    pub(crate) size: Decimal,
+    pub(crate) entry_price: Decimal,
+    pub(crate) realized_pnl: Decimal,
+    pub(crate) update_time: u64,
+}
+
+/// Margin figures of the whole account
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Margin {
+    pub(crate) wallet_balance: Decimal,
+    pub(crate) unrealized_pnl: Decimal,
+    pub(crate) position_margin: Decimal,
+    pub(crate) order_margin: Decimal,
+}
+
+impl Margin {
+    pub(crate) fn margin_balance(&self) -> Result<Decimal> {
+        in_range(self.wallet_balance.checked_add(self.unrealized_pnl))
+    }
+
+    pub(crate) fn initial_margin(&self) -> Result<Decimal> {
+        in_range(self.position_margin.checked_add(self.order_margin))
+    }
+
+    pub(crate) fn available_balance(&self) -> Result<Decimal> {
+        in_range(self.margin_balance()?.checked_sub(self.initial_margin()?))
+    }
+}
+
+/// State of the simulated account and the books it trades against
+pub(crate) struct Engine {
+    pub(crate) asset: String,
+    pub(crate) maker_fee: Decimal,
+    pub(crate) taker_fee: Decimal,
+    default_leverage: u32,
+    wallet: Decimal,
+    next_order_id: u64,
+    next_trade_id: u64,
+    pub(crate) dual_side: bool,
+    books: HashMap<String, Book>,
+    last_prices: HashMap<String, Decimal>,
+    orders: BTreeMap<u64, SimOrder>,
+    positions: HashMap<(String, PositionSide), SimPosition>,
+    leverage: HashMap<String, u32>,
+    events: Vec<WebSocketMessage>,
+    /// Simulated time replacing the system clock, when replaying history
+    clock: Option<u64>,
+}
+
+impl Engine {
+    pub(crate) fn new(config: &PaperConfig) -> Self {
+        Self {
+            asset: config.asset.clone(),
+            maker_fee: config.maker_fee,
+            taker_fee: config.taker_fee,
+            default_leverage: config.leverage,
+            wallet: config.balance,
+            next_order_id: 1,
+            next_trade_id: 1,
+            dual_side: false,
+            books: HashMap::new(),
+            last_prices: HashMap::new(),
+            orders: BTreeMap::new(),
+            positions: HashMap::new(),
+            leverage: HashMap::new(),
+            events: Vec::new(),
+            clock: None,
+        }
+    }
+
+    pub(crate) fn now(&self) -> u64 {
+        self.clock.unwrap_or_else(get_timestamp)
+    }
+
+    /// Run on simulated time from now on
+    pub(crate) fn set_time(&mut self, time: u64) {
+        self.clock = Some(time);
+    }
+
+    /// Events produced since the last call
+    pub(crate) fn drain_events(&mut self) -> Vec<WebSocketMessage> {
+        std::mem::take(&mut self.events)
+    }
+
+    // Market data
+
+    pub(crate) fn on_depth(&mut self, symbol: &str, bids: &[[Decimal; 2]], asks: &[[Decimal; 2]], replace: bool) -> Result<()> {
+        let book = self.books.entry(symbol.to_string()).or_default();
+        if replace {
+            *book = Book::default();
+        }
+        Book::update(&mut book.bids, bids);
+        Book::update(&mut book.asks, asks);
+        book.uncross(!bids.is_empty());
+
+        // Resting orders the book has moved through trade against it
+        for order_id in self.resting(symbol) {
+            let order = &self.orders[&order_id];
+            let (side, price) = (order.side, order.price);
+            let mut remaining = order.remaining()?;
+            let book = &self.books[symbol];
+            let mut fills = Vec::new();
+            for (level, quantity) in book.opposite(side) {
+                if remaining.is_zero() || !order.crossed_by(level) {
+                    break;
+                }
+                let quantity = quantity.min(remaining);
+                fills.push((level, quantity));
+                remaining = in_range(remaining.checked_sub(quantity))?;
+            }
+            for (level, quantity) in fills {
+                self.books.get_mut(symbol).unwrap().consume(side, level, quantity)?;
+                self.fill(order_id, quantity, price, true)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn on_trade(&mut self, symbol: &str, price: &Decimal, quantity: &Decimal) -> Result<()> {
+        self.trade_at(symbol, *price, *quantity)
+    }
+
+    pub(crate) fn trade_at(&mut self, symbol: &str, price: Decimal, mut volume: Decimal) -> Result<()> {
+        self.last_prices.insert(symbol.to_string(), price);
+        self.trigger(symbol, price)?;
+
+        // Trades printing through a resting order fill it at its own price, up to the traded volume
+        for order_id in self.resting(symbol) {
+            let order = &self.orders[&order_id];
+            if volume.is_zero() || !order.crossed_by(price) {
+                continue;
+            }
+            let quantity = order.remaining()?.min(volume);
+            let limit = order.price;
+            volume = in_range(volume.checked_sub(quantity))?;
+            self.fill(order_id, quantity, limit, true)?;
+        }
+        Ok(())
+    }
+
+    /// Conditional orders on `symbol` a trade at `price` triggers, sent to the book as taker in order of placement
+    fn trigger(&mut self, symbol: &str, price: Decimal) -> Result<()> {
+        let pending: Vec<u64> = self
+            .orders(Some(symbol))
+            .filter(|order| order.status.is_open() && !order.working())
+            .map(|order| order.order_id)
+            .collect();
+        for order_id in pending {
+            let order = self.orders.get_mut(&order_id).unwrap();
+            if !order.reaches_trigger(price)? {
+                continue;
+            }
+            order.triggered = true;
+            // Margin is only checked once the order enters the book; a rejection expires the order
+            match self.check_margin(&self.orders[&order_id]) {
+                Err(BinanceError::Api { .. }) => {
+                    self.finish(order_id, OrderStatus::Expired);
+                    continue;
+                }
+                result => result?,
+            }
+            self.take(order_id)?;
+            let order = &self.orders[&order_id];
+            if order.status.is_open() && order.expires() {
+                self.finish(order_id, OrderStatus::Expired);
+            }
+        }
+        Ok(())
+    }
+
+    /// Prices at which waiting conditional orders on `symbol` trigger
+    pub(crate) fn trigger_prices(&self, symbol: &str) -> Result<Vec<Decimal>> {
+        let mut prices = Vec::new();
+        for order in self.orders(Some(symbol)).filter(|order| order.status.is_open() && !order.working()) {
+            let price = match order.order_type {
+                OrderType::TrailingStopMarket => order.trailing_trigger()?.or(order.activation_price),
+                _ => Some(order.stop_price),
+            };
+            prices.extend(price);
+        }
+        Ok(prices)
+    }
+
+    /// Working open orders on `symbol` in price-time priority
+    fn resting(&self, symbol: &str) -> Vec<u64> {
+        let mut resting: Vec<&SimOrder> = self
+            .orders
+            .values()
+            .filter(|order| order.symbol == symbol && order.status.is_open() && order.working())
+            .collect();
+        resting.sort_by(|a, b| {
+            let by_price = match a.side {
+                OrderSide::Buy => b.price.cmp(&a.price),
+                OrderSide::Sell => a.price.cmp(&b.price),
+            };
+            by_price.then(a.order_id.cmp(&b.order_id))
+        });
+        resting.into_iter().map(|order| order.order_id).collect()
+    }
+
+    /// Best estimate of the current price: last trade, then mid price
+    pub(crate) fn mark_price(&self, symbol: &str) -> Option<Decimal> {
+        if let Some(price) = self.last_prices.get(symbol) {
+            return Some(*price);
+        }
+        let book = self.books.get(symbol)?;
+        match (book.best_bid(), book.best_ask()) {
+            // Half the spread added to the bid stays below the ask, so cannot overflow
+            (Some(bid), Some(ask)) => bid.checked_add(div(ask.checked_sub(bid)?, Decimal::new(2, 0))?),
+            (bid, ask) => bid.or(ask),
+        }
+    }
+
+    // Orders
+
+    /// Check a new order without placing it
+    pub(crate) fn check(&self, request: &NewOrderRequest) -> Result<()> {
+        let order = self.build(request)?;
+        match order.working() {
+            true => self.check_margin(&order),
+            false => Ok(()),
+        }
+    }
+
+    pub(crate) fn place(&mut self, request: &NewOrderRequest) -> Result<SimOrder> {
+        let order = self.build(request)?;
+        if !order.working() {
+            return Ok(self.insert(order));
+        }
+        self.check_margin(&order)?;
+
+        let liquidity = self.liquidity(&order)?;
+        match order.time_in_force {
+            TimeInForce::Gtx if liquidity.is_none_or(|quantity| !quantity.is_zero()) => {
+                return Err(reject(
+                    BinanceErrorCode::GtxOrderReject,
+                    "Due to the order could not be executed as maker, the Post Only order will be rejected.",
+                ));
+            }
+            TimeInForce::Fok if liquidity.is_some_and(|quantity| quantity < order.quantity) => {
+                return Err(reject(
+                    BinanceErrorCode::FokOrderReject,
+                    "Due to the order could not be filled immediately, the FOK order has been rejected.",
+                ));
+            }
+            _ => {}
+        }
+
+        let order_id = self.insert(order).order_id;
+        self.take(order_id)?;
+
+        let order = &self.orders[&order_id];
+        if order.status.is_open() && order.expires() {
+            self.finish(order_id, OrderStatus::Expired);
+        }
+        Ok(self.orders[&order_id].clone())
+    }
+
+    /// Assign ids to a new order and announce it
+    fn insert(&mut self, mut order: SimOrder) -> SimOrder {
+        order.order_id = self.next_order_id;
+        self.next_order_id += 1;
+        if order.client_order_id.is_empty() {
+            order.client_order_id = format!("paper-{}", order.order_id);
+        }
+        let order_id = order.order_id;
+        self.orders.insert(order_id, order);
+        self.emit_order(order_id, "NEW", None);
+        self.orders[&order_id].clone()
+    }
+
+    /// Turn a request into an order, checking what the exchange would
+    fn build(&self, request: &NewOrderRequest) -> Result<SimOrder> {
+        let invalid = |msg: String| reject(BinanceErrorCode::MandatoryParamEmptyOrMalformed, msg);
+
+        if request.price_match.is_some() {
+            return Err(reject(
+                BinanceErrorCode::UnsupportedOrderTypePriceMatch,
+                "priceMatch is not supported by the paper exchange.",
+            ));
+        }
+        if request.close_position == Some(true) {
+            return Err(reject(
+                BinanceErrorCode::InvalidParameter,
+                "closePosition is not supported by the paper exchange.",
+            ));
+        }
+        let order_type = request.order_type;
+        let required = |value: Option<Decimal>, name: &str| {
+            value
+                .ok_or_else(|| invalid(format!("Mandatory parameter '{}' was not sent, was empty/null, or malformed.", name)))
+        };
+        let price = match order_type {
+            OrderType::Limit | OrderType::Stop | OrderType::TakeProfit => required(request.price, "price")?,
+            _ if request.price.is_some() => {
+                return Err(reject(BinanceErrorCode::ParamNotRequired, "Parameter 'price' sent when not required."));
+            }
+            _ => Decimal::ZERO,
+        };
+        let stop_price = match order_type {
+            OrderType::Stop | OrderType::StopMarket | OrderType::TakeProfit | OrderType::TakeProfitMarket => {
+                let stop_price = required(request.stop_price, "stopPrice")?;
+                if stop_price.is_zero() || stop_price.is_negative() {
+                    return Err(reject(BinanceErrorCode::StopPriceLessThanZero, "Stop price less than or equal to zero."));
+                }
+                stop_price
+            }
+            _ => Decimal::ZERO,
+        };
+        let (activation_price, callback_rate) = match order_type {
+            OrderType::TrailingStopMarket => {
+                let callback_rate = required(request.callback_rate, "callbackRate")?;
+                if !(CallbackRate::MIN..=CallbackRate::MAX).contains(&callback_rate) {
+                    return Err(reject(BinanceErrorCode::InvalidParameter, "Invalid callBack rate."));
+                }
+                (request.activation_price, Some(callback_rate))
+            }
+            _ => (None, None),
+        };
+        let quantity = request
+            .quantity
+            .ok_or_else(|| invalid("Mandatory parameter 'quantity' was not sent, was empty/null, or malformed.".to_string()))?;
+        if quantity.is_zero() || quantity.is_negative() {
+            return Err(reject(BinanceErrorCode::QtyLessThanZero, "Quantity less than or equal to zero."));
+        }
+        if price.is_negative() || price.is_zero() && matches!(order_type, OrderType::Limit | OrderType::Stop | OrderType::TakeProfit) {
+            return Err(reject(BinanceErrorCode::PriceLessThanZero, "Price less than or equal to zero."));
+        }
+
+        let position_side = request.position_side.unwrap_or(PositionSide::Both);
+        if self.dual_side == (position_side == PositionSide::Both) {
+            return Err(reject(
+                BinanceErrorCode::PositionSideNotMatch,
+                "Order's position side does not match user's setting.",
+            ));
+        }
+
+        let reduce_only = request.reduce_only == Some(true);
+        if reduce_only {
+            let size = self.position_size(&request.symbol, position_side);
+            let reduces = match request.side {
+                OrderSide::Buy => size.is_negative(),
+                OrderSide::Sell => !size.is_negative() && !size.is_zero(),
+            };
+            if !reduces {
+                return Err(reject(BinanceErrorCode::ReduceOnlyReject, "ReduceOnly Order is rejected."));
+            }
+        }
+
+        if let Some(client_order_id) = &request.new_client_order_id {
+            if self.orders.values().any(|o| &o.client_order_id == client_order_id && o.status.is_open()) {
+                return Err(reject(BinanceErrorCode::DuplicatedClientTranId, "Duplicate clientOrderId."));
+            }
+        }
+
+        let now = self.now();
+        let mut order = SimOrder {
+            order_id: 0,
+            client_order_id: request.new_client_order_id.clone().unwrap_or_default(),
+            symbol: request.symbol.clone(),
+            side: request.side,
+            position_side,
+            order_type,
+            time_in_force: request.time_in_force.unwrap_or(TimeInForce::Gtc),
+            price,
+            quantity,
+            stop_price,
+            activation_price,
+            callback_rate,
+            trailing_extreme: None,
+            triggered: false,
+            executed: Decimal::ZERO,
+            cum_quote: Decimal::ZERO,
+            status: OrderStatus::New,
+            reduce_only,
+            time: now,
+            update_time: now,
+        };
+
+        // Stops already through the current price are refused; a trailing stop
+        // without activation price starts trailing from it
+        if let Some(current) = self.mark_price(&order.symbol) {
+            match order_type {
+                OrderType::TrailingStopMarket if activation_price.is_none() => order.trailing_extreme = Some(current),
+                OrderType::TrailingStopMarket => {}
+                _ if !order.working() && order.clone().reaches_trigger(current)? => {
+                    return Err(reject(BinanceErrorCode::OrderWouldImmediatelyTrigger, "Order would immediately trigger."));
+                }
+                _ => {}
+            }
+        }
+        Ok(order)
+    }
+
+    /// Quantity available to `order` right now; `None` when only a last
+    /// trade price is known, which is treated as unlimited
+    fn liquidity(&self, order: &SimOrder) -> Result<Option<Decimal>> {
+        let levels = self.books.get(&order.symbol).map(|book| book.opposite(order.side)).unwrap_or_default();
+        if levels.is_empty() {
+            return Ok(match self.last_prices.get(&order.symbol) {
+                Some(last) if order.accepts(*last) => None,
+                _ => Some(Decimal::ZERO),
+            });
+        }
+        levels
+            .into_iter()
+            .take_while(|(price, _)| order.accepts(*price))
+            .try_fold(Decimal::ZERO, |total, (_, quantity)| in_range(total.checked_add(quantity)))
+            .map(Some)
+    }
+
+    /// Fill an incoming order against the book as taker
+    fn take(&mut self, order_id: u64) -> Result<()> {
+        let order = &self.orders[&order_id];
+        let side = order.side;
+        let symbol = order.symbol.clone();
+        let mut remaining = order.remaining()?;
+        let levels = self.books.get(&symbol).map(|book| book.opposite(side)).unwrap_or_default();
+
+        let mut fills = Vec::new();
+        if levels.is_empty() {
+            if let Some(last) = self.last_prices.get(&symbol).filter(|last| order.accepts(**last)) {
+                fills.push((*last, remaining));
+            }
+        }
+        for (price, quantity) in levels {
+            if remaining.is_zero() || !order.accepts(price) {
+                break;
+            }
+            let quantity = quantity.min(remaining);
+            fills.push((price, quantity));
+            remaining = in_range(remaining.checked_sub(quantity))?;
+        }
+
+        for (price, quantity) in fills {
+            if let Some(book) = self.books.get_mut(&symbol) {
+                book.consume(side, price, quantity)?;
+            }
+            self.fill(order_id, quantity, price, false)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn cancel(&mut self, symbol: &str, order_id: Option<u64>, client_order_id: Option<&str>) -> Result<SimOrder> {
+        let order = self.find(symbol, order_id, client_order_id).map_err(|_| unknown_order())?;
+        if !order.status.is_open() {
+            return Err(unknown_order());
+        }
+        let order_id = order.order_id;
+        self.finish(order_id, OrderStatus::Canceled);
+        Ok(self.orders[&order_id].clone())
+    }
+
+    pub(crate) fn cancel_all(&mut self, symbol: &str) {
+        let open: Vec<u64> = self
+            .orders(Some(symbol))
+            .filter(|order| order.status.is_open())
+            .map(|order| order.order_id)
+            .collect();
+        for order_id in open {
+            self.finish(order_id, OrderStatus::Canceled);
+        }
+    }
+
+    pub(crate) fn modify(&mut self, modify: &ModifyOrderRequest) -> Result<SimOrder> {
+        if modify.price_match.is_some() {
+            return Err(reject(
+                BinanceErrorCode::UnsupportedOrderTypePriceMatch,
+                "priceMatch is not supported by the paper exchange.",
+            ));
+        }
+        let order = self.find(&modify.symbol, modify.order_id, modify.orig_client_order_id.as_deref())?;
+        if !order.status.is_open() {
+            return Err(reject(BinanceErrorCode::InvalidOrderStatus, "Invalid order status."));
+        }
+        if order.order_type != OrderType::Limit {
+            return Err(reject(BinanceErrorCode::LimitOrderOnly, "Only limit order is supported."));
+        }
+        if order.side != modify.side {
+            return Err(reject(BinanceErrorCode::InvalidSide, "Invalid side."));
+        }
+        let quantity = modify.quantity;
+        let price = modify.price.unwrap_or(order.price);
+        if quantity <= order.executed {
+            return Err(reject(BinanceErrorCode::QtyLessThanZero, "Quantity must be greater than the executed quantity."));
+        }
+        if price == order.price && quantity == order.quantity {
+            return Err(reject(BinanceErrorCode::SameOrder, "No need to modify the order."));
+        }
+
+        let order_id = order.order_id;
+        let now = self.now();
+        let order = self.orders.get_mut(&order_id).unwrap();
+        order.price = price;
+        order.quantity = quantity;
+        order.update_time = now;
+        self.emit_order(order_id, "AMENDMENT", None);
+        self.take(order_id)?;
+        Ok(self.orders[&order_id].clone())
+    }
+
+    pub(crate) fn find(&self, symbol: &str, order_id: Option<u64>, client_order_id: Option<&str>) -> Result<&SimOrder> {
+        let order = match (order_id, client_order_id) {
+            (Some(order_id), _) => self.orders.get(&order_id),
+            (None, Some(client_order_id)) => {
+                self.orders.values().rev().find(|order| order.client_order_id == client_order_id)
+            }
+            (None, None) => {
+                return Err(reject(
+                    BinanceErrorCode::MandatoryParamEmptyOrMalformed,
+                    "Either orderId or origClientOrderId must be sent.",
+                ));
+            }
+        };
+        order
+            .filter(|order| order.symbol == symbol)
+            .ok_or_else(|| reject(BinanceErrorCode::NoSuchOrder, "Order does not exist."))
+    }
+
+    pub(crate) fn orders<'a>(&'a self, symbol: Option<&'a str>) -> impl Iterator<Item = &'a SimOrder> {
+        self.orders.values().filter(move |order| symbol.is_none_or(|symbol| order.symbol == symbol))
+    }
+
+    fn finish(&mut self, order_id: u64, status: OrderStatus) {
+        let now = self.now();
+        let order = self.orders.get_mut(&order_id).unwrap();
+        order.status = status;
+        order.update_time = now;
+        let execution_type = match status {
+            OrderStatus::Canceled => "CANCELED",
+            _ => "EXPIRED",
+        };
+        self.emit_order(order_id, execution_type, None);
+    }
+
+    // Accounting
+
+    fn fill(&mut self, order_id: u64, quantity: Decimal, price: Decimal, maker: bool) -> Result<()> {
+        let order = &self.orders[&order_id];
+        let key = (order.symbol.clone(), order.position_side);
+        let side = order.side;
+        let reduce_only = order.reduce_only;
+
+        let quantity = match reduce_only {
+            true => quantity.min(self.position_size(&key.0, key.1).abs()),
+            false => quantity,
+        };
+        if quantity.is_zero() {
+            self.finish(order_id, OrderStatus::Expired);
+            return Ok(());
+        }
+
+        // Everything is worked out before anything changes, so an overflow leaves the account as it was
+        let now = self.now();
+        let notional = in_range(mul(quantity, price))?;
+        let fee = in_range(mul(notional, if maker { self.maker_fee } else { self.taker_fee }))?;
+        let (position, realized) = self.position_after(&key, side, quantity, price, now)?;
+        let balance_change = in_range(realized.checked_sub(fee))?;
+        let wallet = in_range(self.wallet.checked_add(balance_change))?;
+        let executed = in_range(order.executed.checked_add(quantity))?;
+        let cum_quote = in_range(order.cum_quote.checked_add(notional))?;
+        let account_update = self.account_update("ORDER", wallet, balance_change, &position)?;
+
+        self.positions.insert(key.clone(), position);
+        self.wallet = wallet;
+        let trade_id = self.next_trade_id;
+        self.next_trade_id += 1;
+        let order = self.orders.get_mut(&order_id).unwrap();
+        order.executed = executed;
+        order.cum_quote = cum_quote;
+        order.status = if executed >= order.quantity {
+            OrderStatus::Filled
+        } else {
+            OrderStatus::PartiallyFilled
+        };
+        order.update_time = now;
+
+        let trade = Trade {
+            quantity,
+            price,
+            fee,
+            realized,
+            trade_id,
+            maker,
+        };
+        self.emit_order(order_id, "TRADE", Some(trade));
+        self.events.push(account_update);
+
+        // A reduce-only order expires once the position is closed
+        if reduce_only && self.orders[&order_id].status.is_open() && self.position_size(&key.0, key.1).is_zero() {
+            self.finish(order_id, OrderStatus::Expired);
+        }
+        Ok(())
+    }
+
+    /// The position at `key` with a fill applied, and the PnL the fill realizes
+    fn position_after(
+        &self,
+        key: &(String, PositionSide),
+        side: OrderSide,
+        quantity: Decimal,
+        price: Decimal,
+        now: u64,
+    ) -> Result<(SimPosition, Decimal)> {
+        let mut position = self.positions.get(key).cloned().unwrap_or_else(|| SimPosition {
+            symbol: key.0.clone(),
+            position_side: key.1,
+            size: Decimal::ZERO,
+            entry_price: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            update_time: now,
+        });
+        let delta = match side {
+            OrderSide::Buy => quantity,
+            OrderSide::Sell => in_range(Decimal::ZERO.checked_sub(quantity))?,
+        };
+        let size = position.size;
+        let new_size = in_range(size.checked_add(delta))?;
+        let mut realized = Decimal::ZERO;
+
+        if size.is_zero() || size.is_negative() == delta.is_negative() {
+            let held = mul(size.abs(), position.entry_price);
+            let cost = held.zip(mul(quantity, price)).and_then(|(held, added)| held.checked_add(added));
+            position.entry_price = in_range(cost.and_then(|cost| div(cost, new_size.abs())))?;
+        } else {
+            let closed = quantity.min(size.abs());
+            let pnl = in_range(price.checked_sub(position.entry_price).and_then(|per_unit| mul(closed, per_unit)))?;
+            realized = match size.is_negative() {
+                false => pnl,
+                true => in_range(Decimal::ZERO.checked_sub(pnl))?,
+            };
+            if new_size.is_zero() {
+                position.entry_price = Decimal::ZERO;
+            } else if new_size.is_negative() != size.is_negative() {
+                // Flipped: the rest opens a new position at the fill price
+                position.entry_price = price;
+            }
+        }
+
+        position.size = new_size;
+        position.realized_pnl = in_range(position.realized_pnl.checked_add(realized))?;
+        position.update_time = now;
+        Ok((position, realized))
+    }
+
+    pub(crate) fn position_size(&self, symbol: &str, position_side: PositionSide) -> Decimal {
+        self.positions
+            .get(&(symbol.to_string(), position_side))
+            .map_or(Decimal::ZERO, |position| position.size)
+    }
+
+    pub(crate) fn positions(&self, symbol: Option<&str>) -> Vec<SimPosition> {
+        let mut positions: Vec<SimPosition> = self
+            .positions
+            .values()
+            .filter(|position| symbol.is_none_or(|symbol| position.symbol == symbol))
+            .cloned()
+            .collect();
+        positions.sort_by(|a, b| (&a.symbol, a.position_side.to_string()).cmp(&(&b.symbol, b.position_side.to_string())));
+        positions
+    }
+
+    pub(crate) fn unrealized_pnl(&self, position: &SimPosition) -> Result<Decimal> {
+        match self.mark_price(&position.symbol) {
+            Some(mark) => in_range(mark.checked_sub(position.entry_price).and_then(|per_unit| mul(position.size, per_unit))),
+            None => Ok(Decimal::ZERO),
+        }
+    }
+
+    pub(crate) fn leverage(&self, symbol: &str) -> u32 {
+        self.leverage.get(symbol).copied().unwrap_or(self.default_leverage)
+    }
+
+    /// Pay or receive funding on every position in `symbol`; returns the
+    /// balance change, negative when paying. Nothing is settled on overflow.
+    pub(crate) fn settle_funding(&mut self, symbol: &str, rate: Decimal, mark_price: Option<Decimal>) -> Result<Decimal> {
+        let Some(mark) = mark_price.or_else(|| self.mark_price(symbol)) else {
+            return Ok(Decimal::ZERO);
+        };
+        let mut wallet = self.wallet;
+        let mut total = Decimal::ZERO;
+        let mut updates = Vec::new();
+        for position in self.positions(Some(symbol)) {
+            if position.size.is_zero() {
+                continue;
+            }
+            // Longs pay shorts when the rate is positive
+            let owed = mul(position.size, mark).and_then(|notional| mul(notional, rate));
+            let payment = in_range(owed.and_then(|owed| Decimal::ZERO.checked_sub(owed)))?;
+            wallet = in_range(wallet.checked_add(payment))?;
+            total = in_range(total.checked_add(payment))?;
+            updates.push(self.account_update("FUNDING_FEE", wallet, payment, &position)?);
+        }
+        self.wallet = wallet;
+        self.events.extend(updates);
+        Ok(total)
+    }
+
+    pub(crate) fn set_leverage(&mut self, symbol: &str, leverage: u32) -> Result<()> {
+        if !(1..=125).contains(&leverage) {
+            return Err(reject(BinanceErrorCode::InvalidLeverage, "Leverage is not valid."));
+        }
+        self.leverage.insert(symbol.to_string(), leverage);
+        Ok(())
+    }
+
+    pub(crate) fn set_dual_side(&mut self, dual_side: bool) -> Result<()> {
+        if dual_side == self.dual_side {
+            return Err(reject(BinanceErrorCode::NoNeedToChangePositionSide, "No need to change position side."));
+        }
+        if self.orders.values().any(|order| order.status.is_open()) {
+            return Err(reject(
+                BinanceErrorCode::PositionSideChangeExistsOpenOrders,
+                "Position side cannot be changed if there exists open orders.",
+            ));
+        }
+        if self.positions.values().any(|position| !position.size.is_zero()) {
+            return Err(reject(
+                BinanceErrorCode::PositionSideChangeExistsQuantity,
+                "Position side cannot be changed if there exists position.",
+            ));
+        }
+        self.dual_side = dual_side;
+        Ok(())
+    }
+
+    /// Initial margin of a position at the current price
+    pub(crate) fn position_margin(&self, position: &SimPosition) -> Result<Decimal> {
+        let price = self.mark_price(&position.symbol).unwrap_or(position.entry_price);
+        self.initial_margin(&position.symbol, in_range(mul(position.size.abs(), price))?)
+    }
+
+    pub(crate) fn order_margin(&self, order: &SimOrder) -> Result<Decimal> {
+        if order.reduce_only || !order.status.is_open() || !order.working() {
+            return Ok(Decimal::ZERO);
+        }
+        self.initial_margin(&order.symbol, in_range(mul(order.remaining()?, order.price))?)
+    }
+
+    fn initial_margin(&self, symbol: &str, notional: Decimal) -> Result<Decimal> {
+        // Dividing by a leverage of at least 1 cannot overflow; a zero leverage counts as 1x
+        Ok(div(notional, Decimal::new(self.leverage(symbol), 0)).unwrap_or(notional))
+    }
+
+    pub(crate) fn margin(&self) -> Result<Margin> {
+        let mut margin = Margin {
+            wallet_balance: self.wallet,
+            unrealized_pnl: Decimal::ZERO,
+            position_margin: Decimal::ZERO,
+            order_margin: Decimal::ZERO,
+        };
+        for position in self.positions.values() {
+            margin.unrealized_pnl = in_range(margin.unrealized_pnl.checked_add(self.unrealized_pnl(position)?))?;
+            margin.position_margin = in_range(margin.position_margin.checked_add(self.position_margin(position)?))?;
+        }
+        for order in self.orders.values() {
+            margin.order_margin = in_range(margin.order_margin.checked_add(self.order_margin(order)?))?;
+        }
+        Ok(margin)
+    }
+
+    /// Reject orders whose opening part needs more margin than is available
+    fn check_margin(&self, order: &SimOrder) -> Result<()> {
+        if order.reduce_only {
+            return Ok(());
+        }
+        let size = self.position_size(&order.symbol, order.position_side);
+        let closes = match order.side {
+            OrderSide::Buy => size.is_negative(),
+            OrderSide::Sell => !size.is_negative(),
+        };
+        let opening = match closes {
+            true => in_range(order.quantity.checked_sub(order.quantity.min(size.abs())))?,
+            false => order.quantity,
+        };
+        if opening.is_zero() {
+            return Ok(());
+        }
+
+        let price = match order.has_limit() {
+            false => self
+                .books
+                .get(&order.symbol)
+                .and_then(|book| book.opposite(order.side).first().map(|(price, _)| *price))
+                .or_else(|| self.last_prices.get(&order.symbol).copied())
+                .ok_or_else(|| reject(BinanceErrorCode::NoDepth, "No orders on book for symbol."))?,
+            true => order.price,
+        };
+        let required = self.initial_margin(&order.symbol, in_range(mul(opening, price))?)?;
+        if required > self.margin()?.available_balance()? {
+            return Err(reject(BinanceErrorCode::MarginNotSufficient, "Margin is insufficient."));
+        }
+        Ok(())
+    }
+
+    // Events
+
+    fn emit_order(&mut self, order_id: u64, execution_type: &str, trade: Option<Trade>) {
+        let order = &self.orders[&order_id];
+        let now = self.now();
+        let trade = trade.unwrap_or_default();
+        let data = OrderUpdateData {
+            symbol: order.symbol.clone(),
+            client_order_id: order.client_order_id.clone(),
+            side: order.side,
+            order_type: order.order_type,
+            time_in_force: order.time_in_force,
+            original_quantity: order.quantity,
+            original_price: order.price,
+            average_price: order.avg_price(),
+            stop_price: order.stop_price,
+            execution_type: execution_type.to_string(),
+            order_status: order.status,
+            order_id,
+            last_filled_quantity: trade.quantity,
+            cumulative_filled_quantity: order.executed,
+            last_filled_price: trade.price,
+            commission_amount: trade.fee,
+            commission_asset: (!trade.fee.is_zero()).then(|| self.asset.clone()),
+            order_trade_time: now,
+            trade_id: trade.trade_id,
+            bids_notional: Decimal::ZERO,
+            ask_notional: Decimal::ZERO,
+            is_maker: trade.maker,
+            reduce_only: order.reduce_only,
+            working_type: "CONTRACT_PRICE".to_string(),
+            original_order_type: order.order_type,
+            position_side: order.position_side,
+            close_position: false,
+            activation_price: order.activation_price,
+            callback_rate: order.callback_rate,
+            realized_profit: trade.realized,
+        };
+        self.events.push(WebSocketMessage::OrderUpdate(OrderUpdate {
+            event_type: "ORDER_TRADE_UPDATE".to_string(),
+            event_time: now,
+            transaction_time: now,
+            order: data,
+        }));
+    }
+
+    /// `ACCOUNT_UPDATE` for a balance change and the position it touched,
+    /// built before the change is applied
+    fn account_update(&self, reason: &str, wallet: Decimal, balance_change: Decimal, position: &SimPosition) -> Result<WebSocketMessage> {
+        let now = self.now();
+        let position = PositionUpdate {
+            symbol: position.symbol.clone(),
+            position_amount: position.size,
+            entry_price: position.entry_price,
+            accumulated_realized: position.realized_pnl,
+            unrealized_pnl: self.unrealized_pnl(position)?,
+            margin_type: "cross".to_string(),
+            isolated_wallet: Decimal::ZERO,
+            position_side: position.position_side,
+        };
+        Ok(WebSocketMessage::AccountUpdate(AccountUpdate {
+            event_type: "ACCOUNT_UPDATE".to_string(),
+            event_time: now,
+            transaction_time: now,
+            account_update: AccountUpdateData {
+                event_reason: reason.to_string(),
+                balances: vec![BalanceUpdate {
+                    asset: self.asset.clone(),
+                    wallet_balance: wallet,
+                    cross_wallet_balance: wallet,
+                    balance_change,
+                }],
+                positions: vec![position],
+            },
+        }))
+    }
+}
+
+/// Details of one fill for its order update
+#[derive(Debug, Clone, Copy, Default)]
+struct Trade {
+    quantity: Decimal,
+    price: Decimal,
+    fee: Decimal,
+    realized: Decimal,
+    trade_id: u64,
+    maker: bool,
+}
+
+pub(crate) fn reject(code: BinanceErrorCode, msg: impl Into<String>) -> BinanceError {
+    BinanceError::api(code.code(), msg)
+}
+
+fn unknown_order() -> BinanceError {
+    reject(BinanceErrorCode::CancelRejected, "Unknown order sent.")
+}
+
+/// `a * b` without trailing zeros, or `None` on overflow
+fn mul(a: Decimal, b: Decimal) -> Option<Decimal> {
+    Some(a.checked_mul(b)?.normalize())
+}
+
+/// `a / b` rounded to [`PRICE_SCALE`] decimals, or `None` on overflow or division by zero
+fn div(a: Decimal, b: Decimal) -> Option<Decimal> {
+    let quotient = a.checked_div(b)?;
+    Some(quotient.round_dp_with_strategy(PRICE_SCALE, RoundingStrategy::MidpointAwayFromZero).normalize())
+}
@@ -0,0 +1,359 @@
+//! Paper trading against live market data.
+//!
+//! [`PaperExchange`] is a [`Transport`](crate::client::Transport) that answers
+//! the order and account endpoints from a simulated account, so
+//! [`TradingApi`](crate::api::TradingApi), [`AccountApi`](crate::api::AccountApi)
+//! and everything built on them run unchanged. Orders fill against the book
+//! and trades fed in with [`PaperExchange::on_message`]; every change is
+//! published as the `ORDER_TRADE_UPDATE` and `ACCOUNT_UPDATE` messages the
+//! user data stream would deliver.
+//!
+//! LIMIT, MARKET, STOP, STOP_MARKET, TAKE_PROFIT, TAKE_PROFIT_MARKET and
+//! TRAILING_STOP_MARKET orders are supported, in one-way or hedge mode, with
+//! cross margin at a per-symbol leverage. Conditional orders trigger on the
+//! last trade price. There is no liquidation.
+
+pub(crate) mod engine;
+pub(crate) mod transport;
+
+use crate::client::{Credentials, HttpClient};
+use crate::error::Result;
+use crate::types::decimal::Decimal;
+use crate::types::market::OrderBook;
+use crate::websocket::types::WebSocketMessage;
+use crate::BinanceClient;
+use engine::Engine;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// Events kept for slow subscribers before they start missing some
+const EVENT_CAPACITY: usize = 1024;
+
+/// Starting balance, fees and leverage of a paper account
+#[derive(Debug, Clone)]
+pub struct PaperConfig {
+    asset: String,
+    balance: Decimal,
+    maker_fee: Decimal,
+    taker_fee: Decimal,
+    leverage: u32,
+}
+
+impl PaperConfig {
+    /// An account holding `balance` of the margin asset, with the default
+    /// fees (0.02% maker, 0.05% taker) and 20x leverage
+    pub fn new(asset: impl Into<String>, balance: Decimal) -> Self {
+        Self {
+            asset: asset.into(),
+            balance,
+            maker_fee: "0.0002".parse().expect("valid fee"),
+            taker_fee: "0.0005".parse().expect("valid fee"),
+            leverage: 20,
+        }
+    }
+
+    /// Maker fee as a fraction of notional, e.g. 0.0002
+    pub fn maker_fee(mut self, maker_fee: Decimal) -> Self {
+        self.maker_fee = maker_fee;
+        self
+    }
+
+    /// Taker fee as a fraction of notional, e.g. 0.0005
+    pub fn taker_fee(mut self, taker_fee: Decimal) -> Self {
+        self.taker_fee = taker_fee;
+        self
+    }
+
+    /// Leverage of symbols not changed through `change_leverage`
+    pub fn leverage(mut self, leverage: u32) -> Self {
+        self.leverage = leverage;
+        self
+    }
+}
+
+impl Default for PaperConfig {
+    fn default() -> Self {
+        Self::new("USDT", "10000".parse().expect("valid balance"))
+    }
+}
+
+/// Simulated exchange account. Clones share state.
+#[derive(Clone)]
+pub struct PaperExchange {
+    engine: Arc<Mutex<Engine>>,
+    events: broadcast::Sender<WebSocketMessage>,
+}
+
+impl PaperExchange {
+    pub fn new(config: PaperConfig) -> Self {
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        Self {
+            engine: Arc::new(Mutex::new(Engine::new(&config))),
+            events,
+        }
+    }
+
+    /// HTTP client whose signed requests go to this exchange
+    pub fn http_client(&self) -> HttpClient {
+        let credentials = Credentials::new("paper".to_string(), "paper".to_string());
+        HttpClient::new_with_credentials(credentials).with_transport(self.clone())
+    }
+
+    /// Client for [`trading`](BinanceClient::trading) and
+    /// [`account`](BinanceClient::account) calls against this exchange.
+    ///
+    /// Market data endpoints are not simulated; use a regular client for those.
+    pub fn client(&self) -> BinanceClient {
+        BinanceClient::from_http_client(self.http_client())
+    }
+
+    /// Receive the order and account updates of this exchange
+    pub fn subscribe(&self) -> broadcast::Receiver<WebSocketMessage> {
+        self.events.subscribe()
+    }
+
+    /// Feed a market data message from a [`StreamBuilder`](crate::websocket::StreamBuilder) stream.
+    ///
+    /// Depth updates are applied as diffs, so use the diff depth stream after
+    /// loading a snapshot with [`load_depth`](Self::load_depth). Returns whether
+    /// the message was market data, or an error if a fill it causes overflows
+    /// the account's balance, PnL or fees; that fill is then left out.
+    pub fn on_message(&self, message: &WebSocketMessage) -> Result<bool> {
+        match message {
+            WebSocketMessage::DepthUpdate(depth) => {
+                self.with_engine(|engine| engine.on_depth(&depth.symbol, &depth.bids, &depth.asks, false))?;
+                Ok(true)
+            }
+            WebSocketMessage::Trade(trade) => {
+                self.with_engine(|engine| engine.on_trade(&trade.symbol, &trade.price, &trade.quantity))?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Replace the book of `symbol` with a `depth` snapshot
+    pub fn load_depth(&self, symbol: &str, book: &OrderBook) -> Result<()> {
+        self.with_engine(|engine| engine.on_depth(symbol, &book.bids, &book.asks, true))
+    }
+
+    /// Run `f` on the engine and publish the events it produced
+    fn with_engine<T>(&self, f: impl FnOnce(&mut Engine) -> T) -> T {
+        let (result, events) = {
+            let mut engine = self.engine.lock().unwrap();
+            let result = f(&mut engine);
+            (result, engine.drain_events())
+        };
+        for event in events {
+            // No subscribers is fine
+            let _ = self.events.send(event);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::common::{OrderSide, OrderStatus};
+    use crate::types::order_builder::{kind, LimitTimeInForce, OrderBuilder};
+    use crate::types::trading::{CancelOrderRequest, NewOrderRequest};
+    use crate::websocket::types::{DepthUpdate, TradeStream};
+    use crate::BinanceErrorCode;
+
+    fn d(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    fn limit(side: OrderSide, quantity: &str, price: &str, time_in_force: LimitTimeInForce) -> OrderBuilder<kind::Limit> {
+        NewOrderRequest::limit("BTCUSDT".to_string(), side, d(quantity), d(price), time_in_force)
+    }
+
+    fn depth(bids: &[(&str, &str)], asks: &[(&str, &str)]) -> WebSocketMessage {
+        let levels = |levels: &[(&str, &str)]| levels.iter().map(|(p, q)| [d(p), d(q)]).collect();
+        WebSocketMessage::DepthUpdate(DepthUpdate {
+            event_type: "depthUpdate".to_string(),
+            event_time: 0,
+            transaction_time: 0,
+            symbol: "BTCUSDT".to_string(),
+            first_update_id: 0,
+            final_update_id: 0,
+            previous_final_update_id: 0,
+            bids: levels(bids),
+            asks: levels(asks),
+        })
+    }
+
+    fn trade(price: &str, quantity: &str) -> WebSocketMessage {
+        WebSocketMessage::Trade(TradeStream {
+            event_type: "trade".to_string(),
+            event_time: 0,
+            trade_time: 0,
+            symbol: "BTCUSDT".to_string(),
+            trade_id: 0,
+            price: d(price),
+            quantity: d(quantity),
+            buyer_order_id: 0,
+            seller_order_id: 0,
+            is_buyer_maker: false,
+        })
+    }
+
+    fn exchange() -> PaperExchange {
+        let exchange = PaperExchange::new(PaperConfig::new("USDT", d("10000")).leverage(10));
+        exchange.on_message(&depth(&[("49990", "1"), ("49980", "2")], &[("50010", "0.5"), ("50020", "2")])).unwrap();
+        exchange
+    }
+
+    #[tokio::test]
+    async fn test_market_order_walks_book_and_updates_account() {
+        let exchange = exchange();
+        let mut events = exchange.subscribe();
+        let client = exchange.client();
+
+        let order = client
+            .trading()
+            .new_order(NewOrderRequest::market("BTCUSDT".to_string(), OrderSide::Buy, d("1")))
+            .await
+            .unwrap();
+        assert_eq!(order.status, OrderStatus::Filled);
+        assert_eq!(order.avg_price.to_string(), "50015");
+
+        // NEW, then a trade and an account update per level
+        let mut order_updates = 0;
+        let mut account_updates = 0;
+        while let Ok(event) = events.try_recv() {
+            match event {
+                WebSocketMessage::OrderUpdate(_) => order_updates += 1,
+                WebSocketMessage::AccountUpdate(_) => account_updates += 1,
+                _ => {}
+            }
+        }
+        assert_eq!((order_updates, account_updates), (3, 2));
+
+        let positions = client.account().position_risk(Some("BTCUSDT")).await.unwrap();
+        assert_eq!(positions[0].position_amt.to_string(), "1");
+        assert_eq!(positions[0].entry_price.to_string(), "50015");
+        // Taker fee: 50015 * 0.0005
+        let balance = client.account().balance().await.unwrap();
+        assert_eq!(balance[0].balance.to_string(), "9974.9925");
+
+        // Closing at a profit realizes it into the wallet
+        exchange.on_message(&depth(&[("50100", "5")], &[])).unwrap();
+        let close = NewOrderRequest::market("BTCUSDT".to_string(), OrderSide::Sell, d("1"))
+            .reduce_only(true);
+        client.trading().new_order(close).await.unwrap();
+        let account = client.account().account_info().await.unwrap();
+        // 9974.9925 + 85 - 50100 * 0.0005
+        assert_eq!(account.total_wallet_balance.to_string(), "10034.9425");
+        assert!(client.account().position_risk(None).await.unwrap()[0].position_amt.to_string().starts_with('0'));
+    }
+
+    #[tokio::test]
+    async fn test_limit_order_rests_and_fills_on_trades() {
+        let exchange = exchange();
+        let trading = exchange.client().trading();
+
+        let bid = limit(OrderSide::Buy, "1", "49900", LimitTimeInForce::Gtc);
+        let order = trading.new_order(bid).await.unwrap();
+        assert_eq!(order.status, OrderStatus::New);
+        assert_eq!(trading.open_orders(Some("BTCUSDT")).await.unwrap().len(), 1);
+
+        // Trading at the limit price is not enough, trading through it is
+        exchange.on_message(&trade("49900", "3")).unwrap();
+        exchange.on_message(&trade("49890", "0.4")).unwrap();
+        let query = crate::types::trading::QueryOrderRequest::new("BTCUSDT".to_string()).order_id(order.order_id);
+        let order = trading.query_order(query).await.unwrap();
+        assert_eq!(order.status, OrderStatus::PartiallyFilled);
+        assert_eq!(order.executed_qty.to_string(), "0.4");
+
+        let canceled = trading
+            .cancel_order(CancelOrderRequest::new("BTCUSDT".to_string()).order_id(order.order_id))
+            .await
+            .unwrap();
+        assert_eq!(canceled.status, OrderStatus::Canceled);
+        let err = trading
+            .cancel_order(CancelOrderRequest::new("BTCUSDT".to_string()).order_id(order.order_id))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Some(BinanceErrorCode::CancelRejected));
+    }
+
+    #[tokio::test]
+    async fn test_all_orders_stream_yields_each_order_once() {
+        use futures_util::TryStreamExt;
+
+        let exchange = exchange();
+        let trading = exchange.client().trading();
+        for price in ["49000", "49100"] {
+            trading.new_order(limit(OrderSide::Buy, "0.1", price, LimitTimeInForce::Gtc)).await.unwrap();
+        }
+
+        // Each 7-day window only returns the orders placed within it
+        let now = crate::utils::get_timestamp();
+        let orders: Vec<_> = trading
+            .all_orders_stream("BTCUSDT", now - 21 * 24 * 60 * 60 * 1000, Some(now + 60_000))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(orders.len(), 2);
+        assert!(trading.all_orders("BTCUSDT", None, Some(0), Some(now - 60_000), None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_margin_and_order_rules() {
+        let exchange = exchange();
+        let trading = exchange.client().trading();
+
+        // 10000 USDT at 10x buys at most about 2 BTC
+        let too_big = limit(OrderSide::Buy, "3", "49000", LimitTimeInForce::Gtc);
+        let err = trading.new_order(too_big).await.unwrap_err();
+        assert_eq!(err.code(), Some(BinanceErrorCode::MarginNotSufficient));
+
+        let post_only = limit(OrderSide::Buy, "0.1", "50010", LimitTimeInForce::Gtx);
+        let err = trading.new_order(post_only).await.unwrap_err();
+        assert_eq!(err.code(), Some(BinanceErrorCode::GtxOrderReject));
+
+        let reduce_only = NewOrderRequest::market("BTCUSDT".to_string(), OrderSide::Sell, d("0.1"))
+            .reduce_only(true);
+        let err = trading.new_order(reduce_only).await.unwrap_err();
+        assert!(err.is_order_rejected());
+
+        // A sell stop above the current price would trigger at once
+        let stop = NewOrderRequest::stop_market("BTCUSDT".to_string(), OrderSide::Sell, d("0.1"), d("51000"));
+        let err = trading.new_order(stop).await.unwrap_err();
+        assert_eq!(err.code(), Some(BinanceErrorCode::OrderWouldImmediatelyTrigger));
+        let stop = NewOrderRequest::stop_market("BTCUSDT".to_string(), OrderSide::Sell, d("0.1"), d("49000"));
+        assert_eq!(trading.new_order(stop).await.unwrap().status, OrderStatus::New);
+
+        // IOC takes what it can and expires the rest
+        let ioc = limit(OrderSide::Buy, "1", "50010", LimitTimeInForce::Ioc);
+        let order = trading.new_order(ioc).await.unwrap();
+        assert_eq!(order.status, OrderStatus::Expired);
+        assert_eq!(order.executed_qty.to_string(), "0.5");
+    }
+
+    #[tokio::test]
+    async fn test_overflowing_fill_is_an_error() {
+        let exchange = exchange();
+        let client = exchange.client();
+        client.account().change_leverage("BTCUSDT", 125).await.unwrap();
+        exchange.on_message(&depth(&[], &[("50000", "30")])).unwrap();
+        let buy = NewOrderRequest::market("BTCUSDT".to_string(), OrderSide::Buy, d("20"));
+        client.trading().new_order(buy).await.unwrap();
+
+        // 20 at 5e27 is more than a Decimal holds, so the fill is refused
+        let sell = limit(OrderSide::Sell, "20", "5000000000000000000000000000", LimitTimeInForce::Gtc).reduce_only(true);
+        let order = client.trading().new_order(sell).await.unwrap();
+        let mut events = exchange.subscribe();
+        assert!(exchange.on_message(&trade("6000000000000000000000000000", "20")).is_err());
+        assert!(events.try_recv().is_err());
+
+        let query = crate::types::trading::QueryOrderRequest::new("BTCUSDT".to_string()).order_id(order.order_id);
+        let order = client.trading().query_order(query).await.unwrap();
+        assert_eq!(order.status, OrderStatus::New);
+        assert!(order.executed_qty.is_zero());
+        // So is the unrealized PnL of the position at that price
+        assert!(client.account().balance().await.is_err());
+    }
+}
@@ -0,0 +1,367 @@
+//! REST endpoints of [`PaperExchange`], answered from the engine
+
+use super::engine::{reject, Engine, SimOrder, SimPosition};
+use super::PaperExchange;
+use crate::client::{Transport, TransportRequest, TransportResponse};
+use crate::error::{BinanceError, BinanceErrorCode, Result};
+use crate::types::decimal::{in_range, Decimal};
+use crate::types::trading::{ModifyOrderRequest, NewOrderRequest, Order};
+use async_trait::async_trait;
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Request parameters by name
+type Params = HashMap<String, String>;
+
+#[async_trait]
+impl Transport for PaperExchange {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse> {
+        let params: Params = request.params().into_iter().collect();
+        let response = self.with_engine(|engine| route(engine, &request.method, &request.endpoint, &params));
+        Ok(match response {
+            Ok(body) => TransportResponse::new(200, body.to_string()),
+            Err(BinanceError::Api { code, msg, .. }) => error_response(code.code(), &msg),
+            Err(e) => error_response(BinanceErrorCode::UnknownError.code(), &e.to_string()),
+        }
+        .header("content-type", "application/json"))
+    }
+}
+
+fn error_response(code: i32, msg: &str) -> TransportResponse {
+    TransportResponse::new(400, json!({ "code": code, "msg": msg }).to_string())
+}
+
+fn route(engine: &mut Engine, method: &Method, endpoint: &str, params: &Params) -> Result<Value> {
+    match (method.as_str(), endpoint) {
+        ("GET", "/fapi/v1/ping") => Ok(json!({})),
+        ("GET", "/fapi/v1/time") => Ok(json!({ "serverTime": engine.now() })),
+
+        ("POST", "/fapi/v1/order") => Ok(order_json(&engine.place(&new_order(params)?)?)),
+        ("POST", "/fapi/v1/order/test") => engine.check(&new_order(params)?).map(|_| json!({})),
+        ("PUT", "/fapi/v1/order") => Ok(order_json(&engine.modify(&modify_order(params)?)?)),
+        ("DELETE", "/fapi/v1/order") => {
+            let order_id = optional(params, "orderId")?;
+            let order = engine.cancel(required(params, "symbol")?, order_id, str_param(params, "origClientOrderId"))?;
+            Ok(order_json(&order))
+        }
+        ("GET", "/fapi/v1/order") => {
+            let order_id = optional(params, "orderId")?;
+            let order = engine.find(required(params, "symbol")?, order_id, str_param(params, "origClientOrderId"))?;
+            Ok(order_json(order))
+        }
+        ("GET", "/fapi/v1/openOrders") => {
+            let orders = engine.orders(str_param(params, "symbol")).filter(|order| order.status.is_open());
+            Ok(orders.map(order_json).collect())
+        }
+        ("GET", "/fapi/v1/allOrders") => {
+            let from_id = optional::<u64>(params, "orderId")?.unwrap_or(0);
+            let start_time = optional::<u64>(params, "startTime")?.unwrap_or(0);
+            let end_time = optional::<u64>(params, "endTime")?.unwrap_or(u64::MAX);
+            let limit = optional(params, "limit")?.unwrap_or(500);
+            let orders = engine
+                .orders(Some(required(params, "symbol")?))
+                .filter(|order| order.order_id >= from_id && (start_time..=end_time).contains(&order.time))
+                .take(limit);
+            Ok(orders.map(order_json).collect())
+        }
+        ("DELETE", "/fapi/v1/allOpenOrders") => {
+            engine.cancel_all(required(params, "symbol")?);
+            Ok(json!({ "code": 200, "msg": "The operation of cancel all open order is done." }))
+        }
+        ("POST" | "PUT" | "DELETE", "/fapi/v1/batchOrders") => batch(engine, method, params),
+
+        ("GET", "/fapi/v2/account") => account_json(engine),
+        ("GET", "/fapi/v2/balance") => Ok(json!([balance_json(engine)?])),
+        ("GET", "/fapi/v2/positionRisk") => {
+            let positions = engine.positions(str_param(params, "symbol"));
+            positions.iter().map(|position| position_risk_json(engine, position)).collect()
+        }
+        ("GET", "/fapi/v1/commissionRate") => Ok(json!({
+            "symbol": required(params, "symbol")?,
+            "makerCommissionRate": engine.maker_fee.to_string(),
+            "takerCommissionRate": engine.taker_fee.to_string(),
+        })),
+        ("POST", "/fapi/v1/leverage") => {
+            let symbol = required(params, "symbol")?;
+            engine.set_leverage(symbol, parse_param(params, "leverage")?)?;
+            Ok(json!({ "symbol": symbol, "leverage": engine.leverage(symbol), "maxNotionalValue": "1000000000" }))
+        }
+        ("GET", "/fapi/v1/positionSide/dual") => Ok(json!({ "dualSidePosition": engine.dual_side })),
+        ("POST", "/fapi/v1/positionSide/dual") => {
+            engine.set_dual_side(parse_param(params, "dualSidePosition")?)?;
+            Ok(json!({ "code": 200, "msg": "success" }))
+        }
+
+        _ => Err(reject(
+            BinanceErrorCode::UnsupportedOperation,
+            format!("{} {} is not supported by the paper exchange.", method, endpoint),
+        )),
+    }
+}
+
+/// Run each entry of a batch request, collecting per-entry results
+fn batch(engine: &mut Engine, method: &Method, params: &Params) -> Result<Value> {
+    let item = |result: Result<SimOrder>| match result {
+        Ok(order) => order_json(&order),
+        Err(BinanceError::Api { code, msg, .. }) => json!({ "code": code.code(), "msg": msg }),
+        Err(e) => json!({ "code": BinanceErrorCode::UnknownError.code(), "msg": e.to_string() }),
+    };
+
+    if *method == Method::DELETE {
+        let symbol = required(params, "symbol")?;
+        let order_ids: Vec<u64> = optional_json(params, "orderIdList")?.unwrap_or_default();
+        let client_order_ids: Vec<String> = optional_json(params, "origClientOrderIdList")?.unwrap_or_default();
+        let mut results: Vec<Value> = order_ids
+            .into_iter()
+            .map(|order_id| item(engine.cancel(symbol, Some(order_id), None)))
+            .collect();
+        results.extend(client_order_ids.iter().map(|id| item(engine.cancel(symbol, None, Some(id)))));
+        return Ok(results.into());
+    }
+
+    let entries: Vec<Params> = optional_json(params, "batchOrders")?.ok_or_else(|| missing("batchOrders"))?;
+    let results = entries.iter().map(|entry| match *method {
+        Method::POST => item(new_order(entry).and_then(|order| engine.place(&order))),
+        _ => item(modify_order(entry).and_then(|modify| engine.modify(&modify))),
+    });
+    Ok(results.collect())
+}
+
+fn new_order(params: &Params) -> Result<NewOrderRequest> {
+    let mut order = NewOrderRequest::new(
+        required(params, "symbol")?.to_string(),
+        typed(params, "side")?.ok_or_else(|| missing("side"))?,
+        typed(params, "type")?.ok_or_else(|| missing("type"))?,
+    );
+    order.position_side = typed(params, "positionSide")?;
+    order.time_in_force = typed(params, "timeInForce")?;
+    order.quantity = optional(params, "quantity")?;
+    order.reduce_only = optional(params, "reduceOnly")?;
+    order.price = optional(params, "price")?;
+    order.new_client_order_id = str_param(params, "newClientOrderId").map(str::to_string);
+    order.stop_price = optional(params, "stopPrice")?;
+    order.close_position = optional(params, "closePosition")?;
+    order.activation_price = optional(params, "activationPrice")?;
+    order.callback_rate = optional(params, "callbackRate")?;
+    order.working_type = typed(params, "workingType")?;
+    order.price_match = typed(params, "priceMatch")?;
+    Ok(order)
+}
+
+fn modify_order(params: &Params) -> Result<ModifyOrderRequest> {
+    let mut modify = ModifyOrderRequest::new(
+        required(params, "symbol")?.to_string(),
+        typed(params, "side")?.ok_or_else(|| missing("side"))?,
+        parse_param(params, "quantity")?,
+    );
+    modify.order_id = optional(params, "orderId")?;
+    modify.orig_client_order_id = str_param(params, "origClientOrderId").map(str::to_string);
+    modify.price = optional(params, "price")?;
+    modify.price_match = typed(params, "priceMatch")?;
+    Ok(modify)
+}
+
+fn str_param<'a>(params: &'a Params, key: &str) -> Option<&'a str> {
+    params.get(key).map(String::as_str)
+}
+
+fn required<'a>(params: &'a Params, key: &str) -> Result<&'a str> {
+    str_param(params, key).ok_or_else(|| missing(key))
+}
+
+fn optional<T: std::str::FromStr>(params: &Params, key: &str) -> Result<Option<T>> {
+    str_param(params, key)
+        .map(|value| value.parse().map_err(|_| malformed(key)))
+        .transpose()
+}
+
+fn parse_param<T: std::str::FromStr>(params: &Params, key: &str) -> Result<T> {
+    optional(params, key)?.ok_or_else(|| missing(key))
+}
+
+/// Enum parameter, parsed from its wire name
+fn typed<T: DeserializeOwned>(params: &Params, key: &str) -> Result<Option<T>> {
+    str_param(params, key)
+        .map(|value| serde_json::from_value(Value::String(value.to_string())).map_err(|_| malformed(key)))
+        .transpose()
+}
+
+/// JSON-encoded parameter such as `batchOrders`
+fn optional_json<T: DeserializeOwned>(params: &Params, key: &str) -> Result<Option<T>> {
+    str_param(params, key)
+        .map(|value| serde_json::from_str(value).map_err(|_| malformed(key)))
+        .transpose()
+}
+
+fn missing(key: &str) -> BinanceError {
+    reject(
+        BinanceErrorCode::MandatoryParamEmptyOrMalformed,
+        format!("Mandatory parameter '{}' was not sent, was empty/null, or malformed.", key),
+    )
+}
+
+fn malformed(key: &str) -> BinanceError {
+    reject(BinanceErrorCode::IllegalChars, format!("Illegal characters found in parameter '{}'.", key))
+}
+
+fn num(value: Decimal) -> String {
+    value.normalize().to_string()
+}
+
+/// The REST view of a simulated order
+pub(crate) fn order(order: &SimOrder) -> Order {
+    serde_json::from_value(order_json(order)).expect("order JSON matches Order")
+}
+
+fn order_json(order: &SimOrder) -> Value {
+    json!({
+        "orderId": order.order_id,
+        "symbol": order.symbol,
+        "status": order.status,
+        "clientOrderId": order.client_order_id,
+        "price": num(order.price),
+        "avgPrice": num(order.avg_price()),
+        "origQty": num(order.quantity),
+        "executedQty": num(order.executed),
+        "cumQty": num(order.executed),
+        "cumQuote": num(order.cum_quote),
+        "timeInForce": order.time_in_force,
+        "type": order.order_type,
+        "origType": order.order_type,
+        "reduceOnly": order.reduce_only,
+        "closePosition": false,
+        "side": order.side,
+        "positionSide": order.position_side,
+        "stopPrice": num(order.stop_price),
+        "activatePrice": order.activation_price.map(num),
+        "priceRate": order.callback_rate.map(num),
+        "workingType": "CONTRACT_PRICE",
+        "priceProtect": false,
+        "priceMatch": "NONE",
+        "selfTradePreventionMode": "NONE",
+        "goodTillDate": 0,
+        "time": order.time,
+        "updateTime": order.update_time,
+    })
+}
+
+fn balance_json(engine: &Engine) -> Result<Value> {
+    let margin = engine.margin()?;
+    let available = margin.available_balance()?;
+    Ok(json!({
+        "accountAlias": "paper",
+        "asset": engine.asset,
+        "balance": num(margin.wallet_balance),
+        "crossWalletBalance": num(margin.wallet_balance),
+        "crossUnPnl": num(margin.unrealized_pnl),
+        "availableBalance": num(available),
+        "maxWithdrawAmount": num(available),
+        "marginAvailable": true,
+        "updateTime": engine.now(),
+    }))
+}
+
+fn account_json(engine: &Engine) -> Result<Value> {
+    let margin = engine.margin()?;
+    let initial_margin = margin.initial_margin()?;
+    let margin_balance = margin.margin_balance()?;
+    let available = margin.available_balance()?;
+    let now = engine.now();
+    let positions = engine
+        .positions(None)
+        .iter()
+        .map(|position| {
+            let open_order_margin = engine
+                .orders(Some(&position.symbol))
+                .filter(|order| order.position_side == position.position_side)
+                .try_fold(Decimal::ZERO, |sum, order| in_range(sum.checked_add(engine.order_margin(order)?)))?;
+            let position_margin = engine.position_margin(position)?;
+            Ok(json!({
+                "symbol": position.symbol,
+                "initialMargin": num(in_range(position_margin.checked_add(open_order_margin))?),
+                "maintMargin": "0",
+                "unrealizedPnl": num(engine.unrealized_pnl(position)?),
+                "positionInitialMargin": num(position_margin),
+                "openOrderInitialMargin": num(open_order_margin),
+                "leverage": engine.leverage(&position.symbol).to_string(),
+                "isolated": false,
+                "entryPrice": num(position.entry_price),
+                "maxNotional": "1000000000",
+                "positionSide": position.position_side,
+                "positionAmt": num(position.size),
+                "notional": num(notional(engine, position)?),
+                "isolatedWallet": "0",
+                "updateTime": position.update_time,
+                "bidNotional": "0",
+                "askNotional": "0",
+            }))
+        })
+        .collect::<Result<Vec<Value>>>()?;
+
+    Ok(json!({
+        "feeTier": 0,
+        "canTrade": true,
+        "canDeposit": true,
+        "canWithdraw": true,
+        "updateTime": now,
+        "totalInitialMargin": num(initial_margin),
+        "totalMaintMargin": "0",
+        "totalWalletBalance": num(margin.wallet_balance),
+        "totalUnrealizedPnl": num(margin.unrealized_pnl),
+        "totalMarginBalance": num(margin_balance),
+        "totalPositionInitialMargin": num(margin.position_margin),
+        "totalOpenOrderInitialMargin": num(margin.order_margin),
+        "totalCrossWalletBalance": num(margin.wallet_balance),
+        "totalCrossUnPnl": num(margin.unrealized_pnl),
+        "availableBalance": num(available),
+        "maxWithdrawAmount": num(available),
+        "assets": [{
+            "asset": engine.asset,
+            "walletBalance": num(margin.wallet_balance),
+            "unrealizedPnl": num(margin.unrealized_pnl),
+            "marginBalance": num(margin_balance),
+            "maintMargin": "0",
+            "initialMargin": num(initial_margin),
+            "positionInitialMargin": num(margin.position_margin),
+            "openOrderInitialMargin": num(margin.order_margin),
+            "crossWalletBalance": num(margin.wallet_balance),
+            "crossUnPnl": num(margin.unrealized_pnl),
+            "availableBalance": num(available),
+            "maxWithdrawAmount": num(available),
+            "marginAvailable": true,
+            "updateTime": now,
+        }],
+        "positions": positions,
+    }))
+}
+
+fn position_risk_json(engine: &Engine, position: &SimPosition) -> Result<Value> {
+    Ok(json!({
+        "symbol": position.symbol,
+        "positionAmt": num(position.size),
+        "entryPrice": num(position.entry_price),
+        "markPrice": num(engine.mark_price(&position.symbol).unwrap_or(Decimal::ZERO)),
+        "unRealizedPnl": num(engine.unrealized_pnl(position)?),
+        "liquidationPrice": "0",
+        "leverage": engine.leverage(&position.symbol).to_string(),
+        "maxNotionalValue": "1000000000",
+        "marginType": "cross",
+        "isolatedMargin": "0",
+        "isAutoAddMargin": false,
+        "positionSide": position.position_side,
+        "notional": num(notional(engine, position)?),
+        "isolatedWallet": "0",
+        "updateTime": position.update_time,
+        "bidNotional": "0",
+        "askNotional": "0",
+    }))
+}
+
+fn notional(engine: &Engine, position: &SimPosition) -> Result<Decimal> {
+    match engine.mark_price(&position.symbol) {
+        Some(mark) => in_range(position.size.checked_mul(mark)),
+        None => Ok(Decimal::ZERO),
+    }
+}
@@ -0,0 +1,629 @@
+//! Pre-trade risk checks applied by [`TradingApi`](crate::api::TradingApi) before an order is sent.
+//!
+//! Unlike [`OrderValidator`](crate::validation::OrderValidator), which mirrors
+//! the exchange's filters, these are limits chosen by the caller. An order that
+//! breaks one is returned as [`BinanceError::RiskRejected`] naming the rule
+//! that fired, and nothing is sent.
+
+use crate::api::MarketApi;
+use crate::state::{OrderManager, PositionTracker};
+use crate::error::{BinanceError, Result};
+use crate::types::common::{OrderSide, OrderType, PositionSide};
+use crate::types::decimal::{in_range, Decimal};
+use crate::types::trading::{ModifyOrderRequest, NewOrderRequest};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Limits for one symbol; `None` disables the check
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SymbolLimits {
+    /// Largest notional of a single order, in the quote asset
+    pub max_order_notional: Option<Decimal>,
+    /// Largest absolute position size an order may leave behind, counting
+    /// open orders on the same side as if they had filled
+    pub max_position: Option<Decimal>,
+    /// Most orders that may rest on the book at once
+    pub max_open_orders: Option<usize>,
+    /// Largest distance of an order price from the mark price, in percent
+    pub price_band_percent: Option<Decimal>,
+}
+
+impl SymbolLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_order_notional(mut self, max_order_notional: Decimal) -> Self {
+        self.max_order_notional = Some(max_order_notional);
+        self
+    }
+
+    pub fn max_position(mut self, max_position: Decimal) -> Self {
+        self.max_position = Some(max_position);
+        self
+    }
+
+    pub fn max_open_orders(mut self, max_open_orders: usize) -> Self {
+        self.max_open_orders = Some(max_open_orders);
+        self
+    }
+
+    pub fn price_band_percent(mut self, price_band_percent: Decimal) -> Self {
+        self.price_band_percent = Some(price_band_percent);
+        self
+    }
+
+    /// These limits, with unset ones taken from `defaults`
+    fn or(&self, defaults: &SymbolLimits) -> SymbolLimits {
+        SymbolLimits {
+            max_order_notional: self.max_order_notional.or(defaults.max_order_notional),
+            max_position: self.max_position.or(defaults.max_position),
+            max_open_orders: self.max_open_orders.or(defaults.max_open_orders),
+            price_band_percent: self.price_band_percent.or(defaults.price_band_percent),
+        }
+    }
+}
+
+/// Risk configuration: a symbol allow-list, default limits and per-symbol overrides
+#[derive(Debug, Clone, Default)]
+pub struct RiskLimits {
+    allowed_symbols: Option<HashSet<String>>,
+    defaults: SymbolLimits,
+    symbols: HashMap<String, SymbolLimits>,
+}
+
+impl RiskLimits {
+    /// No limits and every symbol allowed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only allow orders on these symbols
+    pub fn allow_symbols<I, S>(mut self, symbols: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_symbols
+            .get_or_insert_with(HashSet::new)
+            .extend(symbols.into_iter().map(Into::into));
+        self
+    }
+
+    /// Limits for symbols without an override
+    pub fn defaults(mut self, limits: SymbolLimits) -> Self {
+        self.defaults = limits;
+        self
+    }
+
+    /// Override limits for one symbol; unset fields fall back to the defaults
+    pub fn symbol(mut self, symbol: impl Into<String>, limits: SymbolLimits) -> Self {
+        self.symbols.insert(symbol.into(), limits);
+        self
+    }
+
+    pub fn is_allowed(&self, symbol: &str) -> bool {
+        self.allowed_symbols.as_ref().is_none_or(|allowed| allowed.contains(symbol))
+    }
+
+    /// Effective limits for `symbol`
+    pub fn limits_for(&self, symbol: &str) -> SymbolLimits {
+        match self.symbols.get(symbol) {
+            Some(limits) => limits.or(&self.defaults),
+            None => self.defaults.clone(),
+        }
+    }
+}
+
+/// The risk rule an order broke
+#[derive(Debug, Clone, PartialEq)]
+pub enum RiskViolation {
+    /// The symbol is not on the allow-list
+    SymbolNotAllowed { symbol: String },
+    /// The order's notional is above the per-order limit
+    MaxOrderNotional { symbol: String, notional: Decimal, limit: Decimal },
+    /// The order would leave a position larger than the limit
+    MaxPosition { symbol: String, position: Decimal, limit: Decimal },
+    /// The symbol already has the maximum number of open orders
+    MaxOpenOrders { symbol: String, open: usize, limit: usize },
+    /// The order price is too far from the mark price
+    PriceBand { symbol: String, price: Decimal, mark_price: Decimal, max_deviation_percent: Decimal },
+    /// A check needs the mark price but none is known; orders fail closed
+    MissingMarkPrice { symbol: String },
+    /// A modified order is not tracked, so the position it adds to is unknown;
+    /// the position check fails closed
+    UntrackedOrder { symbol: String },
+}
+
+impl RiskViolation {
+    pub fn symbol(&self) -> &str {
+        match self {
+            RiskViolation::SymbolNotAllowed { symbol }
+            | RiskViolation::MaxOrderNotional { symbol, .. }
+            | RiskViolation::MaxPosition { symbol, .. }
+            | RiskViolation::MaxOpenOrders { symbol, .. }
+            | RiskViolation::PriceBand { symbol, .. }
+            | RiskViolation::MissingMarkPrice { symbol }
+            | RiskViolation::UntrackedOrder { symbol } => symbol,
+        }
+    }
+}
+
+impl fmt::Display for RiskViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RiskViolation::SymbolNotAllowed { symbol } => write!(f, "{} is not an allowed symbol", symbol),
+            RiskViolation::MaxOrderNotional { symbol, notional, limit } => {
+                write!(f, "{} order notional {} is above the limit of {}", symbol, notional, limit)
+            }
+            RiskViolation::MaxPosition { symbol, position, limit } => {
+                write!(f, "{} position would be {}, above the limit of {}", symbol, position, limit)
+            }
+            RiskViolation::MaxOpenOrders { symbol, open, limit } => {
+                write!(f, "{} has {} open orders, the limit is {}", symbol, open, limit)
+            }
+            RiskViolation::PriceBand { symbol, price, mark_price, max_deviation_percent } => write!(
+                f,
+                "{} price {} is more than {}% away from the mark price {}",
+                symbol, price, max_deviation_percent, mark_price
+            ),
+            RiskViolation::MissingMarkPrice { symbol } => write!(f, "no mark price known for {}", symbol),
+            RiskViolation::UntrackedOrder { symbol } => {
+                write!(f, "{} order to modify is not tracked, its position side is unknown", symbol)
+            }
+        }
+    }
+}
+
+/// Checks orders against [`RiskLimits`] using live orders, positions and mark prices.
+///
+/// Open orders and positions come from the given [`OrderManager`] and
+/// [`PositionTracker`], so those should be fed from the user data stream.
+/// Mark prices are set with [`set_mark_price`](Self::set_mark_price) or
+/// [`refresh_mark_prices`](Self::refresh_mark_prices). Clones share state.
+#[derive(Debug, Clone)]
+pub struct RiskChecker {
+    limits: Arc<RiskLimits>,
+    orders: OrderManager,
+    positions: PositionTracker,
+    mark_prices: Arc<Mutex<HashMap<String, Decimal>>>,
+}
+
+/// What the checks need to know about an order or a modification
+struct Intent<'a> {
+    symbol: &'a str,
+    side: OrderSide,
+    /// `None` for a modification of an order that is not tracked
+    position_side: Option<PositionSide>,
+    quantity: Option<Decimal>,
+    /// How much the order adds to the position if it fills; for a modification,
+    /// the change in the order's total quantity, as its unfilled part already counts
+    added_quantity: Option<Decimal>,
+    price: Option<Decimal>,
+    stop_price: Option<Decimal>,
+    reduces_only: bool,
+    rests: bool,
+}
+
+impl<'a> Intent<'a> {
+    fn order(order: &'a NewOrderRequest) -> Result<Self> {
+        Ok(Self {
+            symbol: &order.symbol,
+            side: order.side,
+            position_side: Some(order.position_side.unwrap_or(PositionSide::Both)),
+            quantity: order.quantity,
+            added_quantity: order.quantity,
+            price: order.price,
+            stop_price: order.stop_price,
+            reduces_only: order.reduce_only == Some(true) || order.close_position == Some(true),
+            rests: order.order_type != OrderType::Market,
+        })
+    }
+
+    /// A modification of an order in `orders`; one that is not tracked counts in full,
+    /// on a position side that is not known
+    fn modify(modify: &'a ModifyOrderRequest, orders: &OrderManager) -> Result<Self> {
+        let existing = match (modify.order_id, &modify.orig_client_order_id) {
+            (Some(order_id), _) => orders.get(order_id),
+            (None, Some(client_order_id)) => orders.get_by_client_order_id(client_order_id),
+            (None, None) => None,
+        };
+        let quantity = modify.quantity;
+        // The new quantity is the order's total, and its unfilled part already
+        // counts as resting, so only the change in the total is new exposure
+        let added_quantity = match &existing {
+            Some(order) => in_range(quantity.checked_sub(order.orig_qty))?,
+            None => quantity,
+        };
+        Ok(Self {
+            symbol: &modify.symbol,
+            side: modify.side,
+            position_side: existing.map(|order| order.position_side),
+            quantity: Some(quantity),
+            added_quantity: Some(added_quantity),
+            price: modify.price,
+            stop_price: None,
+            reduces_only: false,
+            // The order already rests, so it does not add to the count
+            rests: false,
+        })
+    }
+}
+
+/// Orders earlier in the same batch
+#[derive(Default)]
+struct Pending {
+    open_orders: HashMap<String, usize>,
+    positions: HashMap<(String, PositionSide), Decimal>,
+}
+
+impl RiskChecker {
+    pub fn new(limits: RiskLimits, orders: OrderManager, positions: PositionTracker) -> Self {
+        Self {
+            limits: Arc::new(limits),
+            orders,
+            positions,
+            mark_prices: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn limits(&self) -> &RiskLimits {
+        &self.limits
+    }
+
+    pub fn set_mark_price(&self, symbol: impl Into<String>, mark_price: Decimal) {
+        self.mark_prices.lock().unwrap().insert(symbol.into(), mark_price);
+    }
+
+    pub fn mark_price(&self, symbol: &str) -> Option<Decimal> {
+        self.mark_prices.lock().unwrap().get(symbol).cloned()
+    }
+
+    /// Load the latest mark price of every symbol
+    pub async fn refresh_mark_prices(&self, market: &MarketApi) -> Result<()> {
+        let mark_prices = market.mark_price(None).await?;
+        let mut known = self.mark_prices.lock().unwrap();
+        for mark_price in mark_prices {
+            known.insert(mark_price.symbol, mark_price.mark_price);
+        }
+        Ok(())
+    }
+
+    /// Check a single new order
+    pub fn check(&self, order: &NewOrderRequest) -> Result<()> {
+        self.check_batch(std::slice::from_ref(order))
+    }
+
+    /// Check a batch of new orders as a whole: open order counts and
+    /// positions include the orders before each one
+    pub fn check_batch(&self, orders: &[NewOrderRequest]) -> Result<()> {
+        let mut pending = Pending::default();
+        for order in orders {
+            self.check_intent(&Intent::order(order)?, &mut pending)?;
+        }
+        Ok(())
+    }
+
+    /// Check a modification as if its new quantity and price were a new order,
+    /// except that only the change in its total quantity counts toward the position
+    pub fn check_modify(&self, modify: &ModifyOrderRequest) -> Result<()> {
+        self.check_modify_batch(std::slice::from_ref(modify))
+    }
+
+    pub fn check_modify_batch(&self, modifies: &[ModifyOrderRequest]) -> Result<()> {
+        let mut pending = Pending::default();
+        for modify in modifies {
+            self.check_intent(&Intent::modify(modify, &self.orders)?, &mut pending)?;
+        }
+        Ok(())
+    }
+
+    fn check_intent(&self, intent: &Intent<'_>, pending: &mut Pending) -> Result<()> {
+        let symbol = intent.symbol;
+        if !self.limits.is_allowed(symbol) {
+            return Err(rejected(RiskViolation::SymbolNotAllowed { symbol: symbol.to_string() }));
+        }
+        let limits = self.limits.limits_for(symbol);
+        let mark_price = || {
+            self.mark_price(symbol)
+                .ok_or_else(|| rejected(RiskViolation::MissingMarkPrice { symbol: symbol.to_string() }))
+        };
+
+        if let (Some(band), Some(price)) = (&limits.price_band_percent, intent.price) {
+            let mark = mark_price()?;
+            // |price - mark| / mark > band / 100, without dividing
+            let hundred = Decimal::new(100, 0);
+            let deviation = in_range(price.checked_sub(mark).and_then(|diff| diff.abs().checked_mul(hundred)))?;
+            if deviation > in_range(band.checked_mul(mark))? {
+                return Err(rejected(RiskViolation::PriceBand {
+                    symbol: symbol.to_string(),
+                    price,
+                    mark_price: mark,
+                    max_deviation_percent: *band,
+                }));
+            }
+        }
+
+        if let (Some(limit), Some(quantity)) = (&limits.max_order_notional, intent.quantity) {
+            let reference = match intent.price.or(intent.stop_price) {
+                Some(price) => price,
+                None => mark_price()?,
+            };
+            let notional = in_range(reference.checked_mul(quantity))?;
+            if notional > *limit {
+                return Err(rejected(RiskViolation::MaxOrderNotional {
+                    symbol: symbol.to_string(),
+                    notional: notional.normalize(),
+                    limit: *limit,
+                }));
+            }
+        }
+
+        if let (Some(limit), Some(quantity), false) =
+            (&limits.max_position, intent.added_quantity, intent.reduces_only)
+        {
+            let position_side = intent
+                .position_side
+                .ok_or_else(|| rejected(RiskViolation::UntrackedOrder { symbol: symbol.to_string() }))?;
+            let key = (symbol.to_string(), position_side);
+            let earlier = pending.positions.get(&key).copied().unwrap_or(Decimal::ZERO);
+            let position = self.current_position(symbol, position_side)?;
+            let resting = self.resting_quantity(symbol, position_side, intent.side)?;
+            let current = in_range(position.checked_add(resting).and_then(|current| current.checked_add(earlier)))?;
+            let delta = match intent.side {
+                OrderSide::Buy => quantity,
+                OrderSide::Sell => in_range(Decimal::ZERO.checked_sub(quantity))?,
+            };
+            let projected = in_range(current.checked_add(delta))?;
+            // Orders that shrink the position are always allowed
+            if projected.abs() > *limit && projected.abs() > current.abs() {
+                return Err(rejected(RiskViolation::MaxPosition {
+                    symbol: symbol.to_string(),
+                    position: projected.normalize(),
+                    limit: *limit,
+                }));
+            }
+            pending.positions.insert(key, in_range(earlier.checked_add(delta))?);
+        }
+
+        if let (Some(limit), true) = (limits.max_open_orders, intent.rests) {
+            let open = self.orders.open_order_count(Some(symbol)) + pending.open_orders.get(symbol).copied().unwrap_or(0);
+            if open >= limit {
+                return Err(rejected(RiskViolation::MaxOpenOrders { symbol: symbol.to_string(), open, limit }));
+            }
+            *pending.open_orders.entry(symbol.to_string()).or_default() += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Position the order adds to: the net size in one-way mode, one side in hedge mode
+    fn current_position(&self, symbol: &str, position_side: PositionSide) -> Result<Decimal> {
+        match position_side {
+            PositionSide::Both => self.positions.net_size(symbol),
+            side => Ok(self
+                .positions
+                .position(symbol, side)
+                .map_or(Decimal::ZERO, |position| position.size)),
+        }
+    }
+
+    /// Unfilled quantity of the open orders on `side`, signed like a position.
+    /// Orders on the other side are left out, as they may never fill.
+    fn resting_quantity(&self, symbol: &str, position_side: PositionSide, side: OrderSide) -> Result<Decimal> {
+        let mut resting = Decimal::ZERO;
+        for order in self.orders.open_orders(Some(symbol)) {
+            if order.position_side != position_side || order.side != side {
+                continue;
+            }
+            let remaining = order.remaining_qty()?;
+            resting = in_range(match side {
+                OrderSide::Buy => resting.checked_add(remaining),
+                OrderSide::Sell => resting.checked_sub(remaining),
+            })?;
+        }
+        Ok(resting)
+    }
+}
+
+fn rejected(violation: RiskViolation) -> BinanceError {
+    BinanceError::RiskRejected(violation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order_builder::LimitTimeInForce;
+    use crate::websocket::types::OrderUpdateData;
+
+    fn d(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    fn limit_order(side: OrderSide, quantity: &str, price: &str) -> NewOrderRequest {
+        limit("BTCUSDT", side, quantity, price)
+    }
+
+    fn limit(symbol: &str, side: OrderSide, quantity: &str, price: &str) -> NewOrderRequest {
+        NewOrderRequest::limit(symbol.to_string(), side, d(quantity), d(price), LimitTimeInForce::Gtc).build()
+    }
+
+    fn violation(result: Result<()>) -> RiskViolation {
+        match result {
+            Err(BinanceError::RiskRejected(violation)) => violation,
+            other => panic!("expected a risk rejection, got {:?}", other),
+        }
+    }
+
+    /// A resting limit order on the user data stream, `filled` of `quantity` executed
+    fn resting_order(
+        order_id: u64,
+        side: OrderSide,
+        position_side: &str,
+        quantity: &str,
+        filled: &str,
+    ) -> OrderUpdateData {
+        let side = match side {
+            OrderSide::Buy => "BUY",
+            OrderSide::Sell => "SELL",
+        };
+        let status = if filled == "0" { "NEW" } else { "PARTIALLY_FILLED" };
+        serde_json::from_value(serde_json::json!({
+            "s": "BTCUSDT", "c": format!("resting-{}", order_id), "S": side, "o": "LIMIT", "f": "GTC",
+            "q": quantity, "p": "51000", "ap": "0", "sp": "0", "x": status,
+            "X": status, "i": order_id, "l": "0", "z": filled, "L": "0", "n": "0",
+            "T": 2, "t": 0, "b": "0", "a": "0",
+            "m": false, "R": false, "wt": "CONTRACT_PRICE", "ot": "LIMIT", "ps": position_side,
+            "cp": false, "rp": "0"
+        }))
+        .unwrap()
+    }
+
+    fn fill(checker: &RiskChecker, position_side: PositionSide, side: OrderSide, quantity: &str) {
+        checker.positions.apply_fill(&crate::state::PositionFill {
+            symbol: "BTCUSDT".to_string(),
+            position_side,
+            side,
+            quantity: d(quantity),
+            price: d("50000"),
+            commission: d("0"),
+            commission_asset: None,
+            time: 1,
+        })
+        .unwrap();
+    }
+
+    fn checker(limits: RiskLimits) -> RiskChecker {
+        let checker = RiskChecker::new(limits, OrderManager::new(), PositionTracker::new());
+        checker.set_mark_price("BTCUSDT", d("50000"));
+        checker
+    }
+
+    #[test]
+    fn test_allow_list_and_symbol_overrides() {
+        let limits = RiskLimits::new()
+            .allow_symbols(["BTCUSDT", "ETHUSDT"])
+            .defaults(SymbolLimits::new().max_order_notional(d("1000")).max_open_orders(5))
+            .symbol("BTCUSDT", SymbolLimits::new().max_order_notional(d("100000")));
+        let checker = checker(limits);
+
+        let eth = limit("ETHUSDT", OrderSide::Buy, "1", "3000");
+        assert_eq!(
+            violation(checker.check(&eth)),
+            RiskViolation::MaxOrderNotional { symbol: "ETHUSDT".to_string(), notional: d("3000"), limit: d("1000") }
+        );
+        assert!(checker.check(&limit_order(OrderSide::Buy, "1", "50000")).is_ok());
+        assert_eq!(checker.limits().limits_for("BTCUSDT").max_open_orders, Some(5));
+
+        let sol = NewOrderRequest::market("SOLUSDT".to_string(), OrderSide::Buy, d("1")).build();
+        assert_eq!(
+            violation(checker.check(&sol)),
+            RiskViolation::SymbolNotAllowed { symbol: "SOLUSDT".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_price_band_and_missing_mark_price() {
+        let checker = checker(RiskLimits::new().defaults(SymbolLimits::new().price_band_percent(d("5"))));
+
+        assert!(checker.check(&limit_order(OrderSide::Buy, "1", "52500")).is_ok());
+        let err = checker.check(&limit_order(OrderSide::Sell, "1", "5000")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Risk check failed: BTCUSDT price 5000 is more than 5% away from the mark price 50000"
+        );
+
+        let eth = limit("ETHUSDT", OrderSide::Buy, "1", "3000");
+        assert_eq!(
+            violation(checker.check(&eth)),
+            RiskViolation::MissingMarkPrice { symbol: "ETHUSDT".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_batch_is_cumulative() {
+        let limits = RiskLimits::new().defaults(SymbolLimits::new().max_position(d("1")).max_open_orders(2));
+        let checker = checker(limits);
+        fill(&checker, PositionSide::Both, OrderSide::Buy, "0.5");
+
+        // Each order fits alone, together they exceed the position limit
+        let batch = [limit_order(OrderSide::Buy, "0.3", "50000"), limit_order(OrderSide::Buy, "0.3", "50000")];
+        assert!(checker.check(&batch[0]).is_ok());
+        assert_eq!(
+            violation(checker.check_batch(&batch)),
+            RiskViolation::MaxPosition { symbol: "BTCUSDT".to_string(), position: d("1.1"), limit: d("1") }
+        );
+
+        // Reducing is always allowed
+        assert!(checker.check(&limit_order(OrderSide::Sell, "2", "50000")).is_err());
+        assert!(checker.check(&limit_order(OrderSide::Sell, "1", "50000")).is_ok());
+
+        let batch = [
+            limit_order(OrderSide::Sell, "0.1", "50000"),
+            limit_order(OrderSide::Sell, "0.1", "50000"),
+            limit_order(OrderSide::Sell, "0.1", "50000"),
+        ];
+        assert_eq!(
+            violation(checker.check_batch(&batch)),
+            RiskViolation::MaxOpenOrders { symbol: "BTCUSDT".to_string(), open: 2, limit: 2 }
+        );
+
+        let update = resting_order(1, OrderSide::Sell, "BOTH", "0.1", "0");
+        checker.orders.apply_update(&update).unwrap();
+        assert!(checker.check_batch(&batch[..1]).is_ok());
+        assert!(checker.check_batch(&batch[..2]).is_err());
+    }
+
+    #[test]
+    fn test_modify_counts_only_the_change_in_total_quantity() {
+        let checker = checker(RiskLimits::new().defaults(SymbolLimits::new().max_position(d("1"))));
+        fill(&checker, PositionSide::Long, OrderSide::Buy, "0.3");
+        checker.orders.apply_update(&resting_order(7, OrderSide::Buy, "LONG", "0.8", "0.3")).unwrap();
+
+        // The 0.3 filled and the 0.5 unfilled already count, so a price-only
+        // modify projects 0.8 and growing the order to 1.0 reaches the limit
+        for quantity in ["0.8", "1.0", "0.5"] {
+            let mut modify = ModifyOrderRequest::new("BTCUSDT".to_string(), OrderSide::Buy, d(quantity));
+            modify.order_id = Some(7);
+            assert!(checker.check_modify(&modify).is_ok(), "modify to {}", quantity);
+        }
+
+        let mut modify = ModifyOrderRequest::new("BTCUSDT".to_string(), OrderSide::Buy, d("1.1"));
+        modify.orig_client_order_id = Some("resting-7".to_string());
+        assert_eq!(
+            violation(checker.check_modify(&modify)),
+            RiskViolation::MaxPosition { symbol: "BTCUSDT".to_string(), position: d("1.1"), limit: d("1") }
+        );
+
+        // For an order that is not tracked, even the position side is unknown
+        let mut modify = ModifyOrderRequest::new("BTCUSDT".to_string(), OrderSide::Buy, d("0.1"));
+        modify.order_id = Some(99);
+        assert_eq!(
+            violation(checker.check_modify(&modify)),
+            RiskViolation::UntrackedOrder { symbol: "BTCUSDT".to_string() }
+        );
+
+        // Without a position limit the side does not matter
+        let unlimited = RiskChecker::new(RiskLimits::new(), checker.orders.clone(), checker.positions.clone());
+        assert!(unlimited.check_modify(&modify).is_ok());
+    }
+
+    #[test]
+    fn test_position_limit_counts_open_orders_on_the_same_side() {
+        let checker = checker(RiskLimits::new().defaults(SymbolLimits::new().max_position(d("1"))));
+        checker.orders.apply_update(&resting_order(1, OrderSide::Buy, "BOTH", "0.6", "0.1")).unwrap();
+        checker.orders.apply_update(&resting_order(2, OrderSide::Sell, "BOTH", "0.4", "0")).unwrap();
+
+        // The resting sell may never fill, so it does not make room for more buying
+        assert!(checker.check(&limit_order(OrderSide::Buy, "0.5", "50000")).is_ok());
+        assert_eq!(
+            violation(checker.check(&limit_order(OrderSide::Buy, "0.6", "50000"))),
+            RiskViolation::MaxPosition { symbol: "BTCUSDT".to_string(), position: d("1.1"), limit: d("1") }
+        );
+        assert_eq!(
+            violation(checker.check(&limit_order(OrderSide::Sell, "0.7", "50000"))),
+            RiskViolation::MaxPosition { symbol: "BTCUSDT".to_string(), position: d("-1.1"), limit: d("1") }
+        );
+    }
+}
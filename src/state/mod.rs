@@ -0,0 +1,8 @@
+//! Local copies of the account's orders and positions, kept current from the
+//! user data stream and reconciled against REST snapshots
+
+pub mod order_manager;
+pub mod position_tracker;
+
+pub use order_manager::{OrderFill, OrderManager, ReconcileHandle, ReconcileReport, TrackedOrder};
+pub use position_tracker::{Position, PositionFill, PositionMismatch, PositionTracker};
@@ -0,0 +1,541 @@
+use crate::api::trading::TradingApi;
+use crate::error::{BinanceErrorCode, Result};
+use crate::types::common::{OrderSide, OrderStatus, OrderType, PositionSide};
+use crate::types::decimal::{in_range, Decimal};
+use crate::types::trading::{Order, QueryOrderRequest};
+use crate::websocket::types::{OrderUpdateData, WebSocketMessage};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+/// Number of reconciliation results buffered before new ones are dropped
+const REPORT_CHANNEL_CAPACITY: usize = 32;
+
+/// One execution of a tracked order
+#[derive(Debug, Clone)]
+pub struct OrderFill {
+    pub trade_id: u64,
+    pub price: Decimal,
+    pub quantity: Decimal,
+    pub commission: Decimal,
+    pub commission_asset: Option<String>,
+    pub realized_profit: Decimal,
+    pub is_maker: bool,
+    pub time: u64,
+}
+
+/// Local view of an order, built from `ORDER_TRADE_UPDATE` events and REST snapshots
+#[derive(Debug, Clone)]
+pub struct TrackedOrder {
+    pub symbol: String,
+    pub order_id: u64,
+    pub client_order_id: String,
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    pub position_side: PositionSide,
+    pub price: Decimal,
+    pub stop_price: Decimal,
+    pub orig_qty: Decimal,
+    pub status: OrderStatus,
+    pub filled_qty: Decimal,
+    pub avg_price: Decimal,
+    pub realized_pnl: Decimal,
+    /// Commission paid per asset
+    pub fees: HashMap<String, Decimal>,
+    /// Fills seen on the user data stream; orders first seen through REST may miss earlier fills
+    pub fills: Vec<OrderFill>,
+    pub update_time: u64,
+}
+
+impl TrackedOrder {
+    fn from_update(update: &OrderUpdateData) -> Self {
+        Self {
+            symbol: update.symbol.clone(),
+            order_id: update.order_id,
+            client_order_id: update.client_order_id.clone(),
+            side: update.side,
+            order_type: update.original_order_type,
+            position_side: update.position_side,
+            price: update.original_price,
+            stop_price: update.stop_price,
+            orig_qty: update.original_quantity,
+            status: OrderStatus::New,
+            filled_qty: Decimal::ZERO,
+            avg_price: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            fees: HashMap::new(),
+            fills: Vec::new(),
+            update_time: 0,
+        }
+    }
+
+    fn from_order(order: &Order) -> Self {
+        Self {
+            symbol: order.symbol.clone(),
+            order_id: order.order_id,
+            client_order_id: order.client_order_id.clone(),
+            side: order.side,
+            order_type: order.orig_type,
+            position_side: order.position_side,
+            price: order.price,
+            stop_price: order.stop_price,
+            orig_qty: order.orig_qty,
+            status: OrderStatus::New,
+            filled_qty: Decimal::ZERO,
+            avg_price: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            fees: HashMap::new(),
+            fills: Vec::new(),
+            update_time: 0,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.status.is_open()
+    }
+
+    /// Quantity still waiting to be filled
+    pub fn remaining_qty(&self) -> Result<Decimal> {
+        in_range(self.orig_qty.checked_sub(self.filled_qty))
+    }
+
+    /// Move to `status` unless that would go back, e.g. from FILLED to NEW after a late event
+    fn advance_status(&mut self, status: OrderStatus) -> bool {
+        if self.status.is_final() || status_rank(status) < status_rank(self.status) || status == self.status {
+            return false;
+        }
+        self.status = status;
+        true
+    }
+
+    /// Take a cumulative fill quantity unless it is older than what is already known
+    fn advance_filled(&mut self, filled_qty: &Decimal, avg_price: &Decimal) -> bool {
+        if *filled_qty <= self.filled_qty {
+            return false;
+        }
+        self.filled_qty = *filled_qty;
+        self.avg_price = *avg_price;
+        true
+    }
+
+    fn apply_update(&mut self, update: &OrderUpdateData) -> Result<bool> {
+        let mut changed = false;
+
+        if update.execution_type == "TRADE" && !self.fills.iter().any(|fill| fill.trade_id == update.trade_id) {
+            // Sum first, so a fill that overflows leaves the order untouched
+            let realized_pnl = in_range(self.realized_pnl.checked_add(update.realized_profit))?;
+            let fee = match &update.commission_asset {
+                Some(asset) => {
+                    let paid = self.fees.get(asset).copied().unwrap_or(Decimal::ZERO);
+                    Some((asset.clone(), in_range(paid.checked_add(update.commission_amount))?))
+                }
+                None => None,
+            };
+            self.realized_pnl = realized_pnl;
+            if let Some((asset, fee)) = fee {
+                self.fees.insert(asset, fee);
+            }
+            self.fills.push(OrderFill {
+                trade_id: update.trade_id,
+                price: update.last_filled_price,
+                quantity: update.last_filled_quantity,
+                commission: update.commission_amount,
+                commission_asset: update.commission_asset.clone(),
+                realized_profit: update.realized_profit,
+                is_maker: update.is_maker,
+                time: update.order_trade_time,
+            });
+            changed = true;
+        }
+
+        if update.execution_type == "AMENDMENT" && self.is_open() {
+            self.price = update.original_price;
+            self.orig_qty = update.original_quantity;
+            changed = true;
+        }
+
+        changed |= self.advance_filled(&update.cumulative_filled_quantity, &update.average_price);
+        changed |= self.advance_status(update.order_status);
+        self.update_time = self.update_time.max(update.order_trade_time);
+        Ok(changed)
+    }
+
+    fn apply_order(&mut self, order: &Order) -> bool {
+        let mut changed = false;
+
+        if self.is_open() && (self.price != order.price || self.orig_qty != order.orig_qty) {
+            self.price = order.price;
+            self.orig_qty = order.orig_qty;
+            changed = true;
+        }
+
+        changed |= self.advance_filled(&order.executed_qty, &order.avg_price);
+        changed |= self.advance_status(order.status);
+        self.update_time = self.update_time.max(order.update_time);
+        changed
+    }
+}
+
+/// Differences found by [`OrderManager::reconcile`]
+#[derive(Debug, Clone, Default)]
+pub struct ReconcileReport {
+    /// Open on the exchange but unknown locally
+    pub added: Vec<u64>,
+    /// Known locally but with a stale status, fill or price
+    pub updated: Vec<u64>,
+    /// Open locally but already closed on the exchange
+    pub closed: Vec<u64>,
+    /// Open locally but unknown to the exchange, and dropped
+    pub removed: Vec<u64>,
+}
+
+impl ReconcileReport {
+    /// Whether local state already matched the exchange
+    pub fn is_clean(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.closed.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[derive(Debug, Default)]
+struct OrderBook {
+    orders: HashMap<u64, TrackedOrder>,
+    client_order_ids: HashMap<String, u64>,
+}
+
+impl OrderBook {
+    fn entry(&mut self, order_id: u64, create: impl FnOnce() -> TrackedOrder) -> (&mut TrackedOrder, bool) {
+        let inserted = !self.orders.contains_key(&order_id);
+        let order = self.orders.entry(order_id).or_insert_with(create);
+        // A client order id can be reused once its order is closed; it always refers to the latest order
+        self.client_order_ids.insert(order.client_order_id.clone(), order_id);
+        (order, inserted)
+    }
+
+    fn remove(&mut self, order_id: u64) -> Option<TrackedOrder> {
+        let order = self.orders.remove(&order_id)?;
+        if self.client_order_ids.get(&order.client_order_id) == Some(&order_id) {
+            self.client_order_ids.remove(&order.client_order_id);
+        }
+        Some(order)
+    }
+}
+
+/// Local open-order book driven by `ORDER_TRADE_UPDATE` events.
+///
+/// Orders move through NEW, PARTIALLY_FILLED and a final state; late or
+/// duplicate events never move an order backwards or count a fill twice.
+/// Call [`reconcile`](Self::reconcile) now and then, or
+/// [`spawn_reconcile`](Self::spawn_reconcile), to repair drift after a
+/// missed event. Clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct OrderManager {
+    book: Arc<Mutex<OrderBook>>,
+}
+
+impl OrderManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply an order update from the user data stream; returns whether anything changed.
+    ///
+    /// Fails, without counting the fill, if the order's realized PnL or fees overflow.
+    pub fn apply_update(&self, update: &OrderUpdateData) -> Result<bool> {
+        let mut book = self.book.lock().unwrap();
+        let (order, inserted) = book.entry(update.order_id, || TrackedOrder::from_update(update));
+        Ok(order.apply_update(update)? || inserted)
+    }
+
+    /// Apply a user data stream message, ignoring anything but order updates
+    pub fn on_message(&self, message: &WebSocketMessage) -> Result<bool> {
+        match message {
+            WebSocketMessage::OrderUpdate(event) => self.apply_update(&event.order),
+            _ => Ok(false),
+        }
+    }
+
+    /// Track an order returned by the REST API, e.g. from `new_order`; returns whether anything changed
+    pub fn apply_order(&self, order: &Order) -> bool {
+        let mut book = self.book.lock().unwrap();
+        let (tracked, inserted) = book.entry(order.order_id, || TrackedOrder::from_order(order));
+        tracked.apply_order(order) || inserted
+    }
+
+    pub fn get(&self, order_id: u64) -> Option<TrackedOrder> {
+        self.book.lock().unwrap().orders.get(&order_id).cloned()
+    }
+
+    pub fn get_by_client_order_id(&self, client_order_id: &str) -> Option<TrackedOrder> {
+        let book = self.book.lock().unwrap();
+        let order_id = book.client_order_ids.get(client_order_id)?;
+        book.orders.get(order_id).cloned()
+    }
+
+    /// All tracked orders, oldest order id first
+    pub fn orders(&self) -> Vec<TrackedOrder> {
+        self.select(|_| true)
+    }
+
+    /// Open orders, on one symbol or all, oldest order id first
+    pub fn open_orders(&self, symbol: Option<&str>) -> Vec<TrackedOrder> {
+        self.select(|order| order.is_open() && symbol.is_none_or(|symbol| order.symbol == symbol))
+    }
+
+    /// Number of open orders, on one symbol or all
+    pub fn open_order_count(&self, symbol: Option<&str>) -> usize {
+        self.book
+            .lock()
+            .unwrap()
+            .orders
+            .values()
+            .filter(|order| order.is_open() && symbol.is_none_or(|symbol| order.symbol == symbol))
+            .count()
+    }
+
+    /// Forget orders in a final state; returns how many were removed
+    pub fn remove_closed(&self) -> usize {
+        let mut book = self.book.lock().unwrap();
+        let closed: Vec<u64> = book.orders.values().filter(|o| !o.is_open()).map(|o| o.order_id).collect();
+        for order_id in &closed {
+            book.remove(*order_id);
+        }
+        closed.len()
+    }
+
+    fn select(&self, filter: impl Fn(&TrackedOrder) -> bool) -> Vec<TrackedOrder> {
+        let mut orders: Vec<TrackedOrder> =
+            self.book.lock().unwrap().orders.values().filter(|order| filter(order)).cloned().collect();
+        orders.sort_by_key(|order| order.order_id);
+        orders
+    }
+
+    /// Compare local state with `open_orders` and fix any drift.
+    ///
+    /// Orders open locally but missing from the exchange are queried one by
+    /// one; those Binance no longer knows are dropped. A stale snapshot never
+    /// undoes newer events applied in the meantime.
+    pub async fn reconcile(&self, trading: &TradingApi, symbol: Option<&str>) -> Result<ReconcileReport> {
+        let mut report = ReconcileReport::default();
+        let exchange_open = trading.open_orders(symbol).await?;
+        let exchange_ids: HashSet<u64> = exchange_open.iter().map(|order| order.order_id).collect();
+
+        for order in &exchange_open {
+            let known = self.get(order.order_id).is_some();
+            if self.apply_order(order) {
+                if known {
+                    report.updated.push(order.order_id);
+                } else {
+                    report.added.push(order.order_id);
+                }
+            }
+        }
+
+        let missing: Vec<TrackedOrder> = self
+            .open_orders(symbol)
+            .into_iter()
+            .filter(|order| !exchange_ids.contains(&order.order_id))
+            .collect();
+        for order in missing {
+            let query = QueryOrderRequest::new(order.symbol.clone()).order_id(order.order_id);
+            match trading.query_order(query).await {
+                Ok(snapshot) => {
+                    if self.apply_order(&snapshot) && snapshot.status.is_final() {
+                        report.closed.push(order.order_id);
+                    }
+                }
+                Err(e) if e.code() == Some(BinanceErrorCode::NoSuchOrder) => {
+                    self.book.lock().unwrap().remove(order.order_id);
+                    report.removed.push(order.order_id);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Reconcile every `interval` in a background task, starting immediately.
+    ///
+    /// Only reports with drift, and failures, are passed to the handle.
+    /// Must be called within a Tokio runtime.
+    pub fn spawn_reconcile(&self, trading: TradingApi, symbol: Option<String>, interval: Duration) -> ReconcileHandle {
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+        let (report_tx, report_rx) = mpsc::channel(REPORT_CHANNEL_CAPACITY);
+        let manager = self.clone();
+
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                tokio::select! {
+                    // Resolves on an explicit stop and when the handle is dropped
+                    _ = &mut shutdown_rx => break,
+                    _ = ticker.tick() => {
+                        let result = manager.reconcile(&trading, symbol.as_deref()).await;
+                        if !matches!(&result, Ok(report) if report.is_clean()) {
+                            // A full channel means nobody is reading; drop rather than block reconciliation
+                            let _ = report_tx.try_send(result);
+                        }
+                    }
+                }
+            }
+        });
+
+        ReconcileHandle {
+            shutdown: Some(shutdown_tx),
+            reports: report_rx,
+            task,
+        }
+    }
+}
+
+/// Handle to a background reconciliation started by [`OrderManager::spawn_reconcile`].
+///
+/// Dropping the handle stops the task.
+pub struct ReconcileHandle {
+    shutdown: Option<oneshot::Sender<()>>,
+    reports: mpsc::Receiver<Result<ReconcileReport>>,
+    task: JoinHandle<()>,
+}
+
+impl ReconcileHandle {
+    /// Wait for the next reconciliation that found drift or failed; `None` once stopped
+    pub async fn next_report(&mut self) -> Option<Result<ReconcileReport>> {
+        self.reports.recv().await
+    }
+
+    /// Whether the background task is still running
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+
+    /// Stop reconciling and wait for the task to exit
+    pub async fn stop(mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        let _ = (&mut self.task).await;
+    }
+}
+
+/// Position of a status in the NEW -> PARTIALLY_FILLED -> final order
+fn status_rank(status: OrderStatus) -> u8 {
+    match status {
+        OrderStatus::New => 0,
+        OrderStatus::PartiallyFilled => 1,
+        _ => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::{self, trading_api};
+    use crate::client::MemoryTransport;
+    use reqwest::Method;
+    use serde_json::json;
+
+    fn update(order_id: u64, execution_type: &str, status: &str, trade: Option<(u64, &str, &str)>, filled: &str) -> OrderUpdateData {
+        let (trade_id, last_qty, last_price) = trade.unwrap_or((0, "0", "0"));
+        let json = format!(
+            r#"{{
+                "s": "BTCUSDT", "c": "bot-{order_id}", "S": "BUY", "o": "LIMIT", "f": "GTC",
+                "q": "1.0", "p": "30000", "ap": "{last_price}", "sp": "0", "x": "{execution_type}",
+                "X": "{status}", "i": {order_id}, "l": "{last_qty}", "z": "{filled}", "L": "{last_price}",
+                "n": "0.5", "N": "USDT", "T": {trade_id}, "t": {trade_id}, "b": "0", "a": "0",
+                "m": true, "R": false, "wt": "CONTRACT_PRICE", "ot": "LIMIT", "ps": "BOTH",
+                "cp": false, "rp": "1.5"
+            }}"#
+        );
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn order_json(order_id: u64, status: &str, executed_qty: &str) -> String {
+        test_support::order_json(json!({
+            "orderId": order_id, "status": status, "clientOrderId": format!("bot-{order_id}"), "price": "30000",
+            "avgPrice": "30000", "origQty": "1.0", "executedQty": executed_qty, "side": "BUY"
+        }))
+    }
+
+    #[test]
+    fn test_order_lifecycle_accumulates_fills() {
+        let manager = OrderManager::new();
+        assert!(manager.apply_update(&update(1, "NEW", "NEW", None, "0")).unwrap());
+        assert_eq!(manager.open_orders(Some("BTCUSDT")).len(), 1);
+
+        let partial = update(1, "TRADE", "PARTIALLY_FILLED", Some((10, "0.4", "30000")), "0.4");
+        assert!(manager.apply_update(&partial).unwrap());
+        // A duplicate event changes nothing
+        assert!(!manager.apply_update(&partial).unwrap());
+        manager.apply_update(&update(1, "TRADE", "FILLED", Some((11, "0.6", "30000")), "1.0")).unwrap();
+
+        let order = manager.get_by_client_order_id("bot-1").unwrap();
+        assert_eq!(order.status, OrderStatus::Filled);
+        assert_eq!(order.fills.len(), 2);
+        assert_eq!(order.filled_qty.to_string(), "1.0");
+        assert_eq!(order.remaining_qty().unwrap().to_string(), "0.0");
+        assert_eq!(order.fees["USDT"].to_string(), "1.0");
+        assert_eq!(order.realized_pnl.to_string(), "3.0");
+        assert!(manager.open_orders(None).is_empty());
+
+        // A late NEW event does not reopen the order
+        assert!(!manager.apply_update(&update(1, "NEW", "NEW", None, "0")).unwrap());
+        assert_eq!(manager.get(1).unwrap().status, OrderStatus::Filled);
+
+        assert_eq!(manager.remove_closed(), 1);
+        assert!(manager.get_by_client_order_id("bot-1").is_none());
+    }
+
+    #[test]
+    fn test_overflowing_fill_is_not_counted() {
+        let manager = OrderManager::new();
+        let mut fill = update(1, "TRADE", "PARTIALLY_FILLED", Some((10, "0.4", "30000")), "0.4");
+        fill.realized_profit = "79228162514264337593543950335".parse().unwrap();
+        manager.apply_update(&fill).unwrap();
+
+        let mut next = update(1, "TRADE", "PARTIALLY_FILLED", Some((11, "0.1", "30000")), "0.5");
+        next.realized_profit = "1".parse().unwrap();
+        assert!(manager.apply_update(&next).is_err());
+        let order = manager.get(1).unwrap();
+        assert_eq!(order.fills.len(), 1);
+        assert_eq!(order.fees["USDT"].to_string(), "0.5");
+        assert_eq!(order.filled_qty.to_string(), "0.4");
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_fixes_missed_events() {
+        let transport = MemoryTransport::new();
+        let trading = trading_api(&transport);
+
+        let manager = OrderManager::new();
+        for order_id in 1..=3 {
+            manager.apply_update(&update(order_id, "NEW", "NEW", None, "0")).unwrap();
+        }
+
+        // Exchange: 1 partially filled, 2 filled, 3 unknown, 4 placed elsewhere
+        transport.push_json(
+            Method::GET,
+            "/fapi/v1/openOrders",
+            200,
+            &format!("[{},{}]", order_json(1, "PARTIALLY_FILLED", "0.3"), order_json(4, "NEW", "0")),
+        );
+        transport.push_json(Method::GET, "/fapi/v1/order", 200, &order_json(2, "FILLED", "1.0"));
+        transport.push_json(Method::GET, "/fapi/v1/order", 400, r#"{"code":-2013,"msg":"Order does not exist."}"#);
+
+        let report = manager.reconcile(&trading, Some("BTCUSDT")).await.unwrap();
+        assert_eq!(report.added, vec![4]);
+        assert_eq!(report.updated, vec![1]);
+        assert_eq!(report.closed, vec![2]);
+        assert_eq!(report.removed, vec![3]);
+
+        assert_eq!(manager.get(1).unwrap().filled_qty.to_string(), "0.3");
+        assert_eq!(manager.get(2).unwrap().status, OrderStatus::Filled);
+        assert!(manager.get(3).is_none());
+        let open: Vec<u64> = manager.open_orders(None).iter().map(|o| o.order_id).collect();
+        assert_eq!(open, vec![1, 4]);
+    }
+}
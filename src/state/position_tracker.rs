@@ -0,0 +1,459 @@
+use crate::api::account::AccountApi;
+use crate::error::Result;
+use crate::types::common::{OrderSide, PositionSide};
+use crate::types::decimal::{in_range, Decimal, RoundingStrategy};
+use crate::websocket::types::{AccountUpdate, OrderUpdateData, WebSocketMessage};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Decimals kept in locally computed entry prices
+const ENTRY_PRICE_SCALE: u32 = 8;
+/// Relative entry price difference tolerated by [`PositionTracker::reconcile`]
+const ENTRY_PRICE_TOLERANCE: Decimal = Decimal::new(1, 6);
+
+/// Local view of a position on one symbol and position side
+#[derive(Debug, Clone)]
+pub struct Position {
+    pub symbol: String,
+    pub position_side: PositionSide,
+    /// Signed size: positive when long, negative when short
+    pub size: Decimal,
+    /// Volume-weighted entry price, zero when flat
+    pub entry_price: Decimal,
+    pub realized_pnl: Decimal,
+    /// Commission paid per asset
+    pub fees: HashMap<String, Decimal>,
+    pub update_time: u64,
+    /// Transaction time of the last exchange snapshot; fills up to this time are already in `size`
+    snapshot_time: u64,
+}
+
+impl Position {
+    fn new(symbol: &str, position_side: PositionSide) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            position_side,
+            size: Decimal::ZERO,
+            entry_price: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            fees: HashMap::new(),
+            update_time: 0,
+            snapshot_time: 0,
+        }
+    }
+
+    pub fn is_flat(&self) -> bool {
+        self.size.is_zero()
+    }
+
+    /// Unrealized profit at `mark_price`
+    pub fn unrealized_pnl(&self, mark_price: &Decimal) -> Result<Decimal> {
+        in_range(mark_price.checked_sub(self.entry_price).and_then(|diff| self.size.checked_mul(diff)))
+    }
+
+    /// Absolute position value at `mark_price`
+    pub fn notional(&self, mark_price: &Decimal) -> Result<Decimal> {
+        in_range(self.size.abs().checked_mul(*mark_price))
+    }
+
+    /// Move the position by a trade and return the profit realized by any reduced part
+    fn trade(&mut self, side: OrderSide, quantity: Decimal, price: Decimal) -> Result<Decimal> {
+        let (size, entry) = (self.size, self.entry_price);
+        let signed = match side {
+            OrderSide::Buy => quantity,
+            OrderSide::Sell => in_range(Decimal::ZERO.checked_sub(quantity))?,
+        };
+        let new_size = in_range(size.checked_add(signed))?;
+
+        if size.is_zero() || size.is_negative() == signed.is_negative() {
+            let cost = in_range(
+                size.abs()
+                    .checked_mul(entry)
+                    .zip(quantity.checked_mul(price))
+                    .and_then(|(held, added)| held.checked_add(added)),
+            )?;
+            let entry = match cost.checked_div(new_size.abs()) {
+                Some(entry) => entry.round_dp_with_strategy(ENTRY_PRICE_SCALE, RoundingStrategy::MidpointAwayFromZero),
+                None => Decimal::ZERO,
+            };
+            self.size = new_size;
+            self.entry_price = entry.normalize();
+            return Ok(Decimal::ZERO);
+        }
+
+        let closed = quantity.min(size.abs());
+        let realized = in_range(if size.is_negative() {
+            entry.checked_sub(price).and_then(|diff| closed.checked_mul(diff))
+        } else {
+            price.checked_sub(entry).and_then(|diff| closed.checked_mul(diff))
+        })?;
+        if new_size.is_zero() {
+            self.entry_price = Decimal::ZERO;
+        } else if new_size.is_negative() != size.is_negative() {
+            // Flipped: the remainder was opened at this trade's price
+            self.entry_price = price;
+        }
+        self.size = new_size;
+        Ok(realized)
+    }
+
+    fn add_fee(&mut self, asset: &str, commission: &Decimal) -> Result<()> {
+        let fee = self.fees.entry(asset.to_string()).or_insert(Decimal::ZERO);
+        *fee = in_range(fee.checked_add(*commission))?;
+        Ok(())
+    }
+
+    fn add_realized(&mut self, realized: Decimal) -> Result<()> {
+        self.realized_pnl = in_range(self.realized_pnl.checked_add(realized))?;
+        Ok(())
+    }
+}
+
+/// A single execution to apply to a position
+#[derive(Debug, Clone)]
+pub struct PositionFill {
+    pub symbol: String,
+    pub position_side: PositionSide,
+    pub side: OrderSide,
+    pub quantity: Decimal,
+    pub price: Decimal,
+    pub commission: Decimal,
+    pub commission_asset: Option<String>,
+    pub time: u64,
+}
+
+impl PositionFill {
+    /// Fill reported by an `ORDER_TRADE_UPDATE` event, if the event is a trade
+    pub fn from_update(update: &OrderUpdateData) -> Option<Self> {
+        if update.execution_type != "TRADE" {
+            return None;
+        }
+        Some(Self {
+            symbol: update.symbol.clone(),
+            position_side: update.position_side,
+            side: update.side,
+            quantity: update.last_filled_quantity,
+            price: update.last_filled_price,
+            commission: update.commission_amount,
+            commission_asset: update.commission_asset.clone(),
+            time: update.order_trade_time,
+        })
+    }
+}
+
+/// Difference between a tracked position and `positionRisk`
+#[derive(Debug, Clone)]
+pub struct PositionMismatch {
+    pub symbol: String,
+    pub position_side: PositionSide,
+    pub local_size: Decimal,
+    pub exchange_size: Decimal,
+    pub local_entry_price: Decimal,
+    pub exchange_entry_price: Decimal,
+}
+
+/// Live positions built from fills and `ACCOUNT_UPDATE` events.
+///
+/// Positions are kept per symbol and [`PositionSide`], so one-way (BOTH)
+/// and hedge mode (LONG/SHORT) accounts are both covered. Fills move the
+/// size and volume-weighted entry price and realize profit on the reduced
+/// part; `ACCOUNT_UPDATE` positions overwrite size and entry price with the
+/// exchange's values. A fill no newer than the last snapshot is assumed to
+/// be in it already and only adds its fee and Binance's realized profit.
+/// Clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct PositionTracker {
+    positions: Arc<Mutex<HashMap<(String, PositionSide), Position>>>,
+}
+
+impl PositionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a fill computed locally, e.g. by a simulator.
+    ///
+    /// Fails, leaving the position unchanged, if its size, cost, realized
+    /// PnL or fees overflow.
+    pub fn apply_fill(&self, fill: &PositionFill) -> Result<()> {
+        let mut positions = self.positions.lock().unwrap();
+        apply_fill_locked(entry(&mut positions, &fill.symbol, fill.position_side), fill)
+    }
+
+    /// Apply a trade from the user data stream; returns whether it was a trade.
+    ///
+    /// Fails like [`apply_fill`](Self::apply_fill).
+    pub fn apply_order_update(&self, update: &OrderUpdateData) -> Result<bool> {
+        let Some(fill) = PositionFill::from_update(update) else {
+            return Ok(false);
+        };
+
+        let mut positions = self.positions.lock().unwrap();
+        let position = entry(&mut positions, &fill.symbol, fill.position_side);
+        // Checked and applied under one lock, so an ACCOUNT_UPDATE cannot slip in between
+        if fill.time > position.snapshot_time {
+            apply_fill_locked(position, &fill)?;
+            return Ok(true);
+        }
+
+        let mut next = position.clone();
+        next.add_realized(update.realized_profit)?;
+        if let Some(asset) = &fill.commission_asset {
+            next.add_fee(asset, &fill.commission)?;
+        }
+        *position = next;
+        Ok(true)
+    }
+
+    /// Take the positions in an `ACCOUNT_UPDATE` event as the exchange's current state
+    pub fn apply_account_update(&self, event: &AccountUpdate) {
+        let mut positions = self.positions.lock().unwrap();
+        for update in &event.account_update.positions {
+            let position = entry(&mut positions, &update.symbol, update.position_side);
+            position.size = update.position_amount;
+            position.entry_price = update.entry_price;
+            position.snapshot_time = position.snapshot_time.max(event.transaction_time);
+            position.update_time = position.update_time.max(event.transaction_time);
+        }
+    }
+
+    /// Apply a user data stream message, ignoring anything but order and account updates
+    pub fn on_message(&self, message: &WebSocketMessage) -> Result<bool> {
+        match message {
+            WebSocketMessage::OrderUpdate(event) => self.apply_order_update(&event.order),
+            WebSocketMessage::AccountUpdate(event) => {
+                self.apply_account_update(event);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    pub fn position(&self, symbol: &str, position_side: PositionSide) -> Option<Position> {
+        self.positions
+            .lock()
+            .unwrap()
+            .get(&(symbol.to_string(), position_side))
+            .cloned()
+    }
+
+    /// All tracked positions, including flat ones, by symbol
+    pub fn positions(&self) -> Vec<Position> {
+        let mut positions: Vec<Position> = self.positions.lock().unwrap().values().cloned().collect();
+        positions.sort_by(|a, b| (&a.symbol, a.position_side.to_string()).cmp(&(&b.symbol, b.position_side.to_string())));
+        positions
+    }
+
+    /// Positions that are not flat
+    pub fn open_positions(&self) -> Vec<Position> {
+        self.positions().into_iter().filter(|position| !position.is_flat()).collect()
+    }
+
+    /// Signed size across all position sides of `symbol`
+    pub fn net_size(&self, symbol: &str) -> Result<Decimal> {
+        self.positions
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|position| position.symbol == symbol)
+            .try_fold(Decimal::ZERO, |net, position| in_range(net.checked_add(position.size)))
+    }
+
+    /// Compare with `positionRisk`, adopt the exchange's size and entry price, and return the differences.
+    ///
+    /// Entry prices within a relative 0.0001% are treated as equal.
+    pub async fn reconcile(&self, account: &AccountApi, symbol: Option<&str>) -> Result<Vec<PositionMismatch>> {
+        let exchange = account.position_risk(symbol).await?;
+        let mut mismatches = Vec::new();
+        let mut positions = self.positions.lock().unwrap();
+
+        let mut seen = Vec::with_capacity(exchange.len());
+        for risk in &exchange {
+            seen.push((risk.symbol.clone(), risk.position_side));
+            let position = entry(&mut positions, &risk.symbol, risk.position_side);
+            let (local_size, exchange_size) = (position.size, risk.position_amt);
+            let (local_entry, exchange_entry) = (position.entry_price, risk.entry_price);
+
+            let entry_differs = !exchange_size.is_zero()
+                && in_range(local_entry.checked_sub(exchange_entry))?.abs()
+                    > in_range(exchange_entry.abs().checked_mul(ENTRY_PRICE_TOLERANCE))?;
+            if local_size != exchange_size || entry_differs {
+                mismatches.push(PositionMismatch {
+                    symbol: risk.symbol.clone(),
+                    position_side: risk.position_side,
+                    local_size: position.size,
+                    exchange_size: risk.position_amt,
+                    local_entry_price: position.entry_price,
+                    exchange_entry_price: risk.entry_price,
+                });
+            }
+
+            position.size = risk.position_amt;
+            position.entry_price = risk.entry_price;
+            position.snapshot_time = position.snapshot_time.max(risk.update_time);
+        }
+
+        // Open locally but not reported by the exchange at all
+        for ((position_symbol, position_side), position) in positions.iter_mut() {
+            let in_scope = symbol.is_none_or(|symbol| position_symbol == symbol);
+            if !in_scope || position.is_flat() || seen.contains(&(position_symbol.clone(), *position_side)) {
+                continue;
+            }
+            mismatches.push(PositionMismatch {
+                symbol: position_symbol.clone(),
+                position_side: *position_side,
+                local_size: position.size,
+                exchange_size: Decimal::ZERO,
+                local_entry_price: position.entry_price,
+                exchange_entry_price: Decimal::ZERO,
+            });
+            position.size = Decimal::ZERO;
+            position.entry_price = Decimal::ZERO;
+        }
+
+        Ok(mismatches)
+    }
+}
+
+/// Trade a fill into a position the caller holds the lock for, all or nothing
+fn apply_fill_locked(position: &mut Position, fill: &PositionFill) -> Result<()> {
+    let mut next = position.clone();
+    let realized = next.trade(fill.side, fill.quantity, fill.price)?;
+    next.add_realized(realized)?;
+    if let Some(asset) = &fill.commission_asset {
+        next.add_fee(asset, &fill.commission)?;
+    }
+    next.update_time = next.update_time.max(fill.time);
+    *position = next;
+    Ok(())
+}
+
+fn entry<'a>(
+    positions: &'a mut HashMap<(String, PositionSide), Position>,
+    symbol: &str,
+    position_side: PositionSide,
+) -> &'a mut Position {
+    positions
+        .entry((symbol.to_string(), position_side))
+        .or_insert_with(|| Position::new(symbol, position_side))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::account_api;
+    use crate::client::MemoryTransport;
+    use reqwest::Method;
+
+    fn fill(position_side: PositionSide, side: OrderSide, quantity: &str, price: &str) -> PositionFill {
+        PositionFill {
+            symbol: "BTCUSDT".to_string(),
+            position_side,
+            side,
+            quantity: quantity.parse().unwrap(),
+            price: price.parse().unwrap(),
+            commission: "0.1".parse().unwrap(),
+            commission_asset: Some("USDT".to_string()),
+            time: 1,
+        }
+    }
+
+    #[test]
+    fn test_one_way_average_entry_and_realized_pnl() {
+        let tracker = PositionTracker::new();
+        tracker.apply_fill(&fill(PositionSide::Both, OrderSide::Buy, "1", "100")).unwrap();
+        tracker.apply_fill(&fill(PositionSide::Both, OrderSide::Buy, "3", "200")).unwrap();
+
+        let position = tracker.position("BTCUSDT", PositionSide::Both).unwrap();
+        assert_eq!(position.size.to_string(), "4");
+        assert_eq!(position.entry_price.to_string(), "175");
+        assert_eq!(position.unrealized_pnl(&"180".parse().unwrap()).unwrap().to_string(), "20");
+
+        // Sell through zero: close 4 at 150, open 1 short at 150
+        tracker.apply_fill(&fill(PositionSide::Both, OrderSide::Sell, "5", "150")).unwrap();
+        let position = tracker.position("BTCUSDT", PositionSide::Both).unwrap();
+        assert_eq!(position.size.to_string(), "-1");
+        assert_eq!(position.entry_price.to_string(), "150");
+        assert_eq!(position.realized_pnl.to_string(), "-100");
+        assert_eq!(position.fees["USDT"].to_string(), "0.3");
+
+        // A fill that overflows the position is rejected as a whole
+        let max = "79228162514264337593543950335";
+        assert!(tracker.apply_fill(&fill(PositionSide::Both, OrderSide::Sell, max, "2")).is_err());
+        let unchanged = tracker.position("BTCUSDT", PositionSide::Both).unwrap();
+        assert_eq!(unchanged.size.to_string(), "-1");
+        assert_eq!(unchanged.fees["USDT"].to_string(), "0.3");
+    }
+
+    #[test]
+    fn test_hedge_mode_keeps_sides_apart() {
+        let tracker = PositionTracker::new();
+        tracker.apply_fill(&fill(PositionSide::Long, OrderSide::Buy, "2", "100")).unwrap();
+        tracker.apply_fill(&fill(PositionSide::Short, OrderSide::Sell, "1", "110")).unwrap();
+        tracker.apply_fill(&fill(PositionSide::Short, OrderSide::Buy, "1", "100")).unwrap();
+
+        let long = tracker.position("BTCUSDT", PositionSide::Long).unwrap();
+        let short = tracker.position("BTCUSDT", PositionSide::Short).unwrap();
+        assert_eq!(long.size.to_string(), "2");
+        assert!(short.is_flat());
+        assert_eq!(short.realized_pnl.to_string(), "10");
+        assert_eq!(tracker.net_size("BTCUSDT").unwrap().to_string(), "2");
+        assert_eq!(tracker.open_positions().len(), 1);
+    }
+
+    #[test]
+    fn test_fill_included_in_account_update_is_not_counted_twice() {
+        let tracker = PositionTracker::new();
+        let account_update: AccountUpdate = serde_json::from_str(
+            r#"{"e":"ACCOUNT_UPDATE","E":5,"T":5,"a":{"m":"ORDER","B":[],"P":[{
+                "s":"BTCUSDT","pa":"1","ep":"100","cr":"0","up":"0","mt":"cross","iw":"0","ps":"BOTH"
+            }]}}"#,
+        )
+        .unwrap();
+        let order_update: OrderUpdateData = serde_json::from_str(
+            r#"{"s":"BTCUSDT","c":"bot-1","S":"BUY","o":"MARKET","f":"GTC","q":"1","p":"0","ap":"100",
+                "sp":"0","x":"TRADE","X":"FILLED","i":1,"l":"1","z":"1","L":"100","n":"0.04","N":"USDT",
+                "T":5,"t":9,"b":"0","a":"0","m":false,"R":false,"wt":"CONTRACT_PRICE","ot":"MARKET",
+                "ps":"BOTH","cp":false,"rp":"0"}"#,
+        )
+        .unwrap();
+
+        tracker.apply_account_update(&account_update);
+        assert!(tracker.apply_order_update(&order_update).unwrap());
+
+        let position = tracker.position("BTCUSDT", PositionSide::Both).unwrap();
+        assert_eq!(position.size.to_string(), "1");
+        assert_eq!(position.fees["USDT"].to_string(), "0.04");
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_flags_and_fixes_mismatches() {
+        let transport = MemoryTransport::new();
+        let account = account_api(&transport);
+        transport.push_json(
+            Method::GET,
+            "/fapi/v2/positionRisk",
+            200,
+            r#"[{
+                "symbol": "BTCUSDT", "positionAmt": "0.5", "entryPrice": "100", "markPrice": "101",
+                "unRealizedPnl": "0.5", "liquidationPrice": "0", "leverage": "10",
+                "maxNotionalValue": "1000000", "marginType": "cross", "isolatedMargin": "0",
+                "isAutoAddMargin": false, "positionSide": "BOTH", "notional": "50.5",
+                "isolatedWallet": "0", "updateTime": 10, "bidNotional": "0", "askNotional": "0"
+            }]"#,
+        );
+
+        let tracker = PositionTracker::new();
+        tracker.apply_fill(&fill(PositionSide::Both, OrderSide::Buy, "1", "100")).unwrap();
+        tracker.apply_fill(&fill(PositionSide::Long, OrderSide::Buy, "1", "100")).unwrap();
+
+        let mismatches = tracker.reconcile(&account, Some("BTCUSDT")).await.unwrap();
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[0].exchange_size.to_string(), "0.5");
+        assert_eq!(mismatches[1].position_side, PositionSide::Long);
+
+        assert_eq!(tracker.position("BTCUSDT", PositionSide::Both).unwrap().size.to_string(), "0.5");
+        assert!(tracker.position("BTCUSDT", PositionSide::Long).unwrap().is_flat());
+        assert!(tracker.reconcile(&account, Some("BTCUSDT")).await.unwrap().is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 use serde::Deserialize;
 use crate::types::common::PositionSide;
+use crate::types::Amount;
 
 /// Account information
 #[derive(Debug, Clone, Deserialize)]
@@ -10,17 +11,17 @@ pub struct AccountInfo {
     pub can_deposit: bool,
     pub can_withdraw: bool,
     pub update_time: u64,
-    pub total_initial_margin: String,
-    pub total_maint_margin: String,
-    pub total_wallet_balance: String,
-    pub total_unrealized_pnl: String,
-    pub total_margin_balance: String,
-    pub total_position_initial_margin: String,
-    pub total_open_order_initial_margin: String,
-    pub total_cross_wallet_balance: String,
-    pub total_cross_un_pnl: String,
-    pub available_balance: String,
-    pub max_withdraw_amount: String,
+    pub total_initial_margin: Amount,
+    pub total_maint_margin: Amount,
+    pub total_wallet_balance: Amount,
+    pub total_unrealized_pnl: Amount,
+    pub total_margin_balance: Amount,
+    pub total_position_initial_margin: Amount,
+    pub total_open_order_initial_margin: Amount,
+    pub total_cross_wallet_balance: Amount,
+    pub total_cross_un_pnl: Amount,
+    pub available_balance: Amount,
+    pub max_withdraw_amount: Amount,
     pub assets: Vec<AssetBalance>,
     pub positions: Vec<Position>,
 }
@@ -30,17 +31,17 @@ pub struct AccountInfo {
 #[serde(rename_all = "camelCase")]
 pub struct AssetBalance {
     pub asset: String,
-    pub wallet_balance: String,
-    pub unrealized_pnl: String,
-    pub margin_balance: String,
-    pub maint_margin: String,
-    pub initial_margin: String,
-    pub position_initial_margin: String,
-    pub open_order_initial_margin: String,
-    pub cross_wallet_balance: String,
-    pub cross_un_pnl: String,
-    pub available_balance: String,
-    pub max_withdraw_amount: String,
+    pub wallet_balance: Amount,
+    pub unrealized_pnl: Amount,
+    pub margin_balance: Amount,
+    pub maint_margin: Amount,
+    pub initial_margin: Amount,
+    pub position_initial_margin: Amount,
+    pub open_order_initial_margin: Amount,
+    pub cross_wallet_balance: Amount,
+    pub cross_un_pnl: Amount,
+    pub available_balance: Amount,
+    pub max_withdraw_amount: Amount,
     pub margin_available: bool,
     pub update_time: u64,
 }
@@ -50,22 +51,22 @@ pub struct AssetBalance {
 #[serde(rename_all = "camelCase")]
 pub struct Position {
     pub symbol: String,
-    pub initial_margin: String,
-    pub maint_margin: String,
-    pub unrealized_pnl: String,
-    pub position_initial_margin: String,
-    pub open_order_initial_margin: String,
-    pub leverage: String,
+    pub initial_margin: Amount,
+    pub maint_margin: Amount,
+    pub unrealized_pnl: Amount,
+    pub position_initial_margin: Amount,
+    pub open_order_initial_margin: Amount,
+    pub leverage: Amount,
     pub isolated: bool,
-    pub entry_price: String,
-    pub max_notional: String,
+    pub entry_price: Amount,
+    pub max_notional: Amount,
     pub position_side: PositionSide,
-    pub position_amt: String,
-    pub notional: String,
-    pub isolated_wallet: String,
+    pub position_amt: Amount,
+    pub notional: Amount,
+    pub isolated_wallet: Amount,
     pub update_time: u64,
-    pub bid_notional: String,
-    pub ask_notional: String,
+    pub bid_notional: Amount,
+    pub ask_notional: Amount,
 }
 
 /// Balance information
@@ -74,11 +75,11 @@ pub struct Position {
 pub struct Balance {
     pub account_alias: String,
     pub asset: String,
-    pub balance: String,
-    pub cross_wallet_balance: String,
-    pub cross_un_pnl: String,
-    pub available_balance: String,
-    pub max_withdraw_amount: String,
+    pub balance: Amount,
+    pub cross_wallet_balance: Amount,
+    pub cross_un_pnl: Amount,
+    pub available_balance: Amount,
+    pub max_withdraw_amount: Amount,
     pub margin_available: bool,
     pub update_time: u64,
 }
@@ -88,22 +89,22 @@ pub struct Balance {
 #[serde(rename_all = "camelCase")]
 pub struct PositionRisk {
     pub symbol: String,
-    pub position_amt: String,
-    pub entry_price: String,
-    pub mark_price: String,
-    pub un_realized_pnl: String,
-    pub liquidation_price: String,
-    pub leverage: String,
-    pub max_notional_value: String,
+    pub position_amt: Amount,
+    pub entry_price: Amount,
+    pub mark_price: Amount,
+    pub un_realized_pnl: Amount,
+    pub liquidation_price: Amount,
+    pub leverage: Amount,
+    pub max_notional_value: Amount,
     pub margin_type: String,
-    pub isolated_margin: String,
+    pub isolated_margin: Amount,
     pub is_auto_add_margin: bool,
     pub position_side: PositionSide,
-    pub notional: String,
-    pub isolated_wallet: String,
+    pub notional: Amount,
+    pub isolated_wallet: Amount,
     pub update_time: u64,
-    pub bid_notional: String,
-    pub ask_notional: String,
+    pub bid_notional: Amount,
+    pub ask_notional: Amount,
 }
 
 /// Income history
@@ -112,7 +113,7 @@ pub struct PositionRisk {
 pub struct Income {
     pub symbol: String,
     pub income_type: String,
-    pub income: String,
+    pub income: Amount,
     pub asset: String,
     pub info: String,
     pub time: u64,
@@ -159,11 +160,12 @@ pub struct AdlQuantileInfo {
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ForceOrder {
+    pub order_id: u64,
     pub symbol: String,
-    pub price: String,
-    pub orig_qty: String,
-    pub executed_qty: String,
-    pub avg_price: String,
+    pub price: Amount,
+    pub orig_qty: Amount,
+    pub executed_qty: Amount,
+    pub avg_price: Amount,
     pub status: String,
     pub time_in_force: String,
     #[serde(rename = "type")]
@@ -177,8 +179,22 @@ pub struct ForceOrder {
 #[serde(rename_all = "camelCase")]
 pub struct CommissionRate {
     pub symbol: String,
-    pub maker_commission_rate: String,
-    pub taker_commission_rate: String,
+    pub maker_commission_rate: Amount,
+    pub taker_commission_rate: Amount,
+}
+
+/// Position mode response
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DualSidePosition {
+    pub dual_side_position: bool,
+}
+
+/// Multi-assets margin mode response
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiAssetsMargin {
+    pub multi_assets_margin: bool,
 }
 
 #[cfg(test)]
@@ -213,6 +229,6 @@ mod tests {
         let account_info: AccountInfo = serde_json::from_str(json).unwrap();
         assert_eq!(account_info.fee_tier, 0);
         assert!(account_info.can_trade);
-        assert_eq!(account_info.total_wallet_balance, "10000.0");
+        assert_eq!(account_info.total_wallet_balance.to_string(), "10000.0");
     }
 }
@@ -20,7 +20,7 @@ impl fmt::Display for OrderSide {
 
 /// Order type
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "UPPERCASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderType {
     Limit,
     Market,
@@ -53,6 +53,7 @@ pub enum TimeInForce {
     Ioc, // Immediate or Cancel
     Fok, // Fill or Kill
     Gtx, // Good Till Crossing
+    Gtd, // Good Till Date
 }
 
 impl fmt::Display for TimeInForce {
@@ -62,12 +63,13 @@ impl fmt::Display for TimeInForce {
             TimeInForce::Ioc => write!(f, "IOC"),
             TimeInForce::Fok => write!(f, "FOK"),
             TimeInForce::Gtx => write!(f, "GTX"),
+            TimeInForce::Gtd => write!(f, "GTD"),
         }
     }
 }
 
 /// Position side
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum PositionSide {
     Both,
@@ -85,17 +87,105 @@ impl fmt::Display for PositionSide {
     }
 }
 
+/// Account position mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionMode {
+    /// Single position per symbol (`positionSide` BOTH)
+    OneWay,
+    /// Separate LONG and SHORT positions per symbol
+    Hedge,
+}
+
+impl PositionMode {
+    /// Mode from Binance's `dualSidePosition` flag
+    pub fn from_dual_side(dual_side_position: bool) -> Self {
+        if dual_side_position {
+            PositionMode::Hedge
+        } else {
+            PositionMode::OneWay
+        }
+    }
+
+    pub fn is_dual_side(&self) -> bool {
+        *self == PositionMode::Hedge
+    }
+
+    /// Whether an order with this position side is accepted in this mode
+    pub fn accepts(&self, position_side: Option<PositionSide>) -> bool {
+        match self {
+            PositionMode::OneWay => matches!(position_side, None | Some(PositionSide::Both)),
+            PositionMode::Hedge => matches!(position_side, Some(PositionSide::Long | PositionSide::Short)),
+        }
+    }
+}
+
+impl fmt::Display for PositionMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PositionMode::OneWay => write!(f, "One-way"),
+            PositionMode::Hedge => write!(f, "Hedge"),
+        }
+    }
+}
+
 /// Working type
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "UPPERCASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum WorkingType {
     MarkPrice,
     ContractPrice,
 }
 
-/// Order status
+impl fmt::Display for WorkingType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkingType::MarkPrice => write!(f, "MARK_PRICE"),
+            WorkingType::ContractPrice => write!(f, "CONTRACT_PRICE"),
+        }
+    }
+}
+
+/// Self-trade prevention mode
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SelfTradePreventionMode {
+    None,
+    ExpireTaker,
+    ExpireMaker,
+    ExpireBoth,
+}
+
+impl fmt::Display for SelfTradePreventionMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelfTradePreventionMode::None => write!(f, "NONE"),
+            SelfTradePreventionMode::ExpireTaker => write!(f, "EXPIRE_TAKER"),
+            SelfTradePreventionMode::ExpireMaker => write!(f, "EXPIRE_MAKER"),
+            SelfTradePreventionMode::ExpireBoth => write!(f, "EXPIRE_BOTH"),
+        }
+    }
+}
+
+/// Response type for new orders
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "UPPERCASE")]
+pub enum NewOrderRespType {
+    Ack,
+    Result,
+}
+
+impl fmt::Display for NewOrderRespType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NewOrderRespType::Ack => write!(f, "ACK"),
+            NewOrderRespType::Result => write!(f, "RESULT"),
+        }
+    }
+}
+
+/// Order status
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderStatus {
     New,
     PartiallyFilled,
@@ -103,6 +193,59 @@ pub enum OrderStatus {
     Canceled,
     Rejected,
     Expired,
+    ExpiredInMatch,
+}
+
+impl OrderStatus {
+    /// Whether the order can still trade (NEW or PARTIALLY_FILLED)
+    pub fn is_open(&self) -> bool {
+        matches!(self, OrderStatus::New | OrderStatus::PartiallyFilled)
+    }
+
+    /// Whether the order reached a final state and will not change again
+    pub fn is_final(&self) -> bool {
+        !self.is_open()
+    }
+}
+
+/// Price match mode, used instead of an explicit price
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum PriceMatch {
+    #[serde(rename = "NONE")]
+    None,
+    #[serde(rename = "OPPONENT")]
+    Opponent,
+    #[serde(rename = "OPPONENT_5")]
+    Opponent5,
+    #[serde(rename = "OPPONENT_10")]
+    Opponent10,
+    #[serde(rename = "OPPONENT_20")]
+    Opponent20,
+    #[serde(rename = "QUEUE")]
+    Queue,
+    #[serde(rename = "QUEUE_5")]
+    Queue5,
+    #[serde(rename = "QUEUE_10")]
+    Queue10,
+    #[serde(rename = "QUEUE_20")]
+    Queue20,
+}
+
+impl fmt::Display for PriceMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            PriceMatch::None => "NONE",
+            PriceMatch::Opponent => "OPPONENT",
+            PriceMatch::Opponent5 => "OPPONENT_5",
+            PriceMatch::Opponent10 => "OPPONENT_10",
+            PriceMatch::Opponent20 => "OPPONENT_20",
+            PriceMatch::Queue => "QUEUE",
+            PriceMatch::Queue5 => "QUEUE_5",
+            PriceMatch::Queue10 => "QUEUE_10",
+            PriceMatch::Queue20 => "QUEUE_20",
+        };
+        write!(f, "{}", s)
+    }
 }
 
 /// Kline interval
@@ -187,6 +330,33 @@ mod tests {
         assert_eq!(serde_json::to_string(&OrderSide::Sell).unwrap(), "\"SELL\"");
     }
 
+    #[test]
+    fn test_multi_word_enum_serialization() {
+        assert_eq!(serde_json::to_string(&OrderType::StopMarket).unwrap(), "\"STOP_MARKET\"");
+        assert_eq!(serde_json::to_string(&WorkingType::MarkPrice).unwrap(), "\"MARK_PRICE\"");
+        assert_eq!(
+            serde_json::from_str::<OrderStatus>("\"PARTIALLY_FILLED\"").unwrap(),
+            OrderStatus::PartiallyFilled
+        );
+        assert_eq!(serde_json::to_string(&PriceMatch::Queue5).unwrap(), "\"QUEUE_5\"");
+        assert_eq!(PriceMatch::Opponent10.to_string(), "OPPONENT_10");
+        assert_eq!(
+            serde_json::to_string(&SelfTradePreventionMode::ExpireTaker).unwrap(),
+            "\"EXPIRE_TAKER\""
+        );
+        assert_eq!(serde_json::from_str::<TimeInForce>("\"GTD\"").unwrap(), TimeInForce::Gtd);
+    }
+
+    #[test]
+    fn test_position_mode_accepts() {
+        assert!(PositionMode::OneWay.accepts(None));
+        assert!(PositionMode::OneWay.accepts(Some(PositionSide::Both)));
+        assert!(!PositionMode::OneWay.accepts(Some(PositionSide::Long)));
+        assert!(PositionMode::Hedge.accepts(Some(PositionSide::Short)));
+        assert!(!PositionMode::Hedge.accepts(None));
+        assert!(PositionMode::from_dual_side(true).is_dual_side());
+    }
+
     #[test]
     fn test_kline_interval_display() {
         assert_eq!(KlineInterval::OneMinute.to_string(), "1m");
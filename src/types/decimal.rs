@@ -0,0 +1,270 @@
+//! Representation of prices, quantities and other exchange amounts.
+//!
+//! [`Decimal`] is an exact decimal number that parses and serializes
+//! Binance's string format without loss, trailing zeros included. It is
+//! backed by [`rust_decimal::Decimal`] and converts to and from it. Only
+//! built with the `decimal` feature, which makes it the
+//! [`Amount`](crate::types::Amount) of every price and quantity field.
+
+use crate::error::{BinanceError, Result};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+pub use rust_decimal::RoundingStrategy;
+
+/// Exact exchange amount: a price, quantity, rate or balance.
+///
+/// Equality and ordering are by value, so `0.10` equals `0.1`, while
+/// [`Display`](fmt::Display) keeps the decimals Binance sent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decimal(rust_decimal::Decimal);
+
+impl Decimal {
+    pub const ZERO: Decimal = Decimal(rust_decimal::Decimal::ZERO);
+
+    /// `mantissa` × 10^-`scale`, for constants
+    pub(crate) const fn new(mantissa: u32, scale: u32) -> Self {
+        Decimal(rust_decimal::Decimal::from_parts(mantissa, 0, 0, false, scale))
+    }
+
+    /// Whether the amount is zero, regardless of its decimals ("0", "0.000")
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    /// Whether the amount is below zero
+    pub fn is_negative(&self) -> bool {
+        self.0.is_sign_negative() && !self.0.is_zero()
+    }
+
+    /// Decimals after the point, as sent
+    pub fn scale(&self) -> u32 {
+        self.0.scale()
+    }
+
+    /// `self + other`, or `None` on overflow
+    pub fn checked_add(self, other: Decimal) -> Option<Decimal> {
+        self.0.checked_add(other.0).map(Decimal)
+    }
+
+    /// `self - other`, or `None` on overflow
+    pub fn checked_sub(self, other: Decimal) -> Option<Decimal> {
+        self.0.checked_sub(other.0).map(Decimal)
+    }
+
+    /// `self * other`, or `None` on overflow; decimals past 28 are rounded
+    pub fn checked_mul(self, other: Decimal) -> Option<Decimal> {
+        self.0.checked_mul(other.0).map(Decimal)
+    }
+
+    /// `self / other`, or `None` on overflow or division by zero
+    pub fn checked_div(self, other: Decimal) -> Option<Decimal> {
+        self.0.checked_div(other.0).map(Decimal)
+    }
+
+    /// Remainder of `self / other` with the sign of `self`, or `None` if `other` is zero
+    pub fn checked_rem(self, other: Decimal) -> Option<Decimal> {
+        self.0.checked_rem(other.0).map(Decimal)
+    }
+
+    /// Round to at most `dp` decimals
+    pub fn round_dp_with_strategy(self, dp: u32, strategy: RoundingStrategy) -> Decimal {
+        Decimal(self.0.round_dp_with_strategy(dp, strategy))
+    }
+
+    pub fn abs(self) -> Decimal {
+        Decimal(self.0.abs())
+    }
+
+    /// Drop trailing zeros after the decimal point
+    pub fn normalize(self) -> Decimal {
+        Decimal(self.0.normalize())
+    }
+
+    /// Whether `self - base` is a whole multiple of `step`; `None` on overflow
+    pub(crate) fn is_step_of(self, base: Decimal, step: Decimal) -> Option<bool> {
+        if step.is_zero() {
+            return Some(true);
+        }
+        Some(self.checked_sub(base)?.checked_rem(step)?.is_zero())
+    }
+
+    /// Round to `base + n * step` using `mode`, keeping the decimals of the
+    /// step and base (e.g. `0.10` -> one); `None` on overflow
+    pub(crate) fn round_to_step(self, base: Decimal, step: Decimal, mode: RoundingMode) -> Option<Decimal> {
+        if step.is_zero() {
+            return Some(self);
+        }
+        let step = step.abs();
+        let offset = self.checked_sub(base)?;
+        let mut rem = offset.checked_rem(step)?;
+        if rem.is_negative() {
+            rem = rem.checked_add(step)?;
+        }
+        let down = offset.checked_sub(rem)?;
+        let up = match mode {
+            RoundingMode::Down => false,
+            RoundingMode::Up => !rem.is_zero(),
+            RoundingMode::Nearest => rem.checked_add(rem)? >= step,
+        };
+        let offset = if up { down.checked_add(step)? } else { down };
+        let mut rounded = offset.checked_add(base)?.0;
+        rounded.rescale(step.normalize().scale().max(base.normalize().scale()));
+        Some(Decimal(rounded))
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = BinanceError;
+
+    /// Parse a plain decimal such as `-12.3400`; exponents and separators are rejected
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid = || BinanceError::InvalidParameter(format!("Invalid decimal: {}", value));
+        let digits = value.strip_prefix(['-', '+']).unwrap_or(value);
+        let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+        if int_part.is_empty() && frac_part.is_empty()
+            || !int_part.bytes().chain(frac_part.bytes()).all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        rust_decimal::Decimal::from_str_exact(value).map(Decimal).map_err(|_| invalid())
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_any(DecimalVisitor)
+    }
+}
+
+/// Accepts Binance's strings, and plain JSON numbers where an endpoint sends those
+struct DecimalVisitor;
+
+impl de::Visitor<'_> for DecimalVisitor {
+    type Value = Decimal;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a decimal number or string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<Decimal, E> {
+        value.parse().map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> std::result::Result<Decimal, E> {
+        Ok(Decimal(value.into()))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> std::result::Result<Decimal, E> {
+        Ok(Decimal(value.into()))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> std::result::Result<Decimal, E> {
+        // The shortest representation that reads back as the same f64
+        value.to_string().parse().map_err(E::custom)
+    }
+}
+
+impl From<rust_decimal::Decimal> for Decimal {
+    fn from(value: rust_decimal::Decimal) -> Self {
+        Decimal(value)
+    }
+}
+
+impl From<Decimal> for rust_decimal::Decimal {
+    fn from(value: Decimal) -> Self {
+        value.0
+    }
+}
+
+/// Turn the `None` of overflowing checked arithmetic into an error
+pub(crate) fn in_range<T>(value: Option<T>) -> Result<T> {
+    value.ok_or_else(|| BinanceError::InvalidParameter("Amount out of range".to_string()))
+}
+
+/// How to round a value onto a tick or step grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Toward negative infinity
+    Down,
+    /// Toward positive infinity
+    Up,
+    /// To the nearest step, halves rounding up
+    Nearest,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lossless_round_trip() {
+        let value: Decimal = "0.00100000".parse().unwrap();
+        assert_eq!(value.to_string(), "0.00100000");
+        assert_eq!(serde_json::to_string(&value).unwrap(), r#""0.00100000""#);
+
+        let parsed: Decimal = serde_json::from_str(r#""50000.10""#).unwrap();
+        assert_eq!(parsed.to_string(), "50000.10");
+    }
+
+    #[test]
+    fn test_is_zero() {
+        for (value, zero) in [("0", true), ("-0.00000000", true), ("0.001", false)] {
+            assert_eq!(value.parse::<Decimal>().unwrap().is_zero(), zero, "{value}");
+        }
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let d = |value: &str| value.parse::<Decimal>().unwrap();
+        let (zero, price, tick) = (Decimal::ZERO, d("50000.123"), d("0.10"));
+        assert_eq!(price.is_step_of(zero, tick), Some(false));
+        assert_eq!(price.round_to_step(zero, tick, RoundingMode::Down).unwrap().to_string(), "50000.1");
+        assert_eq!(price.round_to_step(zero, tick, RoundingMode::Up).unwrap().to_string(), "50000.2");
+        assert_eq!(price.round_to_step(zero, tick, RoundingMode::Nearest).unwrap().to_string(), "50000.1");
+        assert_eq!(d("-0.25").round_to_step(zero, tick, RoundingMode::Down).unwrap().to_string(), "-0.3");
+        assert_eq!(d("100").round_to_step(d("0.5"), d("1"), RoundingMode::Nearest).unwrap().to_string(), "100.5");
+
+        assert_eq!(d("0.0015").checked_mul(price).unwrap().to_string(), "75.0001845");
+        assert_eq!(d("-7.5").checked_rem(d("2")).unwrap().to_string(), "-1.5");
+        let third = d("10").checked_div(d("3")).unwrap();
+        assert_eq!(third.round_dp_with_strategy(4, RoundingStrategy::MidpointAwayFromZero).to_string(), "3.3333");
+        assert!(price.checked_div(zero).is_none());
+        assert!(price.checked_rem(zero).is_none());
+
+        let max = Decimal(rust_decimal::Decimal::MAX);
+        assert!(max.checked_add(d("1")).is_none());
+        assert!(max.checked_mul(d("2")).is_none());
+        assert!(max.is_step_of(d("-1"), tick).is_none());
+    }
+
+    #[test]
+    fn test_invalid_decimal() {
+        for value in ["abc", "", ".", "1e5", "1_000", "1.2.3", "0x10"] {
+            assert!(value.parse::<Decimal>().is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn test_decimal_round_trip() {
+        let price: Decimal = serde_json::from_str("\"30000.10\"").unwrap();
+        assert_eq!(price.to_string(), "30000.10");
+        assert_eq!(serde_json::to_string(&price).unwrap(), "\"30000.10\"");
+        assert_eq!(price, "30000.1".parse().unwrap());
+        assert_eq!(serde_json::from_str::<Decimal>("0.5").unwrap(), "0.5".parse().unwrap());
+        assert!("-0.000".parse::<Decimal>().unwrap().is_zero());
+    }
+}
@@ -1,5 +1,13 @@
+use crate::types::Amount;
 use serde::{Deserialize, Serialize};
 
+/// Server time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerTime {
+    #[serde(rename = "serverTime")]
+    pub server_time: u64,
+}
+
 /// Order book depth
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderBook {
@@ -9,8 +17,8 @@ pub struct OrderBook {
     pub event_time: u64,
     #[serde(rename = "T")]
     pub transaction_time: u64,
-    pub bids: Vec<[String; 2]>, // [price, quantity]
-    pub asks: Vec<[String; 2]>, // [price, quantity]
+    pub bids: Vec<[Amount; 2]>, // [price, quantity]
+    pub asks: Vec<[Amount; 2]>, // [price, quantity]
 }
 
 /// 24hr ticker statistics
@@ -18,24 +26,24 @@ pub struct OrderBook {
 pub struct Ticker24hr {
     pub symbol: String,
     #[serde(rename = "priceChange")]
-    pub price_change: String,
+    pub price_change: Amount,
     #[serde(rename = "priceChangePercent")]
-    pub price_change_percent: String,
+    pub price_change_percent: Amount,
     #[serde(rename = "weightedAvgPrice")]
-    pub weighted_avg_price: String,
+    pub weighted_avg_price: Amount,
     #[serde(rename = "lastPrice")]
-    pub last_price: String,
+    pub last_price: Amount,
     #[serde(rename = "lastQty")]
-    pub last_qty: String,
+    pub last_qty: Amount,
     #[serde(rename = "openPrice")]
-    pub open_price: String,
+    pub open_price: Amount,
     #[serde(rename = "highPrice")]
-    pub high_price: String,
+    pub high_price: Amount,
     #[serde(rename = "lowPrice")]
-    pub low_price: String,
-    pub volume: String,
+    pub low_price: Amount,
+    pub volume: Amount,
     #[serde(rename = "quoteVolume")]
-    pub quote_volume: String,
+    pub quote_volume: Amount,
     #[serde(rename = "openTime")]
     pub open_time: u64,
     #[serde(rename = "closeTime")]
@@ -51,7 +59,7 @@ pub struct Ticker24hr {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceTicker {
     pub symbol: String,
-    pub price: String,
+    pub price: Amount,
     pub time: u64,
 }
 
@@ -59,33 +67,38 @@ pub struct PriceTicker {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Kline {
     pub open_time: u64,
-    pub open: String,
-    pub high: String,
-    pub low: String,
-    pub close: String,
-    pub volume: String,
+    pub open: Amount,
+    pub high: Amount,
+    pub low: Amount,
+    pub close: Amount,
+    pub volume: Amount,
     pub close_time: u64,
-    pub quote_asset_volume: String,
+    pub quote_asset_volume: Amount,
     pub number_of_trades: u64,
-    pub taker_buy_base_asset_volume: String,
-    pub taker_buy_quote_asset_volume: String,
+    pub taker_buy_base_asset_volume: Amount,
+    pub taker_buy_quote_asset_volume: Amount,
     pub ignore: String,
 }
 
+/// Parse a string amount from a kline array, defaulting to zero
+fn kline_amount(value: &serde_json::Value) -> Amount {
+    value.as_str().unwrap_or("0").parse().unwrap_or_default()
+}
+
 impl From<Vec<serde_json::Value>> for Kline {
     fn from(values: Vec<serde_json::Value>) -> Self {
         Self {
             open_time: values[0].as_u64().unwrap_or(0),
-            open: values[1].as_str().unwrap_or("0").to_string(),
-            high: values[2].as_str().unwrap_or("0").to_string(),
-            low: values[3].as_str().unwrap_or("0").to_string(),
-            close: values[4].as_str().unwrap_or("0").to_string(),
-            volume: values[5].as_str().unwrap_or("0").to_string(),
+            open: kline_amount(&values[1]),
+            high: kline_amount(&values[2]),
+            low: kline_amount(&values[3]),
+            close: kline_amount(&values[4]),
+            volume: kline_amount(&values[5]),
             close_time: values[6].as_u64().unwrap_or(0),
-            quote_asset_volume: values[7].as_str().unwrap_or("0").to_string(),
+            quote_asset_volume: kline_amount(&values[7]),
             number_of_trades: values[8].as_u64().unwrap_or(0),
-            taker_buy_base_asset_volume: values[9].as_str().unwrap_or("0").to_string(),
-            taker_buy_quote_asset_volume: values[10].as_str().unwrap_or("0").to_string(),
+            taker_buy_base_asset_volume: kline_amount(&values[9]),
+            taker_buy_quote_asset_volume: kline_amount(&values[10]),
             ignore: values.get(11).and_then(|v| v.as_str()).unwrap_or("0").to_string(),
         }
     }
@@ -95,10 +108,10 @@ impl From<Vec<serde_json::Value>> for Kline {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trade {
     pub id: u64,
-    pub price: String,
-    pub qty: String,
+    pub price: Amount,
+    pub qty: Amount,
     #[serde(rename = "quoteQty")]
-    pub quote_qty: String,
+    pub quote_qty: Amount,
     pub time: u64,
     #[serde(rename = "isBuyerMaker")]
     pub is_buyer_maker: bool,
@@ -110,9 +123,9 @@ pub struct AggTrade {
     #[serde(rename = "a")]
     pub agg_trade_id: u64,
     #[serde(rename = "p")]
-    pub price: String,
+    pub price: Amount,
     #[serde(rename = "q")]
-    pub quantity: String,
+    pub quantity: Amount,
     #[serde(rename = "f")]
     pub first_trade_id: u64,
     #[serde(rename = "l")]
@@ -128,20 +141,42 @@ pub struct AggTrade {
 pub struct MarkPrice {
     pub symbol: String,
     #[serde(rename = "markPrice")]
-    pub mark_price: String,
+    pub mark_price: Amount,
     #[serde(rename = "indexPrice")]
-    pub index_price: String,
+    pub index_price: Amount,
     #[serde(rename = "estimatedSettlePrice")]
-    pub estimated_settle_price: String,
+    pub estimated_settle_price: Amount,
     #[serde(rename = "lastFundingRate")]
-    pub last_funding_rate: String,
+    pub last_funding_rate: Amount,
     #[serde(rename = "nextFundingTime")]
     pub next_funding_time: u64,
     #[serde(rename = "interestRate")]
-    pub interest_rate: String,
+    pub interest_rate: Amount,
     pub time: u64,
 }
 
+/// Historical funding rate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundingRate {
+    pub symbol: String,
+    #[serde(rename = "fundingRate")]
+    pub funding_rate: Amount,
+    #[serde(rename = "fundingTime")]
+    pub funding_time: u64,
+    /// Mark price at funding time; empty in older records
+    #[serde(rename = "markPrice", default, deserialize_with = "empty_as_none")]
+    pub mark_price: Option<Amount>,
+}
+
+/// Treat `""` as a missing amount
+fn empty_as_none<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<Amount>, D::Error> {
+    let value: Option<String> = Option::deserialize(deserializer)?;
+    value
+        .filter(|value| !value.is_empty())
+        .map(|value| value.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
 /// Exchange information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExchangeInfo {
@@ -183,13 +218,120 @@ pub struct SymbolInfo {
     pub base_asset_precision: i32,
     #[serde(rename = "quotePrecision")]
     pub quote_precision: i32,
-    pub filters: Vec<serde_json::Value>,
+    pub filters: Vec<SymbolFilter>,
     #[serde(rename = "orderTypes")]
     pub order_types: Vec<String>,
     #[serde(rename = "timeInForce")]
     pub time_in_force: Vec<String>,
 }
 
+impl ExchangeInfo {
+    /// Find a symbol's trading rules
+    pub fn symbol(&self, symbol: &str) -> Option<&SymbolInfo> {
+        self.symbols.iter().find(|s| s.symbol == symbol)
+    }
+}
+
+/// Trading rule from `exchangeInfo`, tagged by `filterType`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "filterType", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SymbolFilter {
+    /// Price range and tick size; zero disables a bound
+    #[serde(rename_all = "camelCase")]
+    PriceFilter {
+        min_price: Amount,
+        max_price: Amount,
+        tick_size: Amount,
+    },
+    /// Quantity range and step size for non-market orders
+    #[serde(rename_all = "camelCase")]
+    LotSize {
+        min_qty: Amount,
+        max_qty: Amount,
+        step_size: Amount,
+    },
+    /// Quantity range and step size for market orders
+    #[serde(rename_all = "camelCase")]
+    MarketLotSize {
+        min_qty: Amount,
+        max_qty: Amount,
+        step_size: Amount,
+    },
+    /// Minimum `price * quantity`
+    MinNotional { notional: Amount },
+    /// Allowed price band relative to the mark price
+    #[serde(rename_all = "camelCase")]
+    PercentPrice {
+        multiplier_up: Amount,
+        multiplier_down: Amount,
+    },
+    /// Maximum number of open orders
+    MaxNumOrders { limit: u32 },
+    /// Maximum number of open conditional orders
+    MaxNumAlgoOrders { limit: u32 },
+    /// Filter type not known to this crate
+    #[serde(other)]
+    Unknown,
+}
+
+impl SymbolInfo {
+    /// `PRICE_FILTER` as `(min_price, max_price, tick_size)`
+    pub fn price_filter(&self) -> Option<(&Amount, &Amount, &Amount)> {
+        self.filters.iter().find_map(|f| match f {
+            SymbolFilter::PriceFilter { min_price, max_price, tick_size } => Some((min_price, max_price, tick_size)),
+            _ => None,
+        })
+    }
+
+    /// `LOT_SIZE` as `(min_qty, max_qty, step_size)`
+    pub fn lot_size(&self) -> Option<(&Amount, &Amount, &Amount)> {
+        self.filters.iter().find_map(|f| match f {
+            SymbolFilter::LotSize { min_qty, max_qty, step_size } => Some((min_qty, max_qty, step_size)),
+            _ => None,
+        })
+    }
+
+    /// `MARKET_LOT_SIZE` as `(min_qty, max_qty, step_size)`
+    pub fn market_lot_size(&self) -> Option<(&Amount, &Amount, &Amount)> {
+        self.filters.iter().find_map(|f| match f {
+            SymbolFilter::MarketLotSize { min_qty, max_qty, step_size } => Some((min_qty, max_qty, step_size)),
+            _ => None,
+        })
+    }
+
+    /// `MIN_NOTIONAL` notional
+    pub fn min_notional(&self) -> Option<&Amount> {
+        self.filters.iter().find_map(|f| match f {
+            SymbolFilter::MinNotional { notional } => Some(notional),
+            _ => None,
+        })
+    }
+
+    /// `PERCENT_PRICE` as `(multiplier_up, multiplier_down)`
+    pub fn percent_price(&self) -> Option<(&Amount, &Amount)> {
+        self.filters.iter().find_map(|f| match f {
+            SymbolFilter::PercentPrice { multiplier_up, multiplier_down } => Some((multiplier_up, multiplier_down)),
+            _ => None,
+        })
+    }
+
+    /// `MAX_NUM_ORDERS` limit
+    pub fn max_num_orders(&self) -> Option<u32> {
+        self.filters.iter().find_map(|f| match f {
+            SymbolFilter::MaxNumOrders { limit } => Some(*limit),
+            _ => None,
+        })
+    }
+
+    /// `MAX_NUM_ALGO_ORDERS` limit
+    pub fn max_num_algo_orders(&self) -> Option<u32> {
+        self.filters.iter().find_map(|f| match f {
+            SymbolFilter::MaxNumAlgoOrders { limit } => Some(*limit),
+            _ => None,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -213,7 +355,34 @@ mod tests {
 
         let kline = Kline::from(values);
         assert_eq!(kline.open_time, 1640995200000);
-        assert_eq!(kline.open, "50000.0");
-        assert_eq!(kline.high, "51000.0");
+        assert_eq!(kline.open.to_string(), "50000.0");
+        assert_eq!(kline.high.to_string(), "51000.0");
+    }
+
+    #[test]
+    fn test_symbol_filters_deserialization() {
+        let json = r#"[
+            {"minPrice":"556.80","maxPrice":"4529764","filterType":"PRICE_FILTER","tickSize":"0.10"},
+            {"stepSize":"0.001","filterType":"LOT_SIZE","maxQty":"1000","minQty":"0.001"},
+            {"stepSize":"0.001","filterType":"MARKET_LOT_SIZE","maxQty":"120","minQty":"0.001"},
+            {"limit":200,"filterType":"MAX_NUM_ORDERS"},
+            {"limit":10,"filterType":"MAX_NUM_ALGO_ORDERS"},
+            {"notional":"100","filterType":"MIN_NOTIONAL"},
+            {"multiplierDown":"0.9500","multiplierUp":"1.0500","multiplierDecimal":"4","filterType":"PERCENT_PRICE"},
+            {"filterType":"POSITION_RISK_CONTROL","positionControlSide":"NONE"}
+        ]"#;
+
+        let filters: Vec<SymbolFilter> = serde_json::from_str(json).unwrap();
+        assert_eq!(filters.len(), 8);
+        assert_eq!(filters[3], SymbolFilter::MaxNumOrders { limit: 200 });
+        assert_eq!(filters[7], SymbolFilter::Unknown);
+        match &filters[0] {
+            SymbolFilter::PriceFilter { tick_size, .. } => assert_eq!(tick_size.to_string(), "0.10"),
+            other => panic!("unexpected filter {:?}", other),
+        }
+        match &filters[6] {
+            SymbolFilter::PercentPrice { multiplier_up, .. } => assert_eq!(multiplier_up.to_string(), "1.0500"),
+            other => panic!("unexpected filter {:?}", other),
+        }
     }
 }
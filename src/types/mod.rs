@@ -1,9 +1,25 @@
 pub mod account;
 pub mod common;
+#[cfg(feature = "decimal")]
+pub mod decimal;
 pub mod market;
+pub mod order_builder;
 pub mod trading;
 
 pub use account::*;
 pub use common::*;
+#[cfg(feature = "decimal")]
+pub use decimal::{Decimal, RoundingMode, RoundingStrategy};
 pub use market::*;
+pub use order_builder::{CallbackRate, LimitTimeInForce, OrderBuilder};
 pub use trading::*;
+
+/// Prices, quantities, rates and balances: an exact [`Decimal`] with the
+/// `decimal` feature, otherwise the string Binance sends
+#[cfg(feature = "decimal")]
+pub type Amount = Decimal;
+
+/// Prices, quantities, rates and balances: an exact `Decimal` with the
+/// `decimal` feature, otherwise the string Binance sends
+#[cfg(not(feature = "decimal"))]
+pub type Amount = String;
@@ -0,0 +1,472 @@
+use crate::error::{BinanceError, Result};
+use crate::types::common::{
+    NewOrderRespType, OrderSide, OrderType, PositionSide, PriceMatch, SelfTradePreventionMode, TimeInForce,
+    WorkingType,
+};
+#[cfg(feature = "decimal")]
+use crate::types::decimal::Decimal;
+use crate::types::Amount;
+use crate::types::trading::NewOrderRequest;
+use std::marker::PhantomData;
+
+/// Order kinds used as the type parameter of [`OrderBuilder`]
+pub mod kind {
+    /// LIMIT
+    #[derive(Debug, Clone, Copy)]
+    pub struct Limit;
+    /// MARKET
+    #[derive(Debug, Clone, Copy)]
+    pub struct Market;
+    /// STOP (stop limit)
+    #[derive(Debug, Clone, Copy)]
+    pub struct Stop;
+    /// STOP_MARKET with a quantity
+    #[derive(Debug, Clone, Copy)]
+    pub struct StopMarket;
+    /// STOP_MARKET closing the whole position
+    #[derive(Debug, Clone, Copy)]
+    pub struct StopMarketClose;
+    /// TAKE_PROFIT (take profit limit)
+    #[derive(Debug, Clone, Copy)]
+    pub struct TakeProfit;
+    /// TAKE_PROFIT_MARKET with a quantity
+    #[derive(Debug, Clone, Copy)]
+    pub struct TakeProfitMarket;
+    /// TAKE_PROFIT_MARKET closing the whole position
+    #[derive(Debug, Clone, Copy)]
+    pub struct TakeProfitMarketClose;
+    /// TRAILING_STOP_MARKET
+    #[derive(Debug, Clone, Copy)]
+    pub struct TrailingStop;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An order kind
+pub trait OrderKind: sealed::Sealed {
+    const ORDER_TYPE: OrderType;
+}
+
+/// Kinds with a limit price and time in force
+pub trait LimitPriced: OrderKind {}
+
+/// Kinds triggered by a stop price or a callback
+pub trait Triggered: OrderKind {}
+
+/// Kinds with an explicit quantity, which may be reduce-only
+pub trait WithQuantity: OrderKind {}
+
+macro_rules! order_kind {
+    ($kind:ident => $order_type:ident $(, $marker:ident)*) => {
+        impl sealed::Sealed for kind::$kind {}
+        impl OrderKind for kind::$kind {
+            const ORDER_TYPE: OrderType = OrderType::$order_type;
+        }
+        $(impl $marker for kind::$kind {})*
+    };
+}
+
+order_kind!(Limit => Limit, LimitPriced, WithQuantity);
+order_kind!(Market => Market, WithQuantity);
+order_kind!(Stop => Stop, LimitPriced, Triggered, WithQuantity);
+order_kind!(StopMarket => StopMarket, Triggered, WithQuantity);
+order_kind!(StopMarketClose => StopMarket, Triggered);
+order_kind!(TakeProfit => TakeProfit, LimitPriced, Triggered, WithQuantity);
+order_kind!(TakeProfitMarket => TakeProfitMarket, Triggered, WithQuantity);
+order_kind!(TakeProfitMarketClose => TakeProfitMarket, Triggered);
+order_kind!(TrailingStop => TrailingStopMarket, Triggered, WithQuantity);
+
+/// Time in force of a limit order built with [`NewOrderRequest::limit`] or
+/// [`OrderBuilder::time_in_force`].
+///
+/// GTD has no variant here because it needs an expiry; set it with
+/// [`OrderBuilder::good_till_date`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitTimeInForce {
+    Gtc,
+    Ioc,
+    Fok,
+    Gtx,
+}
+
+impl From<LimitTimeInForce> for TimeInForce {
+    fn from(time_in_force: LimitTimeInForce) -> Self {
+        match time_in_force {
+            LimitTimeInForce::Gtc => TimeInForce::Gtc,
+            LimitTimeInForce::Ioc => TimeInForce::Ioc,
+            LimitTimeInForce::Fok => TimeInForce::Fok,
+            LimitTimeInForce::Gtx => TimeInForce::Gtx,
+        }
+    }
+}
+
+impl TryFrom<TimeInForce> for LimitTimeInForce {
+    type Error = BinanceError;
+
+    fn try_from(time_in_force: TimeInForce) -> Result<Self> {
+        match time_in_force {
+            TimeInForce::Gtc => Ok(LimitTimeInForce::Gtc),
+            TimeInForce::Ioc => Ok(LimitTimeInForce::Ioc),
+            TimeInForce::Fok => Ok(LimitTimeInForce::Fok),
+            TimeInForce::Gtx => Ok(LimitTimeInForce::Gtx),
+            TimeInForce::Gtd => Err(BinanceError::InvalidParameter(
+                "GTD needs an expiry, set it with good_till_date".to_string(),
+            )),
+        }
+    }
+}
+
+/// Trailing stop callback rate in percent, between 0.1 and 10
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallbackRate(Amount);
+
+impl CallbackRate {
+    #[cfg(feature = "decimal")]
+    pub const MIN: Decimal = Decimal::new(1, 1);
+    #[cfg(feature = "decimal")]
+    pub const MAX: Decimal = Decimal::new(10, 0);
+
+    pub fn new(rate: Amount) -> Result<Self> {
+        if !Self::in_range(&rate) {
+            return Err(BinanceError::InvalidParameter(format!(
+                "Callback rate {} must be between 0.1 and 10",
+                rate
+            )));
+        }
+        Ok(Self(rate))
+    }
+
+    #[cfg(feature = "decimal")]
+    fn in_range(rate: &Decimal) -> bool {
+        (Self::MIN..=Self::MAX).contains(rate)
+    }
+
+    /// Without exact decimals the bounds are compared as floats, which 0.1 and 10 parse to exactly as the literals
+    #[cfg(not(feature = "decimal"))]
+    fn in_range(rate: &str) -> bool {
+        rate.parse::<f64>().is_ok_and(|rate| (0.1..=10.0).contains(&rate))
+    }
+
+    pub fn value(&self) -> &Amount {
+        &self.0
+    }
+}
+
+/// Order builder that only offers the fields valid for its order kind.
+///
+/// Created by the typed constructors on [`NewOrderRequest`], e.g.
+/// [`NewOrderRequest::limit`]; every required field is a constructor
+/// argument, so a built order always has them.
+#[derive(Debug, Clone)]
+pub struct OrderBuilder<K: OrderKind> {
+    request: NewOrderRequest,
+    kind: PhantomData<K>,
+}
+
+impl<K: OrderKind> OrderBuilder<K> {
+    fn new(symbol: String, side: OrderSide) -> Self {
+        Self {
+            request: NewOrderRequest::new(symbol, side, K::ORDER_TYPE),
+            kind: PhantomData,
+        }
+    }
+
+    fn with(mut self, update: impl FnOnce(&mut NewOrderRequest)) -> Self {
+        update(&mut self.request);
+        self
+    }
+
+    pub fn position_side(self, position_side: PositionSide) -> Self {
+        self.with(|r| r.position_side = Some(position_side))
+    }
+
+    pub fn client_order_id(self, client_order_id: String) -> Self {
+        self.with(|r| r.new_client_order_id = Some(client_order_id))
+    }
+
+    pub fn self_trade_prevention_mode(self, mode: SelfTradePreventionMode) -> Self {
+        self.with(|r| r.self_trade_prevention_mode = Some(mode))
+    }
+
+    pub fn new_order_resp_type(self, resp_type: NewOrderRespType) -> Self {
+        self.with(|r| r.new_order_resp_type = Some(resp_type))
+    }
+
+    /// The request built so far
+    pub fn request(&self) -> &NewOrderRequest {
+        &self.request
+    }
+
+    pub fn build(self) -> NewOrderRequest {
+        self.request
+    }
+}
+
+impl<K: WithQuantity> OrderBuilder<K> {
+    pub fn reduce_only(self, reduce_only: bool) -> Self {
+        self.with(|r| r.reduce_only = Some(reduce_only))
+    }
+}
+
+impl<K: LimitPriced> OrderBuilder<K> {
+    /// Replaces the time in force, and any expiry set by `good_till_date`
+    pub fn time_in_force(self, time_in_force: LimitTimeInForce) -> Self {
+        self.with(|r| {
+            r.time_in_force = Some(time_in_force.into());
+            r.good_till_date = None;
+        })
+    }
+
+    /// Expire the order at `good_till_date` (ms); also sets `TimeInForce::Gtd`
+    pub fn good_till_date(self, good_till_date: u64) -> Self {
+        self.with(|r| {
+            r.good_till_date = Some(good_till_date);
+            r.time_in_force = Some(TimeInForce::Gtd);
+        })
+    }
+
+    /// Match the price to the order book; replaces the limit price
+    pub fn price_match(self, price_match: PriceMatch) -> Self {
+        self.with(|r| {
+            r.price = None;
+            r.price_match = Some(price_match);
+        })
+    }
+}
+
+impl<K: Triggered> OrderBuilder<K> {
+    pub fn working_type(self, working_type: WorkingType) -> Self {
+        self.with(|r| r.working_type = Some(working_type))
+    }
+
+    pub fn price_protect(self, price_protect: bool) -> Self {
+        self.with(|r| r.price_protect = Some(price_protect))
+    }
+}
+
+impl<K: OrderKind> From<OrderBuilder<K>> for NewOrderRequest {
+    fn from(builder: OrderBuilder<K>) -> Self {
+        builder.request
+    }
+}
+
+impl NewOrderRequest {
+    /// LIMIT order
+    pub fn limit(
+        symbol: String,
+        side: OrderSide,
+        quantity: Amount,
+        price: Amount,
+        time_in_force: LimitTimeInForce,
+    ) -> OrderBuilder<kind::Limit> {
+        OrderBuilder::new(symbol, side).with(|r| {
+            r.quantity = Some(quantity);
+            r.price = Some(price);
+            r.time_in_force = Some(time_in_force.into());
+        })
+    }
+
+    /// MARKET order
+    pub fn market(symbol: String, side: OrderSide, quantity: Amount) -> OrderBuilder<kind::Market> {
+        OrderBuilder::new(symbol, side).with(|r| r.quantity = Some(quantity))
+    }
+
+    /// STOP order: a LIMIT order at `price` placed once `stop_price` is reached
+    pub fn stop(
+        symbol: String,
+        side: OrderSide,
+        quantity: Amount,
+        price: Amount,
+        stop_price: Amount,
+    ) -> OrderBuilder<kind::Stop> {
+        OrderBuilder::new(symbol, side).with(|r| {
+            r.quantity = Some(quantity);
+            r.price = Some(price);
+            r.stop_price = Some(stop_price);
+        })
+    }
+
+    /// STOP_MARKET order for `quantity`
+    pub fn stop_market(
+        symbol: String,
+        side: OrderSide,
+        quantity: Amount,
+        stop_price: Amount,
+    ) -> OrderBuilder<kind::StopMarket> {
+        OrderBuilder::new(symbol, side).with(|r| {
+            r.quantity = Some(quantity);
+            r.stop_price = Some(stop_price);
+        })
+    }
+
+    /// STOP_MARKET order that closes the whole position (`closePosition=true`, no quantity)
+    pub fn stop_market_close_position(
+        symbol: String,
+        side: OrderSide,
+        stop_price: Amount,
+    ) -> OrderBuilder<kind::StopMarketClose> {
+        OrderBuilder::new(symbol, side).with(|r| {
+            r.stop_price = Some(stop_price);
+            r.close_position = Some(true);
+        })
+    }
+
+    /// TAKE_PROFIT order: a LIMIT order at `price` placed once `stop_price` is reached
+    pub fn take_profit(
+        symbol: String,
+        side: OrderSide,
+        quantity: Amount,
+        price: Amount,
+        stop_price: Amount,
+    ) -> OrderBuilder<kind::TakeProfit> {
+        OrderBuilder::new(symbol, side).with(|r| {
+            r.quantity = Some(quantity);
+            r.price = Some(price);
+            r.stop_price = Some(stop_price);
+        })
+    }
+
+    /// TAKE_PROFIT_MARKET order for `quantity`
+    pub fn take_profit_market(
+        symbol: String,
+        side: OrderSide,
+        quantity: Amount,
+        stop_price: Amount,
+    ) -> OrderBuilder<kind::TakeProfitMarket> {
+        OrderBuilder::new(symbol, side).with(|r| {
+            r.quantity = Some(quantity);
+            r.stop_price = Some(stop_price);
+        })
+    }
+
+    /// TAKE_PROFIT_MARKET order that closes the whole position (`closePosition=true`, no quantity)
+    pub fn take_profit_market_close_position(
+        symbol: String,
+        side: OrderSide,
+        stop_price: Amount,
+    ) -> OrderBuilder<kind::TakeProfitMarketClose> {
+        OrderBuilder::new(symbol, side).with(|r| {
+            r.stop_price = Some(stop_price);
+            r.close_position = Some(true);
+        })
+    }
+
+    /// TRAILING_STOP_MARKET order; without `activation_price` it activates at the current price
+    pub fn trailing_stop(
+        symbol: String,
+        side: OrderSide,
+        quantity: Amount,
+        callback_rate: CallbackRate,
+        activation_price: Option<Amount>,
+    ) -> OrderBuilder<kind::TrailingStop> {
+        OrderBuilder::new(symbol, side).with(|r| {
+            r.quantity = Some(quantity);
+            r.callback_rate = Some(callback_rate.0);
+            r.activation_price = activation_price;
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(value: &str) -> Amount {
+        value.parse().unwrap()
+    }
+
+    fn limit_order() -> OrderBuilder<kind::Limit> {
+        NewOrderRequest::limit("BTCUSDT".to_string(), OrderSide::Buy, dec("1"), dec("30000"), LimitTimeInForce::Gtc)
+    }
+
+    #[test]
+    fn test_typed_constructors_set_required_fields() {
+        let limit = limit_order()
+            .reduce_only(true)
+            .build();
+        assert_eq!(limit.order_type, OrderType::Limit);
+        assert_eq!(limit.price.unwrap().to_string(), "30000");
+        assert_eq!(limit.time_in_force, Some(TimeInForce::Gtc));
+        assert_eq!(limit.reduce_only, Some(true));
+
+        let stop: NewOrderRequest =
+            NewOrderRequest::stop("BTCUSDT".to_string(), OrderSide::Sell, dec("1"), dec("29000"), dec("29100"))
+                .working_type(WorkingType::MarkPrice)
+                .into();
+        assert_eq!(stop.order_type, OrderType::Stop);
+        assert_eq!(stop.stop_price.unwrap().to_string(), "29100");
+        assert_eq!(stop.working_type, Some(WorkingType::MarkPrice));
+
+        let market = NewOrderRequest::market("BTCUSDT".to_string(), OrderSide::Buy, dec("0.5")).build();
+        assert_eq!(market.order_type, OrderType::Market);
+        assert!(market.price.is_none());
+    }
+
+    #[test]
+    fn test_close_position_has_no_quantity() {
+        let close = NewOrderRequest::stop_market_close_position("BTCUSDT".to_string(), OrderSide::Sell, dec("28000"))
+            .price_protect(true)
+            .build();
+        assert_eq!(close.order_type, OrderType::StopMarket);
+        assert_eq!(close.close_position, Some(true));
+        assert!(close.quantity.is_none());
+        assert!(close.reduce_only.is_none());
+
+        let take_profit =
+            NewOrderRequest::take_profit_market_close_position("BTCUSDT".to_string(), OrderSide::Sell, dec("35000"))
+                .build();
+        assert_eq!(take_profit.order_type, OrderType::TakeProfitMarket);
+        assert!(take_profit.quantity.is_none());
+    }
+
+    #[test]
+    fn test_price_match_replaces_price() {
+        let order = limit_order()
+            .price_match(PriceMatch::Queue)
+            .good_till_date(1_700_000_000_000)
+            .build();
+        assert!(order.price.is_none());
+        assert_eq!(order.price_match, Some(PriceMatch::Queue));
+        assert_eq!(order.time_in_force, Some(TimeInForce::Gtd));
+    }
+
+    #[test]
+    fn test_gtd_only_with_an_expiry() {
+        assert!(LimitTimeInForce::try_from(TimeInForce::Gtd).is_err());
+        assert_eq!(LimitTimeInForce::try_from(TimeInForce::Gtx).unwrap(), LimitTimeInForce::Gtx);
+
+        let order = limit_order().good_till_date(1_700_000_000_000).build();
+        assert_eq!(order.time_in_force, Some(TimeInForce::Gtd));
+        assert_eq!(order.good_till_date, Some(1_700_000_000_000));
+
+        // Switching back drops the expiry with GTD
+        let order = limit_order()
+            .good_till_date(1_700_000_000_000)
+            .time_in_force(LimitTimeInForce::Ioc)
+            .build();
+        assert_eq!(order.time_in_force, Some(TimeInForce::Ioc));
+        assert!(order.good_till_date.is_none());
+    }
+
+    #[test]
+    fn test_trailing_stop_callback_rate() {
+        assert!(CallbackRate::new(dec("0.1")).is_ok());
+        assert!(CallbackRate::new(dec("10")).is_ok());
+        assert!(CallbackRate::new(dec("0.05")).is_err());
+        assert!(CallbackRate::new(dec("10.1")).is_err());
+
+        let order = NewOrderRequest::trailing_stop(
+            "BTCUSDT".to_string(),
+            OrderSide::Sell,
+            dec("1"),
+            CallbackRate::new(dec("1.5")).unwrap(),
+            Some(dec("31000")),
+        )
+        .build();
+        assert_eq!(order.order_type, OrderType::TrailingStopMarket);
+        assert_eq!(order.callback_rate.unwrap().to_string(), "1.5");
+        assert_eq!(order.activation_price.unwrap().to_string(), "31000");
+    }
+}
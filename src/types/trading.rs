@@ -1,30 +1,46 @@
 use serde::{Deserialize, Serialize};
-use crate::types::common::{OrderSide, OrderType, OrderStatus, TimeInForce, PositionSide, WorkingType};
+use crate::types::common::{
+    NewOrderRespType, OrderSide, OrderStatus, OrderType, PositionMode, PositionSide, PriceMatch,
+    SelfTradePreventionMode, TimeInForce, WorkingType,
+};
+use crate::error::{ApiErrorResponse, BinanceError, Result};
+use crate::types::Amount;
+use std::sync::Arc;
 
 /// New order request
+///
+/// Built with the typed constructors ([`NewOrderRequest::limit`],
+/// [`NewOrderRequest::market`], ...), which only accept the fields valid
+/// for each order type; the getters below read it back.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NewOrderRequest {
-    pub symbol: String,
-    pub side: OrderSide,
+    pub(crate) symbol: String,
+    pub(crate) side: OrderSide,
     #[serde(rename = "type")]
-    pub order_type: OrderType,
-    pub position_side: Option<PositionSide>,
-    pub time_in_force: Option<TimeInForce>,
-    pub quantity: Option<String>,
-    pub reduce_only: Option<bool>,
-    pub price: Option<String>,
-    pub new_client_order_id: Option<String>,
-    pub stop_price: Option<String>,
-    pub close_position: Option<bool>,
-    pub activation_price: Option<String>,
-    pub callback_rate: Option<String>,
-    pub working_type: Option<WorkingType>,
-    pub price_protect: Option<bool>,
+    pub(crate) order_type: OrderType,
+    pub(crate) position_side: Option<PositionSide>,
+    pub(crate) time_in_force: Option<TimeInForce>,
+    pub(crate) quantity: Option<Amount>,
+    pub(crate) reduce_only: Option<bool>,
+    pub(crate) price: Option<Amount>,
+    pub(crate) new_client_order_id: Option<String>,
+    pub(crate) stop_price: Option<Amount>,
+    pub(crate) close_position: Option<bool>,
+    pub(crate) activation_price: Option<Amount>,
+    pub(crate) callback_rate: Option<Amount>,
+    pub(crate) working_type: Option<WorkingType>,
+    pub(crate) price_protect: Option<bool>,
+    pub(crate) price_match: Option<PriceMatch>,
+    pub(crate) self_trade_prevention_mode: Option<SelfTradePreventionMode>,
+    /// Expiry in milliseconds, required with `TimeInForce::Gtd`
+    pub(crate) good_till_date: Option<u64>,
+    pub(crate) new_order_resp_type: Option<NewOrderRespType>,
 }
 
 impl NewOrderRequest {
-    pub fn new(symbol: String, side: OrderSide, order_type: OrderType) -> Self {
+    /// Empty request of `order_type`; the typed constructors fill in the rest
+    pub(crate) fn new(symbol: String, side: OrderSide, order_type: OrderType) -> Self {
         Self {
             symbol,
             side,
@@ -41,42 +57,109 @@ impl NewOrderRequest {
             callback_rate: None,
             working_type: None,
             price_protect: None,
+            price_match: None,
+            self_trade_prevention_mode: None,
+            good_till_date: None,
+            new_order_resp_type: None,
         }
     }
 
-    pub fn quantity(mut self, quantity: String) -> Self {
-        self.quantity = Some(quantity);
-        self
+    pub fn symbol(&self) -> &str {
+        &self.symbol
     }
 
-    pub fn price(mut self, price: String) -> Self {
-        self.price = Some(price);
-        self
+    pub fn side(&self) -> OrderSide {
+        self.side
     }
 
-    pub fn time_in_force(mut self, time_in_force: TimeInForce) -> Self {
-        self.time_in_force = Some(time_in_force);
-        self
+    pub fn order_type(&self) -> OrderType {
+        self.order_type
     }
 
-    pub fn position_side(mut self, position_side: PositionSide) -> Self {
-        self.position_side = Some(position_side);
-        self
+    pub fn position_side(&self) -> Option<PositionSide> {
+        self.position_side
     }
 
-    pub fn reduce_only(mut self, reduce_only: bool) -> Self {
-        self.reduce_only = Some(reduce_only);
-        self
+    pub fn time_in_force(&self) -> Option<TimeInForce> {
+        self.time_in_force
     }
 
-    pub fn stop_price(mut self, stop_price: String) -> Self {
-        self.stop_price = Some(stop_price);
-        self
+    pub fn quantity(&self) -> Option<&Amount> {
+        self.quantity.as_ref()
     }
 
-    pub fn client_order_id(mut self, client_order_id: String) -> Self {
-        self.new_client_order_id = Some(client_order_id);
-        self
+    pub fn reduce_only(&self) -> Option<bool> {
+        self.reduce_only
+    }
+
+    pub fn price(&self) -> Option<&Amount> {
+        self.price.as_ref()
+    }
+
+    pub fn client_order_id(&self) -> Option<&str> {
+        self.new_client_order_id.as_deref()
+    }
+
+    pub fn stop_price(&self) -> Option<&Amount> {
+        self.stop_price.as_ref()
+    }
+
+    pub fn close_position(&self) -> Option<bool> {
+        self.close_position
+    }
+
+    pub fn activation_price(&self) -> Option<&Amount> {
+        self.activation_price.as_ref()
+    }
+
+    /// Trailing stop callback rate in percent
+    pub fn callback_rate(&self) -> Option<&Amount> {
+        self.callback_rate.as_ref()
+    }
+
+    pub fn working_type(&self) -> Option<WorkingType> {
+        self.working_type
+    }
+
+    pub fn price_protect(&self) -> Option<bool> {
+        self.price_protect
+    }
+
+    pub fn price_match(&self) -> Option<PriceMatch> {
+        self.price_match
+    }
+
+    pub fn self_trade_prevention_mode(&self) -> Option<SelfTradePreventionMode> {
+        self.self_trade_prevention_mode
+    }
+
+    /// Expiry in milliseconds, set with `TimeInForce::Gtd`
+    pub fn good_till_date(&self) -> Option<u64> {
+        self.good_till_date
+    }
+
+    pub fn new_order_resp_type(&self) -> Option<NewOrderRespType> {
+        self.new_order_resp_type
+    }
+
+    /// Check that `position_side` and `reduce_only` fit the account's position mode.
+    ///
+    /// A mismatch is reported as `InvalidParameter`; Binance would reject the
+    /// same order with -4061 (position side) or -1106 (`reduceOnly` in hedge mode).
+    pub fn check_position_mode(&self, mode: PositionMode) -> Result<()> {
+        if !mode.accepts(self.position_side) {
+            let position_side = self.position_side.map_or("none".to_string(), |side| side.to_string());
+            return Err(BinanceError::InvalidParameter(format!(
+                "Order's position side {} does not match {} position mode",
+                position_side, mode
+            )));
+        }
+        if mode == PositionMode::Hedge && self.reduce_only.is_some() {
+            return Err(BinanceError::InvalidParameter(
+                "reduceOnly cannot be sent in Hedge position mode".to_string(),
+            ));
+        }
+        Ok(())
     }
 }
 
@@ -86,33 +169,113 @@ impl NewOrderRequest {
 pub struct Order {
     pub symbol: String,
     pub order_id: u64,
-    pub order_list_id: i64,
     pub client_order_id: String,
-    pub price: String,
-    pub orig_qty: String,
-    pub executed_qty: String,
-    pub cummulative_quote_qty: String,
+    pub price: Amount,
+    pub avg_price: Amount,
+    pub orig_qty: Amount,
+    pub executed_qty: Amount,
+    pub cum_qty: Option<Amount>,
+    pub cum_quote: Amount,
     pub status: OrderStatus,
     pub time_in_force: TimeInForce,
     #[serde(rename = "type")]
     pub order_type: OrderType,
+    pub orig_type: OrderType,
     pub side: OrderSide,
-    pub stop_price: String,
-    pub ice_berg_qty: String,
-    pub time: u64,
-    pub update_time: u64,
-    pub is_working: bool,
-    pub working_time: u64,
-    pub orig_quote_order_qty: String,
     pub position_side: PositionSide,
-    pub price_protect: bool,
+    pub stop_price: Amount,
+    pub reduce_only: bool,
     pub close_position: bool,
-    pub activation_price: Option<String>,
-    pub callback_rate: Option<String>,
+    #[serde(rename = "activatePrice")]
+    pub activation_price: Option<Amount>,
+    #[serde(rename = "priceRate")]
+    pub callback_rate: Option<Amount>,
     pub working_type: WorkingType,
-    pub price_match: Option<String>,
-    pub self_trade_prevention_mode: Option<String>,
+    pub price_protect: bool,
+    pub price_match: Option<PriceMatch>,
+    pub self_trade_prevention_mode: Option<SelfTradePreventionMode>,
     pub good_till_date: Option<u64>,
+    pub time: Option<u64>,
+    pub update_time: u64,
+}
+
+/// Modify order request
+///
+/// Only LIMIT orders can be modified. Either `price` or `price_match` must be set.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModifyOrderRequest {
+    pub symbol: String,
+    pub order_id: Option<u64>,
+    pub orig_client_order_id: Option<String>,
+    pub side: OrderSide,
+    pub quantity: Amount,
+    pub price: Option<Amount>,
+    pub price_match: Option<PriceMatch>,
+}
+
+impl ModifyOrderRequest {
+    pub fn new(symbol: String, side: OrderSide, quantity: Amount) -> Self {
+        Self {
+            symbol,
+            order_id: None,
+            orig_client_order_id: None,
+            side,
+            quantity,
+            price: None,
+            price_match: None,
+        }
+    }
+
+    pub fn order_id(mut self, order_id: u64) -> Self {
+        self.order_id = Some(order_id);
+        self
+    }
+
+    pub fn client_order_id(mut self, client_order_id: String) -> Self {
+        self.orig_client_order_id = Some(client_order_id);
+        self
+    }
+
+    pub fn price(mut self, price: Amount) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    pub fn price_match(mut self, price_match: PriceMatch) -> Self {
+        self.price_match = Some(price_match);
+        self
+    }
+}
+
+/// A single field change in an order amendment
+#[derive(Debug, Clone, Deserialize)]
+pub struct AmendmentChange {
+    pub before: Amount,
+    pub after: Amount,
+}
+
+/// Details of an order amendment
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Amendment {
+    pub price: AmendmentChange,
+    pub orig_qty: AmendmentChange,
+    /// Number of times the order has been modified
+    pub count: u32,
+}
+
+/// Order amendment history entry
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderAmendment {
+    pub amendment_id: u64,
+    pub symbol: String,
+    pub pair: String,
+    pub order_id: u64,
+    pub client_order_id: String,
+    pub time: u64,
+    pub amendment: Amendment,
 }
 
 /// Cancel order request
@@ -188,6 +351,64 @@ impl BatchOrderRequest {
     }
 }
 
+/// Countdown cancel-all response
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountdownCancelAll {
+    pub symbol: String,
+    /// Countdown in milliseconds, `"0"` when the timer was cancelled
+    pub countdown_time: String,
+}
+
+/// Result of a single entry in a batch request.
+///
+/// Binance answers batch endpoints with a mixed array: accepted entries are
+/// returned as objects and rejected ones as `{code, msg}`, in request order.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum BatchItemResult<T> {
+    Err(ApiErrorResponse),
+    Ok(T),
+    /// The request carrying this entry failed without an answer per entry,
+    /// e.g. on a timeout or 5xx; see [`is_execution_unknown`](Self::is_execution_unknown)
+    #[serde(skip)]
+    Failed(Arc<BinanceError>),
+}
+
+impl<T> BatchItemResult<T> {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, BatchItemResult::Ok(_))
+    }
+
+    pub fn is_err(&self) -> bool {
+        !self.is_ok()
+    }
+
+    /// Whether the entry may have been executed although no result came back,
+    /// so it should be looked up before being sent again
+    pub fn is_execution_unknown(&self) -> bool {
+        matches!(self, BatchItemResult::Failed(err) if err.is_execution_unknown())
+    }
+
+    /// The accepted entry, discarding the error
+    pub fn ok(self) -> Option<T> {
+        match self {
+            BatchItemResult::Ok(value) => Some(value),
+            BatchItemResult::Err(_) | BatchItemResult::Failed(_) => None,
+        }
+    }
+
+    /// Convert into a `Result`, turning a rejected entry into an API error
+    /// and a failed one into [`BinanceError::BatchRequest`]
+    pub fn into_result(self) -> Result<T> {
+        match self {
+            BatchItemResult::Ok(value) => Ok(value),
+            BatchItemResult::Err(err) => Err(BinanceError::from(err)),
+            BatchItemResult::Failed(err) => Err(BinanceError::BatchRequest(err)),
+        }
+    }
+}
+
 /// Trade information
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -196,12 +417,12 @@ pub struct UserTrade {
     pub id: u64,
     pub order_id: u64,
     pub side: OrderSide,
-    pub price: String,
-    pub qty: String,
-    pub realized_pnl: String,
+    pub price: Amount,
+    pub qty: Amount,
+    pub realized_pnl: Amount,
     pub margin_asset: String,
-    pub quote_qty: String,
-    pub commission: String,
+    pub quote_qty: Amount,
+    pub commission: Amount,
     pub commission_asset: String,
     pub time: u64,
     pub position_side: PositionSide,
@@ -212,24 +433,71 @@ pub struct UserTrade {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::order_builder::{CallbackRate, LimitTimeInForce};
 
     #[test]
-    fn test_new_order_request_builder() {
-        let order = NewOrderRequest::new(
+    fn test_new_order_request_getters() {
+        let order: NewOrderRequest = NewOrderRequest::limit(
             "BTCUSDT".to_string(),
             OrderSide::Buy,
-            OrderType::Limit,
+            "1.0".parse().unwrap(),
+            "50000.0".parse().unwrap(),
+            LimitTimeInForce::Gtc,
+        )
+        .client_order_id("bot-1".to_string())
+        .into();
+
+        assert_eq!(order.symbol(), "BTCUSDT");
+        assert_eq!(order.side(), OrderSide::Buy);
+        assert_eq!(order.order_type(), OrderType::Limit);
+        assert_eq!(order.quantity().unwrap().to_string(), "1.0");
+        assert_eq!(order.price().unwrap().to_string(), "50000.0");
+        assert_eq!(order.time_in_force(), Some(TimeInForce::Gtc));
+        assert_eq!(order.client_order_id(), Some("bot-1"));
+    }
+
+    #[test]
+    fn test_new_order_request_options() {
+        let order = NewOrderRequest::trailing_stop(
+            "BTCUSDT".to_string(),
+            OrderSide::Sell,
+            "1".parse().unwrap(),
+            CallbackRate::new("0.5".parse().unwrap()).unwrap(),
+            Some("31000".parse().unwrap()),
         )
-        .quantity("1.0".to_string())
-        .price("50000.0".to_string())
-        .time_in_force(TimeInForce::Gtc);
+        .working_type(WorkingType::MarkPrice)
+        .price_protect(true)
+        .self_trade_prevention_mode(SelfTradePreventionMode::ExpireMaker)
+        .new_order_resp_type(NewOrderRespType::Result)
+        .build();
+
+        assert_eq!(order.callback_rate().unwrap().to_string(), "0.5");
+        assert_eq!(order.activation_price().unwrap().to_string(), "31000");
+        assert_eq!(order.working_type(), Some(WorkingType::MarkPrice));
+        assert_eq!(order.price_protect(), Some(true));
+        assert_eq!(order.self_trade_prevention_mode(), Some(SelfTradePreventionMode::ExpireMaker));
+        assert_eq!(order.new_order_resp_type(), Some(NewOrderRespType::Result));
+    }
+
+    #[test]
+    fn test_check_position_mode() {
+        let market = || NewOrderRequest::market("BTCUSDT".to_string(), OrderSide::Buy, "1".parse().unwrap());
+        let order = market().build();
+        assert!(order.check_position_mode(PositionMode::OneWay).is_ok());
+
+        let err = order.check_position_mode(PositionMode::Hedge).unwrap_err();
+        assert!(matches!(err, BinanceError::InvalidParameter(_)));
+        assert!(!err.is_order_rejected());
 
-        assert_eq!(order.symbol, "BTCUSDT");
-        assert_eq!(order.side, OrderSide::Buy);
-        assert_eq!(order.order_type, OrderType::Limit);
-        assert_eq!(order.quantity, Some("1.0".to_string()));
-        assert_eq!(order.price, Some("50000.0".to_string()));
-        assert_eq!(order.time_in_force, Some(TimeInForce::Gtc));
+        let hedged = market().position_side(PositionSide::Long);
+        assert!(hedged.request().check_position_mode(PositionMode::Hedge).is_ok());
+        assert!(hedged.request().check_position_mode(PositionMode::OneWay).is_err());
+
+        let reduce = hedged.reduce_only(true).build();
+        assert!(matches!(
+            reduce.check_position_mode(PositionMode::Hedge),
+            Err(BinanceError::InvalidParameter(_))
+        ));
     }
 
     #[test]
@@ -240,4 +508,73 @@ mod tests {
         assert_eq!(cancel_req.symbol, "BTCUSDT");
         assert_eq!(cancel_req.order_id, Some(12345));
     }
+
+    #[test]
+    fn test_modify_order_request_builder() {
+        let modify = ModifyOrderRequest::new("BTCUSDT".to_string(), OrderSide::Sell, "0.5".parse().unwrap())
+            .order_id(42)
+            .price_match(PriceMatch::Queue5);
+
+        assert_eq!(modify.order_id, Some(42));
+        assert_eq!(modify.quantity.to_string(), "0.5");
+        assert!(modify.price.is_none());
+        assert_eq!(modify.price_match, Some(PriceMatch::Queue5));
+    }
+
+    #[test]
+    fn test_order_deserialization() {
+        let json = r#"{
+            "orderId": 20072994037, "symbol": "BTCUSDT", "pair": "BTCUSDT", "status": "PARTIALLY_FILLED",
+            "clientOrderId": "LJ9R4QZDihCaS8UAOOLpgW", "price": "30005", "avgPrice": "30004.5",
+            "origQty": "1", "executedQty": "0.4", "cumQty": "0.4", "cumQuote": "12001.8",
+            "timeInForce": "GTC", "type": "LIMIT", "reduceOnly": false, "closePosition": false,
+            "side": "BUY", "positionSide": "BOTH", "stopPrice": "0", "workingType": "CONTRACT_PRICE",
+            "priceProtect": false, "origType": "LIMIT", "priceMatch": "NONE",
+            "selfTradePreventionMode": "NONE", "goodTillDate": 0, "updateTime": 1629182711600
+        }"#;
+
+        let order: Order = serde_json::from_str(json).unwrap();
+        assert_eq!(order.status, OrderStatus::PartiallyFilled);
+        assert_eq!(order.working_type, WorkingType::ContractPrice);
+        assert_eq!(order.price_match, Some(PriceMatch::None));
+        assert_eq!(order.self_trade_prevention_mode, Some(SelfTradePreventionMode::None));
+        assert_eq!(order.cum_quote.to_string(), "12001.8");
+        assert!(order.time.is_none());
+    }
+
+    #[test]
+    fn test_order_amendment_deserialization() {
+        let json = r#"[{
+            "amendmentId": 5363, "symbol": "BTCUSDT", "pair": "BTCUSDT", "orderId": 20072994037,
+            "clientOrderId": "LJ9R4QZDihCaS8UAOOLpgW", "time": 1629184560899,
+            "amendment": {
+                "price": {"before": "30004", "after": "30003.2"},
+                "origQty": {"before": "1", "after": "1"},
+                "count": 3
+            }
+        }]"#;
+
+        let history: Vec<OrderAmendment> = serde_json::from_str(json).unwrap();
+        assert_eq!(history[0].amendment_id, 5363);
+        assert_eq!(history[0].amendment.price.after.to_string(), "30003.2");
+        assert_eq!(history[0].amendment.count, 3);
+    }
+
+    #[test]
+    fn test_batch_item_result_deserialization() {
+        let json = r#"[
+            {"code": -2022, "msg": "ReduceOnly Order is rejected."},
+            {"amendmentId": 1, "symbol": "BTCUSDT", "pair": "BTCUSDT", "orderId": 2, "clientOrderId": "a",
+             "time": 3, "amendment": {"price": {"before": "1", "after": "2"},
+             "origQty": {"before": "1", "after": "1"}, "count": 1}}
+        ]"#;
+
+        let results: Vec<BatchItemResult<OrderAmendment>> = serde_json::from_str(json).unwrap();
+        assert!(results[0].is_err());
+        assert!(results[1].is_ok());
+
+        let err = results[0].clone().into_result().unwrap_err();
+        assert_eq!(err.code().unwrap().code(), -2022);
+        assert_eq!(results[1].clone().ok().unwrap().order_id, 2);
+    }
 }
@@ -13,7 +13,7 @@ pub fn get_timestamp() -> u64 {
 /// Convert timestamp to DateTime
 pub fn timestamp_to_datetime(timestamp: u64) -> DateTime<Utc> {
     DateTime::from_timestamp(timestamp as i64 / 1000, ((timestamp % 1000) * 1_000_000) as u32)
-        .unwrap_or_else(|| Utc::now())
+        .unwrap_or_else(Utc::now)
 }
 
 /// Build query string from parameters
@@ -0,0 +1,411 @@
+//! Local order checks against `exchangeInfo` symbol filters.
+//!
+//! Violations are reported with the same [`BinanceErrorCode`] the exchange
+//! would return (e.g. -4014 for a price off the tick grid, -4164 for a too
+//! small notional), so callers can handle local and remote rejections alike.
+
+use crate::error::{BinanceError, BinanceErrorCode, Result};
+use crate::types::common::{OrderSide, OrderType};
+use crate::types::decimal::{in_range, Decimal, RoundingMode};
+use crate::types::market::{SymbolFilter, SymbolInfo};
+use crate::types::trading::NewOrderRequest;
+
+#[derive(Debug, Clone, Copy)]
+struct Range {
+    min: Decimal,
+    max: Decimal,
+    step: Decimal,
+}
+
+/// Validates and rounds orders for one symbol
+#[derive(Debug, Clone)]
+pub struct OrderValidator {
+    symbol: String,
+    price: Option<Range>,
+    lot_size: Option<Range>,
+    market_lot_size: Option<Range>,
+    min_notional: Option<Decimal>,
+    percent_price: Option<(Decimal, Decimal)>,
+    max_num_orders: Option<u32>,
+    max_num_algo_orders: Option<u32>,
+}
+
+impl OrderValidator {
+    /// Build a validator from a symbol's `exchangeInfo` entry
+    pub fn new(symbol_info: &SymbolInfo) -> Self {
+        let mut validator = Self {
+            symbol: symbol_info.symbol.clone(),
+            price: None,
+            lot_size: None,
+            market_lot_size: None,
+            min_notional: None,
+            percent_price: None,
+            max_num_orders: None,
+            max_num_algo_orders: None,
+        };
+
+        for filter in &symbol_info.filters {
+            match filter {
+                SymbolFilter::PriceFilter { min_price, max_price, tick_size } => {
+                    validator.price = Some(range(min_price, max_price, tick_size));
+                }
+                SymbolFilter::LotSize { min_qty, max_qty, step_size } => {
+                    validator.lot_size = Some(range(min_qty, max_qty, step_size));
+                }
+                SymbolFilter::MarketLotSize { min_qty, max_qty, step_size } => {
+                    validator.market_lot_size = Some(range(min_qty, max_qty, step_size));
+                }
+                SymbolFilter::MinNotional { notional } => {
+                    validator.min_notional = Some(*notional);
+                }
+                SymbolFilter::PercentPrice { multiplier_up, multiplier_down } => {
+                    validator.percent_price = Some((*multiplier_up, *multiplier_down));
+                }
+                SymbolFilter::MaxNumOrders { limit } => validator.max_num_orders = Some(*limit),
+                SymbolFilter::MaxNumAlgoOrders { limit } => validator.max_num_algo_orders = Some(*limit),
+                SymbolFilter::Unknown => {}
+            }
+        }
+
+        validator
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Check an order against the price, quantity and notional filters.
+    ///
+    /// `PERCENT_PRICE` and the notional of orders without a price are only
+    /// checked when `mark_price` is given. As on the exchange, `PERCENT_PRICE`
+    /// caps BUY prices at `mark * multiplierUp` and floors SELL prices at
+    /// `mark * multiplierDown`.
+    pub fn validate(&self, order: &NewOrderRequest, mark_price: Option<&Decimal>) -> Result<()> {
+        if order.symbol != self.symbol {
+            return Err(BinanceError::InvalidParameter(format!(
+                "Order symbol {} does not match validator symbol {}",
+                order.symbol, self.symbol
+            )));
+        }
+
+        let mark_price = mark_price.copied();
+        let price = order.price;
+
+        if let Some(price) = price {
+            self.check_price("price", price)?;
+
+            if let (Some((up, down)), Some(mark)) = (self.percent_price, mark_price) {
+                match order.side {
+                    OrderSide::Buy => {
+                        let upper = in_range(mark.checked_mul(up))?;
+                        if price > upper {
+                            return Err(rejected(
+                                BinanceErrorCode::PriceHigherThanMultiplierUp,
+                                format!("Price {} is higher than {} (mark price * multiplierUp)", price, upper),
+                            ));
+                        }
+                    }
+                    OrderSide::Sell => {
+                        let lower = in_range(mark.checked_mul(down))?;
+                        if price < lower {
+                            return Err(rejected(
+                                BinanceErrorCode::PriceLowerThanMultiplierDown,
+                                format!("Price {} is lower than {} (mark price * multiplierDown)", price, lower),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(stop_price) = order.stop_price {
+            self.check_price("stopPrice", stop_price)?;
+        }
+        if let Some(activation_price) = order.activation_price {
+            self.check_price("activationPrice", activation_price)?;
+        }
+
+        let Some(quantity) = order.quantity else {
+            return Ok(());
+        };
+        self.check_quantity(quantity, self.quantity_range(order.order_type))?;
+
+        // Reduce-only orders are exempt from the minimum notional
+        if order.reduce_only != Some(true) {
+            if let (Some(min_notional), Some(reference)) = (self.min_notional, price.or(mark_price)) {
+                let notional = in_range(reference.checked_mul(quantity))?;
+                if notional < min_notional {
+                    return Err(rejected(
+                        BinanceErrorCode::MinNotional,
+                        format!("Order's notional {} must be no smaller than {}", notional, min_notional),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check the number of open orders before placing another one
+    pub fn check_open_orders(&self, order_type: OrderType, open_orders: u32) -> Result<()> {
+        let limit = if is_algo(order_type) {
+            self.max_num_algo_orders
+        } else {
+            self.max_num_orders
+        };
+        match limit {
+            Some(limit) if open_orders >= limit => Err(rejected(
+                BinanceErrorCode::MaxOpenOrderExceeded,
+                format!("Reach max open order limit ({})", limit),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Round a price onto the tick grid
+    pub fn round_price(&self, price: &Decimal, mode: RoundingMode) -> Result<Decimal> {
+        Ok(match self.price {
+            Some(range) => in_range(price.round_to_step(range.min, range.step, mode))?,
+            None => *price,
+        })
+    }
+
+    /// Round a quantity down onto the step grid of the filter used for `order_type`
+    pub fn round_quantity(&self, quantity: &Decimal, order_type: OrderType) -> Result<Decimal> {
+        Ok(match self.quantity_range(order_type) {
+            Some(range) => in_range(quantity.round_to_step(range.min, range.step, RoundingMode::Down))?,
+            None => *quantity,
+        })
+    }
+
+    /// Smallest quantity accepted for `order_type`, if the symbol has a lot size filter
+    pub fn min_quantity(&self, order_type: OrderType) -> Option<Decimal> {
+        self.quantity_range(order_type).map(|range| range.min)
+    }
+
+    /// Largest quantity accepted for `order_type`, if the symbol's lot size filter sets one
+    pub fn max_quantity(&self, order_type: OrderType) -> Option<Decimal> {
+        self.quantity_range(order_type)
+            .filter(|range| !range.max.is_zero())
+            .map(|range| range.max)
+    }
+
+    /// Round all prices to the nearest tick and the quantity down to the step size
+    pub fn round_order(&self, mut order: NewOrderRequest) -> Result<NewOrderRequest> {
+        for price in [&mut order.price, &mut order.stop_price, &mut order.activation_price]
+            .into_iter()
+            .flatten()
+        {
+            *price = self.round_price(price, RoundingMode::Nearest)?;
+        }
+        if let Some(quantity) = &order.quantity {
+            order.quantity = Some(self.round_quantity(quantity, order.order_type)?);
+        }
+        Ok(order)
+    }
+
+    fn quantity_range(&self, order_type: OrderType) -> Option<Range> {
+        match order_type {
+            OrderType::Market => self.market_lot_size.or(self.lot_size),
+            _ => self.lot_size,
+        }
+    }
+
+    fn check_price(&self, name: &str, price: Decimal) -> Result<()> {
+        if price.is_negative() || price.is_zero() {
+            return Err(rejected(
+                BinanceErrorCode::PriceLessThanZero,
+                format!("{} {} must be greater than zero", name, price),
+            ));
+        }
+        let Some(range) = self.price else {
+            return Ok(());
+        };
+
+        if !range.min.is_zero() && price < range.min {
+            return Err(rejected(
+                BinanceErrorCode::PriceLessThanMinPrice,
+                format!("{} {} is less than min price {}", name, price, range.min),
+            ));
+        }
+        if !range.max.is_zero() && price > range.max {
+            return Err(rejected(
+                BinanceErrorCode::PriceGreaterThanMaxPrice,
+                format!("{} {} is greater than max price {}", name, price, range.max),
+            ));
+        }
+        if !in_range(price.is_step_of(range.min, range.step))? {
+            return Err(rejected(
+                BinanceErrorCode::PriceNotIncreasedByTickSize,
+                format!("{} {} is not a multiple of tick size {}", name, price, range.step),
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_quantity(&self, quantity: Decimal, range: Option<Range>) -> Result<()> {
+        if quantity.is_negative() || quantity.is_zero() {
+            return Err(rejected(
+                BinanceErrorCode::QtyLessThanZero,
+                format!("Quantity {} must be greater than zero", quantity),
+            ));
+        }
+        let Some(range) = range else {
+            return Ok(());
+        };
+
+        if quantity < range.min {
+            return Err(rejected(
+                BinanceErrorCode::QtyLessThanMinQty,
+                format!("Quantity {} is less than min qty {}", quantity, range.min),
+            ));
+        }
+        if !range.max.is_zero() && quantity > range.max {
+            return Err(rejected(
+                BinanceErrorCode::QtyGreaterThanMaxQty,
+                format!("Quantity {} is greater than max qty {}", quantity, range.max),
+            ));
+        }
+        if !in_range(quantity.is_step_of(range.min, range.step))? {
+            return Err(rejected(
+                BinanceErrorCode::QtyNotIncreasedByStepSize,
+                format!("Quantity {} is not a multiple of step size {}", quantity, range.step),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl SymbolInfo {
+    /// Build an [`OrderValidator`] from this symbol's filters
+    pub fn validator(&self) -> OrderValidator {
+        OrderValidator::new(self)
+    }
+}
+
+fn is_algo(order_type: OrderType) -> bool {
+    !matches!(order_type, OrderType::Limit | OrderType::Market)
+}
+
+fn range(min: &Decimal, max: &Decimal, step: &Decimal) -> Range {
+    Range {
+        min: *min,
+        max: *max,
+        step: *step,
+    }
+}
+
+fn rejected(code: BinanceErrorCode, msg: String) -> BinanceError {
+    BinanceError::api(code.code(), msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order_builder::LimitTimeInForce;
+
+    fn btcusdt() -> SymbolInfo {
+        serde_json::from_str(
+            r#"{
+                "symbol": "BTCUSDT",
+                "status": "TRADING",
+                "baseAsset": "BTC",
+                "quoteAsset": "USDT",
+                "marginAsset": "USDT",
+                "pricePrecision": 2,
+                "quantityPrecision": 3,
+                "baseAssetPrecision": 8,
+                "quotePrecision": 8,
+                "filters": [
+                    {"minPrice":"556.80","maxPrice":"4529764","filterType":"PRICE_FILTER","tickSize":"0.10"},
+                    {"stepSize":"0.001","filterType":"LOT_SIZE","maxQty":"1000","minQty":"0.001"},
+                    {"stepSize":"0.001","filterType":"MARKET_LOT_SIZE","maxQty":"120","minQty":"0.001"},
+                    {"limit":200,"filterType":"MAX_NUM_ORDERS"},
+                    {"limit":10,"filterType":"MAX_NUM_ALGO_ORDERS"},
+                    {"notional":"100","filterType":"MIN_NOTIONAL"},
+                    {"multiplierDown":"0.9500","multiplierUp":"1.0500","multiplierDecimal":"4","filterType":"PERCENT_PRICE"}
+                ],
+                "orderTypes": ["LIMIT", "MARKET"],
+                "timeInForce": ["GTC", "IOC"]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn limit(price: &str, quantity: &str) -> NewOrderRequest {
+        limit_on(OrderSide::Buy, price, quantity)
+    }
+
+    fn limit_on(side: OrderSide, price: &str, quantity: &str) -> NewOrderRequest {
+        let (quantity, price) = (quantity.parse().unwrap(), price.parse().unwrap());
+        NewOrderRequest::limit("BTCUSDT".to_string(), side, quantity, price, LimitTimeInForce::Gtc).build()
+    }
+
+    fn code(result: Result<()>) -> Option<i32> {
+        result.err().and_then(|e| e.code()).map(|c| c.code())
+    }
+
+    #[test]
+    fn test_validate_filters() {
+        let validator = btcusdt().validator();
+
+        assert!(validator.validate(&limit("50000.1", "0.002"), None).is_ok());
+        assert_eq!(code(validator.validate(&limit("50000.15", "0.002"), None)), Some(-4014));
+        assert_eq!(code(validator.validate(&limit("500", "0.002"), None)), Some(-4013));
+        assert_eq!(code(validator.validate(&limit("50000", "0.0015"), None)), Some(-4023));
+        assert_eq!(code(validator.validate(&limit("50000", "2000"), None)), Some(-4005));
+        assert_eq!(code(validator.validate(&limit("50000", "0.001"), None)), Some(-4164));
+        // Reduce-only orders skip the notional check
+        let mut reduce_only = limit("50000", "0.001");
+        reduce_only.reduce_only = Some(true);
+        assert!(validator.validate(&reduce_only, None).is_ok());
+
+        let mark: Decimal = "45000".parse().unwrap();
+        assert_eq!(code(validator.validate(&limit("50000", "0.002"), Some(&mark))), Some(-4016));
+
+        let market = NewOrderRequest::market("BTCUSDT".to_string(), OrderSide::Sell, "150".parse().unwrap()).build();
+        assert_eq!(code(validator.validate(&market, None)), Some(-4005));
+
+        let err = validator.validate(&limit("50000.15", "0.002"), None).unwrap_err();
+        assert!(err.is_order_rejected());
+    }
+
+    #[test]
+    fn test_percent_price_bounds_each_side() {
+        let validator = btcusdt().validator();
+        let mark: Decimal = "50000".parse().unwrap();
+
+        // BUY is capped at mark * 1.05 but may bid far below the mark
+        assert!(validator.validate(&limit_on(OrderSide::Buy, "52500", "0.002"), Some(&mark)).is_ok());
+        assert_eq!(code(validator.validate(&limit_on(OrderSide::Buy, "52500.1", "0.002"), Some(&mark))), Some(-4016));
+        assert!(validator.validate(&limit_on(OrderSide::Buy, "40000", "0.003"), Some(&mark)).is_ok());
+
+        // SELL is floored at mark * 0.95 but may offer far above the mark
+        assert!(validator.validate(&limit_on(OrderSide::Sell, "47500", "0.003"), Some(&mark)).is_ok());
+        assert_eq!(code(validator.validate(&limit_on(OrderSide::Sell, "47499.9", "0.003"), Some(&mark))), Some(-4024));
+        assert!(validator.validate(&limit_on(OrderSide::Sell, "60000", "0.002"), Some(&mark)).is_ok());
+    }
+
+    #[test]
+    fn test_round_order() {
+        let validator = btcusdt().validator();
+        let order = validator.round_order(limit("50000.16", "0.0029")).unwrap();
+        assert_eq!(order.price.unwrap().to_string(), "50000.2");
+        assert_eq!(order.quantity.unwrap().to_string(), "0.002");
+        assert_eq!(validator.min_quantity(OrderType::Market).unwrap().to_string(), "0.001");
+
+        let price: Decimal = "50000.19".parse().unwrap();
+        assert_eq!(
+            validator.round_price(&price, RoundingMode::Down).unwrap().to_string(),
+            "50000.1"
+        );
+    }
+
+    #[test]
+    fn test_check_open_orders() {
+        let validator = btcusdt().validator();
+        assert!(validator.check_open_orders(OrderType::Limit, 199).is_ok());
+        assert_eq!(code(validator.check_open_orders(OrderType::Limit, 200)), Some(-2025));
+        assert_eq!(code(validator.check_open_orders(OrderType::StopMarket, 10)), Some(-2025));
+    }
+}
@@ -7,6 +7,6 @@ pub mod stream;
 pub mod types;
 pub mod user_data;
 
-pub use stream::{StreamBuilder, WebSocket, WebSocketClient};
+pub use stream::{StreamBuilder, WebSocket, WebSocketClient, WebSocketClientBuilder};
 pub use types::*;
 pub use user_data::{UserDataStream, UserDataStreamConfig, UserDataStreamManager};
@@ -1,18 +1,33 @@
 use crate::error::{BinanceError, Result};
 use crate::websocket::types::*;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use futures_util::{SinkExt, StreamExt};
 use serde_json::Value;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
-use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::header::{HeaderValue, USER_AGENT};
+use tokio_tungstenite::{
+    client_async_tls_with_config, connect_async_with_config, tungstenite::Message, MaybeTlsStream,
+    WebSocketStream,
+};
+use url::Url;
 
-const WS_BASE_URL: &str = "wss://fstream.binance.com/ws/";
-const WS_TESTNET_URL: &str = "wss://stream.binancefuture.com/ws/";
+pub const WS_BASE_URL: &str = "wss://fstream.binance.com/ws/";
+pub const WS_TESTNET_URL: &str = "wss://stream.binancefuture.com/ws/";
 
 pub type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
 /// WebSocket client for Binance Futures streams
+#[derive(Debug, Clone)]
 pub struct WebSocketClient {
     base_url: String,
+    proxy: Option<Url>,
+    connect_timeout: Option<Duration>,
+    tcp_nodelay: bool,
+    user_agent: Option<String>,
 }
 
 impl WebSocketClient {
@@ -20,6 +35,10 @@ impl WebSocketClient {
     pub fn new() -> Self {
         Self {
             base_url: WS_BASE_URL.to_string(),
+            proxy: None,
+            connect_timeout: None,
+            tcp_nodelay: false,
+            user_agent: None,
         }
     }
 
@@ -27,23 +46,69 @@ impl WebSocketClient {
     pub fn testnet() -> Self {
         Self {
             base_url: WS_TESTNET_URL.to_string(),
+            ..Self::new()
         }
     }
 
+    /// Create a builder for a custom client configuration
+    pub fn builder() -> WebSocketClientBuilder {
+        WebSocketClientBuilder::new()
+    }
+
+    /// Get the base URL streams are opened on
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
     /// Connect to a single stream
     pub async fn connect_stream(&self, stream: &str) -> Result<WebSocket> {
         let url = format!("{}{}", self.base_url, stream);
-        let (ws_stream, _) = connect_async(&url).await
-            .map_err(|e| BinanceError::WebSocket(format!("Failed to connect: {}", e)))?;
-        Ok(ws_stream)
+        self.connect_url(&url, "Failed to connect").await
     }
 
     /// Connect to multiple streams
     pub async fn connect_combined_stream(&self, streams: &[String]) -> Result<WebSocket> {
         let combined_streams = streams.join("/");
         let url = format!("{}stream?streams={}", self.base_url, combined_streams);
-        let (ws_stream, _) = connect_async(&url).await
-            .map_err(|e| BinanceError::WebSocket(format!("Failed to connect: {}", e)))?;
+        self.connect_url(&url, "Failed to connect").await
+    }
+
+    /// Open a WebSocket connection, honouring the proxy, timeout and header settings
+    async fn connect_url(&self, url: &str, context: &str) -> Result<WebSocket> {
+        let connect = self.connect_url_inner(url);
+        let result = match self.connect_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, connect)
+                .await
+                .map_err(|_| BinanceError::Timeout)?,
+            None => connect.await,
+        };
+        result.map_err(|e| BinanceError::WebSocket(format!("{}: {}", context, e)))
+    }
+
+    async fn connect_url_inner(&self, url: &str) -> std::result::Result<WebSocket, String> {
+        let mut request = url.into_client_request().map_err(|e| e.to_string())?;
+        if let Some(user_agent) = &self.user_agent {
+            let value = HeaderValue::from_str(user_agent).map_err(|e| e.to_string())?;
+            request.headers_mut().insert(USER_AGENT, value);
+        }
+
+        let (ws_stream, _) = match &self.proxy {
+            None => connect_async_with_config(request, None, self.tcp_nodelay)
+                .await
+                .map_err(|e| e.to_string())?,
+            Some(proxy) => {
+                let uri = request.uri();
+                let host = uri.host().ok_or("URL has no host")?.to_string();
+                let default_port = if uri.scheme_str() == Some("wss") { 443 } else { 80 };
+                let port = uri.port_u16().unwrap_or(default_port);
+
+                let stream = connect_via_proxy(proxy, &host, port).await?;
+                stream.set_nodelay(self.tcp_nodelay).map_err(|e| e.to_string())?;
+                client_async_tls_with_config(request, stream, None, None)
+                    .await
+                    .map_err(|e| e.to_string())?
+            }
+        };
         Ok(ws_stream)
     }
 
@@ -78,7 +143,7 @@ impl WebSocketClient {
     /// Parse WebSocket message
     pub fn parse_message(msg: &str) -> Result<WebSocketMessage> {
         let value: Value = serde_json::from_str(msg)
-            .map_err(|e| BinanceError::Json(e))?;
+            .map_err(BinanceError::Json)?;
 
         // Handle combined stream format
         if let Some(stream_data) = value.get("stream") {
@@ -189,9 +254,7 @@ impl WebSocketClient {
     /// Subscribe to user data stream (requires listen key)
     pub async fn user_data_stream(&self, listen_key: &str) -> Result<WebSocket> {
         let url = format!("{}ws/{}", self.base_url, listen_key);
-        let (ws_stream, _) = connect_async(&url).await
-            .map_err(|e| BinanceError::WebSocket(format!("Failed to connect to user data stream: {}", e)))?;
-        Ok(ws_stream)
+        self.connect_url(&url, "Failed to connect to user data stream").await
     }
 }
 
@@ -201,6 +264,158 @@ impl Default for WebSocketClient {
     }
 }
 
+/// Open a TCP tunnel to `host:port` through an HTTP CONNECT or SOCKS5 proxy
+async fn connect_via_proxy(proxy: &Url, host: &str, port: u16) -> std::result::Result<TcpStream, String> {
+    let proxy_host = proxy.host_str().ok_or("Proxy URL has no host")?;
+    let proxy_port = proxy.port_or_known_default().unwrap_or(1080);
+
+    match proxy.scheme() {
+        "http" => {
+            let mut stream = TcpStream::connect((proxy_host, proxy_port))
+                .await
+                .map_err(|e| e.to_string())?;
+
+            let mut connect = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n");
+            if !proxy.username().is_empty() {
+                let credentials = format!("{}:{}", proxy.username(), proxy.password().unwrap_or(""));
+                connect.push_str(&format!("Proxy-Authorization: Basic {}\r\n", BASE64.encode(credentials)));
+            }
+            connect.push_str("\r\n");
+            stream.write_all(connect.as_bytes()).await.map_err(|e| e.to_string())?;
+
+            // Read the proxy's response headers byte by byte so no tunnelled data is consumed
+            let mut response = Vec::new();
+            while !response.ends_with(b"\r\n\r\n") {
+                let byte = stream.read_u8().await.map_err(|e| format!("Proxy CONNECT failed: {}", e))?;
+                if response.len() > 8192 {
+                    return Err("Proxy CONNECT response too large".to_string());
+                }
+                response.push(byte);
+            }
+
+            let status_line = String::from_utf8_lossy(&response);
+            let status_line = status_line.lines().next().unwrap_or_default();
+            if status_line.split_whitespace().nth(1) != Some("200") {
+                return Err(format!("Proxy CONNECT failed: {}", status_line));
+            }
+            Ok(stream)
+        }
+        "socks5" | "socks5h" => {
+            let stream = if proxy.username().is_empty() {
+                tokio_socks::tcp::Socks5Stream::connect((proxy_host, proxy_port), (host, port)).await
+            } else {
+                tokio_socks::tcp::Socks5Stream::connect_with_password(
+                    (proxy_host, proxy_port),
+                    (host, port),
+                    proxy.username(),
+                    proxy.password().unwrap_or(""),
+                )
+                .await
+            }
+            .map_err(|e| e.to_string())?;
+            Ok(stream.into_inner())
+        }
+        scheme => Err(format!("Unsupported proxy scheme: {}", scheme)),
+    }
+}
+
+/// Builder for [`WebSocketClient`]
+#[derive(Debug, Clone)]
+pub struct WebSocketClientBuilder {
+    base_url: String,
+    proxy: Option<String>,
+    connect_timeout: Option<Duration>,
+    tcp_nodelay: bool,
+    user_agent: Option<String>,
+}
+
+impl WebSocketClientBuilder {
+    pub fn new() -> Self {
+        Self {
+            base_url: WS_BASE_URL.to_string(),
+            proxy: None,
+            connect_timeout: None,
+            tcp_nodelay: false,
+            user_agent: None,
+        }
+    }
+
+    /// Open streams on an arbitrary base URL, e.g. a local mock server
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Use the testnet endpoint
+    pub fn testnet(self) -> Self {
+        self.base_url(WS_TESTNET_URL)
+    }
+
+    /// Connect through an HTTP CONNECT or SOCKS5 proxy,
+    /// e.g. `http://127.0.0.1:8080` or `socks5://127.0.0.1:1080`
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    /// Timeout for establishing a connection, including the handshake
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Enable or disable TCP_NODELAY
+    pub fn tcp_nodelay(mut self, enabled: bool) -> Self {
+        self.tcp_nodelay = enabled;
+        self
+    }
+
+    /// Send a custom `User-Agent` header with the handshake
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Build the client
+    pub fn build(self) -> Result<WebSocketClient> {
+        let mut base_url = self.base_url;
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+        Url::parse(&base_url)
+            .map_err(|e| BinanceError::InvalidParameter(format!("Invalid base URL {}: {}", base_url, e)))?;
+
+        let proxy = match self.proxy {
+            Some(proxy) => {
+                let url = Url::parse(&proxy)
+                    .map_err(|e| BinanceError::InvalidParameter(format!("Invalid proxy URL {}: {}", proxy, e)))?;
+                if !matches!(url.scheme(), "http" | "socks5" | "socks5h") {
+                    return Err(BinanceError::InvalidParameter(format!(
+                        "Unsupported proxy scheme: {}",
+                        url.scheme()
+                    )));
+                }
+                Some(url)
+            }
+            None => None,
+        };
+
+        Ok(WebSocketClient {
+            base_url,
+            proxy,
+            connect_timeout: self.connect_timeout,
+            tcp_nodelay: self.tcp_nodelay,
+            user_agent: self.user_agent,
+        })
+    }
+}
+
+impl Default for WebSocketClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// WebSocket stream builder for easy configuration
 pub struct StreamBuilder {
     client: WebSocketClient,
@@ -222,6 +437,14 @@ impl StreamBuilder {
         }
     }
 
+    /// Use a custom-configured WebSocket client
+    pub fn with_client(client: WebSocketClient) -> Self {
+        Self {
+            client,
+            streams: Vec::new(),
+        }
+    }
+
     /// Add depth stream
     pub fn depth(mut self, symbol: &str, levels: Option<u32>) -> Self {
         self.streams.push(WebSocketClient::depth_stream(symbol, levels));
@@ -309,6 +532,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_websocket_client_builder() {
+        let client = WebSocketClient::builder()
+            .base_url("ws://127.0.0.1:9001/ws")
+            .proxy("socks5h://127.0.0.1:1080")
+            .connect_timeout(std::time::Duration::from_secs(5))
+            .tcp_nodelay(true)
+            .user_agent("my-bot/1.0")
+            .build()
+            .unwrap();
+        assert_eq!(client.base_url(), "ws://127.0.0.1:9001/ws/");
+
+        assert!(WebSocketClient::builder().proxy("ftp://proxy").build().is_err());
+        assert!(WebSocketClient::builder().base_url("not a url").build().is_err());
+    }
+
     #[test]
     fn test_stream_builder() {
         let builder = StreamBuilder::new()
@@ -1,5 +1,6 @@
 use serde::Deserialize;
 use crate::types::common::{OrderSide, OrderStatus, OrderType, PositionSide, TimeInForce};
+use crate::types::Amount;
 
 /// WebSocket stream message wrapper
 #[derive(Debug, Clone, Deserialize)]
@@ -26,9 +27,9 @@ pub struct DepthUpdate {
     #[serde(rename = "pu")]
     pub previous_final_update_id: u64,
     #[serde(rename = "b")]
-    pub bids: Vec<[String; 2]>, // [price, quantity]
+    pub bids: Vec<[Amount; 2]>, // [price, quantity]
     #[serde(rename = "a")]
-    pub asks: Vec<[String; 2]>, // [price, quantity]
+    pub asks: Vec<[Amount; 2]>, // [price, quantity]
 }
 
 /// Trade stream
@@ -45,9 +46,9 @@ pub struct TradeStream {
     #[serde(rename = "t")]
     pub trade_id: u64,
     #[serde(rename = "p")]
-    pub price: String,
+    pub price: Amount,
     #[serde(rename = "q")]
-    pub quantity: String,
+    pub quantity: Amount,
     #[serde(rename = "X")]
     pub buyer_order_id: u64,
     #[serde(rename = "Y")]
@@ -84,25 +85,25 @@ pub struct KlineData {
     #[serde(rename = "L")]
     pub last_trade_id: u64,
     #[serde(rename = "o")]
-    pub open: String,
+    pub open: Amount,
     #[serde(rename = "c")]
-    pub close: String,
+    pub close: Amount,
     #[serde(rename = "h")]
-    pub high: String,
+    pub high: Amount,
     #[serde(rename = "l")]
-    pub low: String,
+    pub low: Amount,
     #[serde(rename = "v")]
-    pub volume: String,
+    pub volume: Amount,
     #[serde(rename = "n")]
     pub trade_count: u64,
     #[serde(rename = "x")]
     pub is_closed: bool,
     #[serde(rename = "q")]
-    pub quote_volume: String,
+    pub quote_volume: Amount,
     #[serde(rename = "V")]
-    pub taker_buy_volume: String,
+    pub taker_buy_volume: Amount,
     #[serde(rename = "Q")]
-    pub taker_buy_quote_volume: String,
+    pub taker_buy_quote_volume: Amount,
 }
 
 /// 24hr ticker stream
@@ -115,25 +116,25 @@ pub struct TickerStream {
     #[serde(rename = "s")]
     pub symbol: String,
     #[serde(rename = "p")]
-    pub price_change: String,
+    pub price_change: Amount,
     #[serde(rename = "P")]
-    pub price_change_percent: String,
+    pub price_change_percent: Amount,
     #[serde(rename = "w")]
-    pub weighted_avg_price: String,
+    pub weighted_avg_price: Amount,
     #[serde(rename = "c")]
-    pub last_price: String,
+    pub last_price: Amount,
     #[serde(rename = "Q")]
-    pub last_quantity: String,
+    pub last_quantity: Amount,
     #[serde(rename = "o")]
-    pub open_price: String,
+    pub open_price: Amount,
     #[serde(rename = "h")]
-    pub high_price: String,
+    pub high_price: Amount,
     #[serde(rename = "l")]
-    pub low_price: String,
+    pub low_price: Amount,
     #[serde(rename = "v")]
-    pub volume: String,
+    pub volume: Amount,
     #[serde(rename = "q")]
-    pub quote_volume: String,
+    pub quote_volume: Amount,
     #[serde(rename = "O")]
     pub open_time: u64,
     #[serde(rename = "C")]
@@ -174,11 +175,11 @@ pub struct BalanceUpdate {
     #[serde(rename = "a")]
     pub asset: String,
     #[serde(rename = "wb")]
-    pub wallet_balance: String,
+    pub wallet_balance: Amount,
     #[serde(rename = "cw")]
-    pub cross_wallet_balance: String,
+    pub cross_wallet_balance: Amount,
     #[serde(rename = "bc")]
-    pub balance_change: String,
+    pub balance_change: Amount,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -186,17 +187,17 @@ pub struct PositionUpdate {
     #[serde(rename = "s")]
     pub symbol: String,
     #[serde(rename = "pa")]
-    pub position_amount: String,
+    pub position_amount: Amount,
     #[serde(rename = "ep")]
-    pub entry_price: String,
+    pub entry_price: Amount,
     #[serde(rename = "cr")]
-    pub accumulated_realized: String,
+    pub accumulated_realized: Amount,
     #[serde(rename = "up")]
-    pub unrealized_pnl: String,
+    pub unrealized_pnl: Amount,
     #[serde(rename = "mt")]
     pub margin_type: String,
     #[serde(rename = "iw")]
-    pub isolated_wallet: String,
+    pub isolated_wallet: Amount,
     #[serde(rename = "ps")]
     pub position_side: PositionSide,
 }
@@ -227,13 +228,13 @@ pub struct OrderUpdateData {
     #[serde(rename = "f")]
     pub time_in_force: TimeInForce,
     #[serde(rename = "q")]
-    pub original_quantity: String,
+    pub original_quantity: Amount,
     #[serde(rename = "p")]
-    pub original_price: String,
+    pub original_price: Amount,
     #[serde(rename = "ap")]
-    pub average_price: String,
+    pub average_price: Amount,
     #[serde(rename = "sp")]
-    pub stop_price: String,
+    pub stop_price: Amount,
     #[serde(rename = "x")]
     pub execution_type: String,
     #[serde(rename = "X")]
@@ -241,13 +242,13 @@ pub struct OrderUpdateData {
     #[serde(rename = "i")]
     pub order_id: u64,
     #[serde(rename = "l")]
-    pub last_filled_quantity: String,
+    pub last_filled_quantity: Amount,
     #[serde(rename = "z")]
-    pub cumulative_filled_quantity: String,
+    pub cumulative_filled_quantity: Amount,
     #[serde(rename = "L")]
-    pub last_filled_price: String,
+    pub last_filled_price: Amount,
     #[serde(rename = "n")]
-    pub commission_amount: String,
+    pub commission_amount: Amount,
     #[serde(rename = "N")]
     pub commission_asset: Option<String>,
     #[serde(rename = "T")]
@@ -255,9 +256,9 @@ pub struct OrderUpdateData {
     #[serde(rename = "t")]
     pub trade_id: u64,
     #[serde(rename = "b")]
-    pub bids_notional: String,
+    pub bids_notional: Amount,
     #[serde(rename = "a")]
-    pub ask_notional: String,
+    pub ask_notional: Amount,
     #[serde(rename = "m")]
     pub is_maker: bool,
     #[serde(rename = "R")]
@@ -271,11 +272,11 @@ pub struct OrderUpdateData {
     #[serde(rename = "cp")]
     pub close_position: bool,
     #[serde(rename = "AP")]
-    pub activation_price: Option<String>,
+    pub activation_price: Option<Amount>,
     #[serde(rename = "cr")]
-    pub callback_rate: Option<String>,
+    pub callback_rate: Option<Amount>,
     #[serde(rename = "rp")]
-    pub realized_profit: String,
+    pub realized_profit: Amount,
 }
 
 /// WebSocket message types
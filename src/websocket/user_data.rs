@@ -1,4 +1,5 @@
 use crate::client::http::HttpClient;
+use crate::client::params::RequestParams;
 use crate::error::{BinanceError, Result};
 use serde::Deserialize;
 use std::time::{Duration, Instant};
@@ -46,7 +47,7 @@ impl UserDataStreamManager {
     /// Keep alive the current listen key
     pub async fn keepalive_listen_key(&mut self) -> Result<()> {
         if let Some(ref key) = self.listen_key {
-            let mut params = std::collections::HashMap::new();
+            let mut params = RequestParams::new();
             params.insert("listenKey".to_string(), key.clone());
             let _: serde_json::Value = self
                 .http_client
@@ -56,17 +57,14 @@ impl UserDataStreamManager {
             self.last_keepalive = Some(Instant::now());
             Ok(())
         } else {
-            Err(BinanceError::Api {
-                code: -1,
-                msg: "No listen key available".to_string(),
-            })
+            Err(BinanceError::api(-1, "No listen key available"))
         }
     }
 
     /// Close the current listen key
     pub async fn close_listen_key(&mut self) -> Result<()> {
         if let Some(ref key) = self.listen_key {
-            let mut params = std::collections::HashMap::new();
+            let mut params = RequestParams::new();
             params.insert("listenKey".to_string(), key.clone());
             let _: serde_json::Value = self
                 .http_client
@@ -77,10 +75,7 @@ impl UserDataStreamManager {
             self.last_keepalive = None;
             Ok(())
         } else {
-            Err(BinanceError::Api {
-                code: -1,
-                msg: "No listen key to close".to_string(),
-            })
+            Err(BinanceError::api(-1, "No listen key to close"))
         }
     }
 